@@ -0,0 +1,129 @@
+//! Sample computation of the streaming hot path: [`BaseDevice::calc_signal_nsamps`] called on consecutive
+//! chunks of a compiled sequence, the way the streamer workers fill the driver buffer.
+//!
+//! Throughput is reported in samples per streamed row (AO channels, or DO ports), so it can be compared against
+//! the real-time rate directly: 10 MS/s for the DO card, 1 MS/s times the number of channels for the AO card.
+//! The `ao_compile` group times the compilation of a channel with many short segments, reported in segments.
+//!
+//! Run with `cargo bench --bench streaming`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use nicompiler_backend::*;
+
+/// Streamer chunk length
+const CHUNK_DUR: f64 = 50e-3;
+/// Sequence length
+const SEQ_DUR: f64 = 1.0;
+/// Number of instructions in the compile benchmark
+const NUM_SEGMENTS: usize = 10_000;
+
+// The helpers take `impl BaseExperiment` so that the trait methods are called rather than
+// the python-facing methods of `Experiment` with the same names.
+
+/// Streams the whole sequence of device `name` in chunks of [`CHUNK_DUR`]
+fn stream_all(exp: &mut impl BaseExperiment, name: &str) -> f64 {
+    let samp_rate = exp.dev(name).samp_rate();
+    let chunk_len = (CHUNK_DUR * samp_rate).round() as usize;
+    let total_len = (SEQ_DUR * samp_rate).round() as usize;
+    let mut checksum = 0.0;
+    for start_pos in (0..total_len).step_by(chunk_len) {
+        let end_pos = usize::min(start_pos + chunk_len, total_len);
+        let chunk = exp.device_calc_signal_nsamps(name, start_pos, end_pos, end_pos - start_pos, true);
+        checksum += chunk[[0, 0]];
+    }
+    checksum
+}
+
+/// 10 MS/s DO card with 8 lines: short TTL pulses and pulse trains
+fn program_do(exp: &mut impl BaseExperiment) {
+    exp.add_do_device("Dev1", 1e7);
+    for line in 0..8 {
+        exp.add_do_channel("Dev1", 0, line, false);
+    }
+    for line in 0..4 {
+        let chan = format!("port0/line{}", line);
+        // 100 1us-long pulses every 10 ms
+        for i in 0..100 {
+            exp.high("Dev1", &chan, i as f64 * 10e-3 + line as f64 * 1e-6, 1e-6);
+        }
+    }
+    for line in 4..8 {
+        let chan = format!("port0/line{}", line);
+        exp.pulse_train("Dev1", &chan, 0.0, 1e-6 * (line + 1) as f64, 0.5, Some(SEQ_DUR), None, None);
+    }
+    exp.compile(Some(SEQ_DUR));
+}
+
+/// 1 MS/s AO card with `nchans` channels playing a mix of analytic waveforms
+fn program_ao(exp: &mut impl BaseExperiment, nchans: usize) {
+    exp.add_ao_device("Dev2", 1e6);
+    for id in 0..nchans {
+        let chan = format!("ao{}", id);
+        exp.add_ao_channel("Dev2", id, 0.0);
+        match id % 4 {
+            0 => exp.sine("Dev2", &chan, 0.0, SEQ_DUR, false, 1e3 * (id + 1) as f64, Some(1.0), None, None, None),
+            1 => {
+                exp.linramp("Dev2", &chan, 0.0, 0.5, -1.0, 1.0, true);
+                exp.sine("Dev2", &chan, 0.5, 0.5, false, 10e3, Some(0.1), None, Some(1.0), None);
+            }
+            2 => exp.expramp("Dev2", &chan, 0.0, SEQ_DUR, 0.1, 5.0, false),
+            _ => exp.noise("Dev2", &chan, 0.0, SEQ_DUR, false, 0.1, id as u64, false, Some(50e3), None),
+        }
+    }
+    exp.compile(Some(SEQ_DUR));
+}
+
+/// 1 MS/s AO channel with [`NUM_SEGMENTS`] back-to-back 10 us instructions, none of which can be merged
+fn program_segments(exp: &mut impl BaseExperiment) {
+    exp.add_ao_device("Dev3", 1e6);
+    exp.add_ao_channel("Dev3", 0, 0.0);
+    for i in 0..NUM_SEGMENTS {
+        let t = i as f64 * 10e-6;
+        if i % 2 == 0 {
+            exp.sine("Dev3", "ao0", t, 10e-6, false, 1e3 + i as f64, None, None, None, None);
+        } else {
+            exp.linramp("Dev3", "ao0", t, 10e-6, 0.0, i as f64 * 1e-4, false);
+        }
+    }
+}
+
+fn compile(exp: &mut impl BaseExperiment) -> f64 {
+    exp.compile(None)
+}
+
+fn do_streaming(c: &mut Criterion) {
+    let mut exp = Experiment::new();
+    program_do(&mut exp);
+    let mut group = c.benchmark_group("do_streaming");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements((SEQ_DUR * 1e7) as u64));
+    group.bench_function("10MSps_8lines", |b| b.iter(|| stream_all(&mut exp, "Dev1")));
+    group.finish();
+}
+
+fn ao_streaming(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ao_streaming");
+    group.sample_size(10);
+    for nchans in [1, 4, 8] {
+        let mut exp = Experiment::new();
+        program_ao(&mut exp, nchans);
+        group.throughput(Throughput::Elements((SEQ_DUR * 1e6) as u64 * nchans as u64));
+        group.bench_with_input(BenchmarkId::new("1MSps", nchans), &nchans, |b, _| {
+            b.iter(|| stream_all(&mut exp, "Dev2"))
+        });
+    }
+    group.finish();
+}
+
+fn ao_compile(c: &mut Criterion) {
+    let mut exp = Experiment::new();
+    program_segments(&mut exp);
+    let mut group = c.benchmark_group("ao_compile");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(NUM_SEGMENTS as u64));
+    group.bench_function("10k_segments", |b| b.iter(|| compile(&mut exp)));
+    group.finish();
+}
+
+criterion_group!(benches, do_streaming, ao_streaming, ao_compile);
+criterion_main!(benches);
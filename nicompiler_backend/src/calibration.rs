@@ -0,0 +1,351 @@
+//! Calibration curves for analogue outputs.
+//!
+//! Instructions on an [`AoChannel`](crate::channel::AoChannel) can be written in the physical unit of the
+//! controlled quantity (e.g. MHz for an AOM driver, mW for a laser, A for a coil driver) once the channel
+//! has a [`Calibration`], see [`AoChannel::set_calibration`](crate::channel::AoChannel::set_calibration).
+//! Waveforms are evaluated in these units and the samples are mapped to volts afterwards in
+//! [`BaseChannel::fill_signal_nsamps`](crate::channel::BaseChannel::fill_signal_nsamps), so analytic
+//! instruction parameters (ramp end points, sine amplitudes, ...) keep their physical meaning.
+//!
+//! Supported curves:
+//! - [`Calibration::Linear`]: `volts = gain * value + offset`
+//! - [`Calibration::Poly`]: `volts = c0 + c1 * value + c2 * value^2 + ...`
+//! - [`Calibration::Table`]: linear interpolation between measured points, e.g. loaded with [`Calibration::from_csv`].
+//!   Values outside of the measured range are rejected rather than extrapolated.
+//!
+//! ```
+//! # use nicompiler_backend::calibration::*;
+//! let table = Calibration::new_table(vec![80., 100., 120.], vec![0.5, 1.5, 4.5]).unwrap();
+//! assert_eq!(table.apply(110.), Ok(3.0));
+//! assert!(table.apply(130.).is_err());
+//! ```
+
+use ndarray::ArrayViewMut1;
+use std::fmt;
+
+/// Curve mapping channel values in physical units to output volts. See the [module docs](self).
+///
+/// With the `serde` feature, deserialized curves go through the same checks as the constructors.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "serialize::CalibrationRecord")
+)]
+pub enum Calibration {
+    Linear { gain: f64, offset: f64 },
+    /// Coefficients in ascending order of powers
+    Poly { coeffs: Vec<f64> },
+    /// Strictly increasing `input` values and the corresponding `output` volts
+    Table { input: Vec<f64>, output: Vec<f64> },
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Calibration::Linear { gain, offset } => write!(f, "linear(gain={}, offset={})", gain, offset),
+            Calibration::Poly { coeffs } => write!(f, "poly({:?})", coeffs),
+            Calibration::Table { input, .. } => write!(
+                f,
+                "table({} points, {}..{})",
+                input.len(),
+                input[0],
+                input[input.len() - 1]
+            ),
+        }
+    }
+}
+
+impl Calibration {
+    /// Linear calibration `volts = gain * value + offset`.
+    ///
+    /// Returns an error if `gain` is zero or any argument is not finite.
+    pub fn new_linear(gain: f64, offset: f64) -> Result<Self, String> {
+        let calibration = Calibration::Linear { gain, offset };
+        calibration.validate()?;
+        Ok(calibration)
+    }
+
+    /// Polynomial calibration `volts = coeffs[0] + coeffs[1] * value + coeffs[2] * value^2 + ...`.
+    ///
+    /// Note the ascending order of powers, which is the reverse of the `numpy.polyfit` output.
+    ///
+    /// Returns an error if `coeffs` is empty or contains non-finite values.
+    pub fn new_poly(coeffs: Vec<f64>) -> Result<Self, String> {
+        let calibration = Calibration::Poly { coeffs };
+        calibration.validate()?;
+        Ok(calibration)
+    }
+
+    /// Lookup-table calibration, linearly interpolating between the points `(input[i], output[i])`.
+    ///
+    /// Returns an error unless there are at least two points, all values are finite
+    /// and `input` is strictly increasing.
+    pub fn new_table(input: Vec<f64>, output: Vec<f64>) -> Result<Self, String> {
+        let calibration = Calibration::Table { input, output };
+        calibration.validate()?;
+        Ok(calibration)
+    }
+
+    /// Checks the curve parameters, see the constructors for the requirements.
+    fn validate(&self) -> Result<(), String> {
+        let (input, output) = match self {
+            Calibration::Linear { gain, offset } => {
+                if gain.is_finite() && offset.is_finite() && *gain != 0.0 {
+                    return Ok(());
+                }
+                return Err(format!(
+                    "Linear calibration requires a finite non-zero gain and a finite offset, got gain={} and offset={}",
+                    gain, offset
+                ));
+            }
+            Calibration::Poly { coeffs } => {
+                if !coeffs.is_empty() && coeffs.iter().all(|c| c.is_finite()) {
+                    return Ok(());
+                }
+                return Err(format!(
+                    "Polynomial calibration requires at least one coefficient, all finite, got {:?}",
+                    coeffs
+                ));
+            }
+            Calibration::Table { input, output } => (input, output),
+        };
+        if input.len() != output.len() {
+            return Err(format!(
+                "Calibration table has {} input values but {} output values",
+                input.len(),
+                output.len()
+            ));
+        }
+        if input.len() < 2 {
+            return Err(format!("Calibration table requires at least 2 points, got {}", input.len()));
+        }
+        if let Some(val) = input.iter().chain(output.iter()).find(|val| !val.is_finite()) {
+            return Err(format!("Calibration table contains the non-finite value {}", val));
+        }
+        if let Some(i) = (1..input.len()).find(|&i| input[i] <= input[i - 1]) {
+            return Err(format!(
+                "Calibration table input must be strictly increasing, got {} followed by {}",
+                input[i - 1],
+                input[i]
+            ));
+        }
+        Ok(())
+    }
+
+    /// Loads a lookup-table calibration from a CSV file with two columns: the value in physical units
+    /// and the corresponding output in volts.
+    ///
+    /// Empty lines and lines starting with `#` are skipped, as is a header line which doesn't parse as numbers.
+    /// Rows may come in any order, they are sorted by input value. See [`Calibration::new_table`] for
+    /// the requirements on the points.
+    pub fn from_csv(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read calibration file {}: {}", path, err))?;
+
+        let mut points: Vec<(f64, f64)> = Vec::new();
+        let mut first_row = true;
+        for (line_idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            let parsed: Result<Vec<f64>, _> = fields.iter().map(|field| field.parse::<f64>()).collect();
+            match parsed {
+                Ok(vals) if vals.len() == 2 => points.push((vals[0], vals[1])),
+                Ok(vals) => {
+                    return Err(format!(
+                        "Calibration file {} line {}: expected 2 columns, got {}",
+                        path,
+                        line_idx + 1,
+                        vals.len()
+                    ))
+                }
+                // Header
+                Err(_) if first_row => {}
+                Err(err) => {
+                    return Err(format!("Calibration file {} line {}: {}", path, line_idx + 1, err))
+                }
+            }
+            first_row = false;
+        }
+
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let (input, output) = points.into_iter().unzip();
+        Calibration::new_table(input, output).map_err(|msg| format!("Calibration file {}: {}", path, msg))
+    }
+
+    /// Range of values the calibration is defined for, `(-inf, inf)` except for lookup tables.
+    pub fn input_range(&self) -> (f64, f64) {
+        match self {
+            Calibration::Table { input, .. } => (input[0], input[input.len() - 1]),
+            _ => (f64::NEG_INFINITY, f64::INFINITY),
+        }
+    }
+
+    /// Maps a value in physical units to volts.
+    /// Returns an error if `val` is outside of [`Calibration::input_range`].
+    pub fn apply(&self, val: f64) -> Result<f64, String> {
+        match self {
+            Calibration::Linear { gain, offset } => Ok(gain * val + offset),
+            Calibration::Poly { coeffs } => Ok(coeffs.iter().rev().fold(0.0, |acc, c| acc * val + c)),
+            Calibration::Table { input, output } => {
+                let (min, max) = self.input_range();
+                // Allow for rounding errors of samples right at the edge, also rejects NaN
+                let tol = (max - min) * 1e-9;
+                if !(min - tol <= val && val <= max + tol) {
+                    return Err(format!(
+                        "Calibration input {} is outside of the calibrated range [{}, {}]",
+                        val, min, max
+                    ));
+                }
+                let val = val.clamp(min, max);
+                // First point above `val`, clamped so that `val == max` uses the last interval
+                let i = input.partition_point(|&x| x <= val).clamp(1, input.len() - 1);
+                let frac = (val - input[i - 1]) / (input[i] - input[i - 1]);
+                Ok(output[i - 1] + frac * (output[i] - output[i - 1]))
+            }
+        }
+    }
+
+    /// Maps every element of `arr` to volts in place, see [`Calibration::apply`].
+    /// Stops at the first value outside of the input range and returns its error.
+    pub fn apply_inplace(&self, arr: &mut ArrayViewMut1<f64>) -> Result<(), String> {
+        for val in arr.iter_mut() {
+            *val = self.apply(*val)?;
+        }
+        Ok(())
+    }
+
+    /// Bounds of the output volts for inputs within `[min, max]`, computed without sampling.
+    ///
+    /// Exact for linear calibrations and lookup tables (`[min, max]` is clipped to the table range).
+    /// Polynomials are left unbounded for non-degenerate intervals, their extrema are not searched for.
+    pub fn map_bounds(&self, (min, max): (f64, f64)) -> (f64, f64) {
+        let unbounded = (f64::NEG_INFINITY, f64::INFINITY);
+        let min_max = |a: f64, b: f64| (a.min(b), a.max(b));
+        match self {
+            Calibration::Linear { gain, offset } => min_max(gain * min + offset, gain * max + offset),
+            Calibration::Poly { .. } if min == max && min.is_finite() => {
+                let val = self.apply(min).unwrap();
+                (val, val)
+            }
+            Calibration::Poly { .. } => unbounded,
+            Calibration::Table { input, output } => {
+                if !(min.is_finite() && max.is_finite()) {
+                    return unbounded;
+                }
+                let (in_min, in_max) = self.input_range();
+                let (min, max) = (min.clamp(in_min, in_max), max.clamp(in_min, in_max));
+                let (mut lo, mut hi) = min_max(self.apply(min).unwrap(), self.apply(max).unwrap());
+                for (x, y) in input.iter().zip(output.iter()) {
+                    if min < *x && *x < max {
+                        lo = lo.min(*y);
+                        hi = hi.max(*y);
+                    }
+                }
+                (lo, hi)
+            }
+        }
+    }
+}
+
+/// Serde support, enabled by the `serde` cargo feature.
+///
+/// Curves are deserialized into `CalibrationRecord` first and checked with [`Calibration::validate`],
+/// so e.g. an empty lookup table is rejected instead of panicking later on.
+#[cfg(feature = "serde")]
+mod serialize {
+    use super::Calibration;
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename = "Calibration")]
+    pub enum CalibrationRecord {
+        Linear { gain: f64, offset: f64 },
+        Poly { coeffs: Vec<f64> },
+        Table { input: Vec<f64>, output: Vec<f64> },
+    }
+
+    impl TryFrom<CalibrationRecord> for Calibration {
+        type Error = String;
+
+        fn try_from(record: CalibrationRecord) -> Result<Self, Self::Error> {
+            let calibration = match record {
+                CalibrationRecord::Linear { gain, offset } => Calibration::Linear { gain, offset },
+                CalibrationRecord::Poly { coeffs } => Calibration::Poly { coeffs },
+                CalibrationRecord::Table { input, output } => Calibration::Table { input, output },
+            };
+            calibration.validate()?;
+            Ok(calibration)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calibration::*;
+
+    #[test]
+    fn curves() {
+        let linear = Calibration::new_linear(0.5, 1.0).unwrap();
+        assert_eq!(linear.apply(4.0), Ok(3.0));
+        assert_eq!(linear.map_bounds((-2.0, 4.0)), (0.0, 3.0));
+        assert_eq!(Calibration::new_linear(-1.0, 0.0).unwrap().map_bounds((1.0, 2.0)), (-2.0, -1.0));
+
+        // 1 + 2x + 3x^2
+        let poly = Calibration::new_poly(vec![1.0, 2.0, 3.0]).unwrap();
+        assert_eq!(poly.apply(2.0), Ok(17.0));
+        assert_eq!(poly.map_bounds((2.0, 2.0)), (17.0, 17.0));
+        assert_eq!(poly.map_bounds((0.0, 2.0)), (f64::NEG_INFINITY, f64::INFINITY));
+
+        assert!(Calibration::new_linear(0.0, 1.0).is_err());
+        assert!(Calibration::new_linear(1.0, f64::NAN).is_err());
+        assert!(Calibration::new_poly(vec![]).is_err());
+    }
+
+    #[test]
+    fn table() {
+        assert!(Calibration::new_table(vec![0.0, 1.0], vec![0.0]).is_err());
+        assert!(Calibration::new_table(vec![0.0, 0.0], vec![0.0, 1.0]).is_err());
+
+        let table = Calibration::new_table(vec![0.0, 1.0, 2.0], vec![0.0, 4.0, 2.0]).unwrap();
+        assert_eq!(table.apply(0.25), Ok(1.0));
+        assert_eq!(table.apply(2.0), Ok(2.0));
+        assert!(table.apply(-0.1).is_err());
+        assert!(table.apply(f64::NAN).is_err());
+        // The peak between the end points is included
+        assert_eq!(table.map_bounds((0.5, 1.5)), (2.0, 4.0));
+
+        let mut arr = ndarray::Array1::from_vec(vec![0.5, 1.5, 2.5]);
+        let err = table.apply_inplace(&mut arr.view_mut()).unwrap_err();
+        assert!(err.contains("2.5 is outside of the calibrated range [0, 2]"));
+    }
+
+    #[test]
+    fn from_csv() {
+        let path = std::env::temp_dir().join(format!("nicompiler_calibration_{}.csv", std::process::id()));
+        std::fs::write(&path, "freq_mhz, volts\n# measured 2023-10-01\n120, 4.5\n80, 0.5\n\n100, 1.5\n").unwrap();
+        let table = Calibration::from_csv(path.to_str().unwrap());
+        std::fs::write(&path, "80, 0.5\n100, 1.5, 2\n").unwrap();
+        let bad_row = Calibration::from_csv(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(table.unwrap(), Calibration::new_table(vec![80.0, 100.0, 120.0], vec![0.5, 1.5, 4.5]).unwrap());
+        assert!(bad_row.unwrap_err().contains("line 2: expected 2 columns, got 3"));
+        assert!(Calibration::from_csv("/nonexistent/calibration.csv").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_validation() {
+        let table = Calibration::new_table(vec![0.0, 1.0], vec![0.0, 2.0]).unwrap();
+        let json = serde_json::to_string(&table).unwrap();
+        assert_eq!(serde_json::from_str::<Calibration>(&json).unwrap(), table);
+
+        let err = serde_json::from_str::<Calibration>(r#"{"Table":{"input":[],"output":[]}}"#).unwrap_err();
+        assert!(err.to_string().contains("requires at least 2 points"));
+        assert!(serde_json::from_str::<Calibration>(r#"{"Table":{"input":[1.0,0.0],"output":[0.0,1.0]}}"#).is_err());
+        assert!(serde_json::from_str::<Calibration>(r#"{"Linear":{"gain":0.0,"offset":1.0}}"#).is_err());
+    }
+}
@@ -2,9 +2,10 @@
 //! implementation details.
 //!
 //! Channels constitute the fundamental unit of interaction with NI devices, and between NI
-//! devices and controlled hardware. A `Channel` instance, trivially implementing the [`BaseChannel`]
-//! trait, corresponds to a physical channel on a NI device and, by extension,
+//! devices and controlled hardware. Channel instances, implementing the [`BaseChannel`]
+//! trait, correspond to physical channels on a NI device and, by extension,
 //! a controllable physical quantity (e.g. laser on/off, coil current).
+//! Analogue outputs are represented by [`AoChannel`] and digital output lines by [`DoChannel`].
 //!
 //! ## Editing behavior
 //! During editing, the user effectively adds [`InstrBook`] instances (instructions with associated
@@ -24,12 +25,13 @@
 //!    Windows or the NI hardware configuration utilities on Linux.
 //!  - `instr_list`: An edit-cache for the channel. Internally, this uses a `BTreeSet` to guarantee
 //!    the sorted ordering of non-overlapping instruction intervals.
-//!  - `task_type`: Specifies the task type associated with the channel. This affects the behavior
-//!    of certain methods within the channel.
+//!  - `default_value`: The value for intervals not covered by any instruction.
+//!  - `reset_value`: The value written by the reset instruction at the end of a sequence,
+//!    see [`BaseChannel::add_reset_instr`].
 //!  - `fresh_compiled`: An internal boolean value that indicates whether the compiled results
 //!    (stored in `instr_end` and `instr_val`) are up-to-date with the content of the edit cache.
 //!
-//! ## Channel property: "editable" and "streamable"
+//! ## Digital lines and ports
 //!
 //! For AO (Analog Output) channels, each edited channel corresponds directly to a NI-DAQmx channel.
 //! However, the situation becomes nuanced when we consider DO (Digital Output) channels.
@@ -48,30 +50,27 @@
 //! This port-based approach provides a substantial efficiency gain, making it indispensable for
 //! successful digital output streaming.
 //!
-//! As a result, while library users interact with "line channels" (with names in the format like
-//! `"port0/line0"`), the library internally aggregates lines from the same port during compilation.
-//! This aggregation merges their instructions for streamlined execution.
+//! As a result, while library users interact with line channels ([`DoChannel`], with names in the format like
+//! `"port0/line0"`), the parent device aggregates lines from the same port during compilation
+//! into a [`DoPort`]. Ports only hold compiled instructions and are kept apart from the line channels.
 //!
-//! For instance, if `line0/port0` is high between `t=1~3` and `line0/port4` is high between `t=2~4`,
-//! the parent device compilation will produce an auxiliary port channel named `port0`.
-//!  This channel has compiled instructions as follows:
+//! For instance, if `port0/line0` is high between `t=1~3` and `port0/line4` is high between `t=2~4`,
+//! the parent device compilation will produce port `port0` with compiled instructions as follows:
 //! `(0, t=0~1), (1, t=1~2), (17, t=2~3), (16, t=3~4), (0, t=4~5)`.
 //!
-//! Channels generated in this manner are labeled as `streamable`, meaning directly used during experiment
-//! streaming to generate driver-write signals. Channels which users directly interact with are labeled as `editable`.
-//!
-//! AO channels are both streamable and editable. DO line channels are editable but not streamable, and DO port
-//! channels are non-editable yet streamable.
+//! AO channels are streamed as they are, while DO devices stream their ports instead of the individual lines.
 
 use ndarray::{s, Array1};
 use std::collections::BTreeSet;
 
+use crate::calibration::*;
 use crate::instruction::*;
 
 /// Enum type for NI tasks. Channels are associated
 /// with a unique task type, which affects their behavior.
 /// Currently supported types: `AO` (analogue output), `DO` (digital output)
 #[derive(PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TaskType {
     AO,
     DO,
@@ -103,7 +102,24 @@ pub trait BaseChannel {
     fn is_fresh_compiled(&self) -> bool;
     /// The `default_value` trait specifies the signal value for not explicitly defined intervals.
     fn default_value(&self) -> f64;
+    /// Value written by [`BaseChannel::add_reset_instr`].
     fn reset_value(&self) -> f64;
+    /// Largest permitted change of the output volts per second, `None` if the channel is not slew-rate limited.
+    /// Checked by [`BaseChannel::compile`], see [`AoChannel::set_max_slew_rate`].
+    fn max_slew_rate(&self) -> Option<f64> {
+        None
+    }
+    /// Range `(min_val, max_val)` of output volts, `None` if the channel is not limited.
+    /// Checked by [`BaseChannel::add_instr`], [`BaseChannel::compile`] and [`BaseChannel::fill_signal_nsamps`],
+    /// see [`AoChannel::set_output_limits`].
+    fn output_limits(&self) -> Option<(f64, f64)> {
+        None
+    }
+    /// Conversion of channel values (in physical units) to output volts, applied by [`BaseChannel::fill_signal_nsamps`].
+    /// `None` if instructions are written in volts directly. See [`AoChannel::set_calibration`].
+    fn calibration(&self) -> Option<&Calibration> {
+        None
+    }
     /// Provides a reference to the edit cache of instrbook list.
     fn instr_list(&self) -> &BTreeSet<InstrBook>;
     /// Returns the ending points of compiled instructions.
@@ -132,21 +148,6 @@ pub trait BaseChannel {
     fn is_edited(&self) -> bool {
         !self.instr_list().is_empty()
     }
-    /// Channel is marked as editable if it is a AO channel or DO line channel (name contains "line")
-    fn editable(&self) -> bool {
-        match self.task_type() {
-            TaskType::AO => true,
-            TaskType::DO => self.name().contains("line"),
-        }
-    }
-    /// Channel is marked as streamable if it is a AO channel or DO port channel (name does not contain "line")
-    fn streamable(&self) -> bool {
-        match self.task_type() {
-            TaskType::AO => true,
-            // for DODevice, only port channels are streamable
-            TaskType::DO => !self.name().contains("line"),
-        }
-    }
 
     /// Compiles the instructions in the channel up to the specified `stop_pos`.
     ///
@@ -155,6 +156,10 @@ pub trait BaseChannel {
     /// it ensures that instructions are contiguous, adding padding as necessary. If two consecutive
     /// instructions have the same value, they are merged into a single instruction. 
     /// The unspecified interval from 0 to the first instruction is kept at the channel default.
+    /// Instructions with a `Local` or `Continuous` [`TimeRef`] are converted to `Absolute` ones here,
+    /// since their carrier phase depends on their placement on the channel.
+    /// Likewise, instructions starting from the current channel value ([`Instruction::with_from_current`])
+    /// get their start value from the preceding segment.
     ///
     /// # Arguments
     ///
@@ -170,7 +175,7 @@ pub trait BaseChannel {
     /// ```
     /// # use nicompiler_backend::channel::*;
     /// # use nicompiler_backend::instruction::*;
-    /// let mut channel = Channel::new(TaskType::DO, "port0/line0", 1e7, 0.);
+    /// let mut channel = DoChannel::new(0, 0, 1e7, false);
     ///
     /// // Add some instructions to the channel.
     /// channel.add_instr(Instruction::new_const(1.), 0., Some((1., false)));
@@ -209,19 +214,38 @@ pub trait BaseChannel {
                 Some(next_instr_book) => next_instr_book.start_pos,
                 None => stop_pos
             };
+            // Resolve the carrier phase reference now that the instruction placement is known.
+            // A `Continuous` instruction picks up the phase of the closest preceding one with the same frequency.
+            let prev = match instr_book.instr.time_ref {
+                TimeRef::Continuous => {
+                    let freq = instr_book.instr.carrier_freq();
+                    instr_val.iter().rev().find(|prev| prev.carrier_freq() == freq)
+                },
+                _ => None,
+            };
+            let start_time = instr_book.start_pos as f64 * self.clock_period();
+            let mut instr = instr_book.instr.resolve_time_ref(start_time, prev);
+            if instr.from_current {
+                // The current channel value is the preceding segment (instruction or padding) evaluated at the start
+                let current = match instr_val.last() {
+                    Some(prev_instr) => prev_instr.eval_point(start_time),
+                    None => self.default_value(),
+                };
+                instr = instr.resolve_from_current(start_time, current);
+            }
             // Action depends on instruction end_pos type:
             //  - Some: insert the original instruction as-is + add a separate instruction for padding until the next_edge if there is a gap
             //  - None ("run until next"): insert instruction taking the next_edge as end_pos
             match instr_book.end_spec {
                 Some((end_pos, keep_val)) => {
                     // The original instruction:
-                    instr_val.push(instr_book.instr.clone());
+                    instr_val.push(instr.clone());
                     instr_end.push(end_pos);
                     // Padding:
                     if end_pos < next_edge {
                         // padding value
                         let pad_val = if keep_val {
-                            instr_book.instr.eval_point(end_pos as f64 * self.clock_period())
+                            instr.eval_point(end_pos as f64 * self.clock_period())
                         } else {
                             self.default_value()
                         };
@@ -231,7 +255,7 @@ pub trait BaseChannel {
                     }
                 },
                 None => {
-                    instr_val.push(instr_book.instr.clone());
+                    instr_val.push(instr);
                     instr_end.push(next_edge);
                 },
             }
@@ -253,9 +277,126 @@ pub trait BaseChannel {
         assert_eq!(self.instr_val().len(), self.instr_end().len());
         assert_eq!(self.total_samps(), stop_pos);
 
+        if let Err(msg) = self.check_output_limits().and_then(|_| self.check_slew_rate()) {
+            self.clear_compile_cache();
+            panic!("{}", msg);
+        }
+
         *self.fresh_compiled_() = true;
     }
 
+    /// Checks the compiled signal against [`BaseChannel::max_slew_rate`],
+    /// returning a description of the first violation.
+    ///
+    /// Every pair of consecutive samples may differ by at most `max_slew_rate / samp_rate`, compared after mapping
+    /// them to volts with [`BaseChannel::calibration`]. Steps across segment boundaries are checked directly.
+    /// Within a segment [`Instruction::slope_bound`] usually settles it (for linear calibrations, scaled by the gain),
+    /// otherwise the samples of the segment are computed and compared.
+    fn check_slew_rate(&self) -> Result<(), String> {
+        let max_slew_rate = match self.max_slew_rate() {
+            Some(max_slew_rate) => max_slew_rate,
+            None => return Ok(()),
+        };
+        let clock_period = self.clock_period();
+        // Leave some room for rounding errors of samples right at the limit
+        let max_step = max_slew_rate * clock_period * (1.0 + 1e-9);
+        let violation = |pos: usize, step: f64| {
+            Err(format!(
+                "Channel {} exceeds its maximum slew rate of {} V/s: the output changes by {} V \
+                between t = {} s and t = {} s.\n\
+                Program transitions as ramps (e.g. ramps starting from the current value) to stay within the limit.",
+                self.name(), max_slew_rate, step, (pos - 1) as f64 * clock_period, pos as f64 * clock_period
+            ))
+        };
+
+        // The limit protects the driven hardware, so it applies to the output volts
+        let to_volts = |val: f64| match self.calibration() {
+            Some(calibration) => calibration.apply(val),
+            None => Ok(val),
+        };
+
+        let mut start_pos = 0;
+        for (idx, (&end_pos, instr)) in self.instr_end().iter().zip(self.instr_val().iter()).enumerate() {
+            if idx > 0 {
+                let prev = &self.instr_val()[idx - 1];
+                let step = to_volts(instr.eval_point(start_pos as f64 * clock_period))?
+                    - to_volts(prev.eval_point((start_pos - 1) as f64 * clock_period))?;
+                if step.abs() > max_step {
+                    return violation(start_pos, step);
+                }
+            }
+            let t_last = (end_pos - 1) as f64 * clock_period;
+            let slope_ok = instr
+                .slope_bound(start_pos as f64 * clock_period, t_last)
+                .and_then(|slope| match self.calibration() {
+                    None => Some(slope),
+                    Some(Calibration::Linear { gain, .. }) => Some(slope * gain.abs()),
+                    Some(_) => None,
+                })
+                .is_some_and(|slope| slope <= max_slew_rate);
+            if !slope_ok {
+                // Go through the samples in chunks, overlapping by one sample
+                const CHUNK: usize = 1 << 16;
+                for chunk_start in (start_pos..end_pos - 1).step_by(CHUNK) {
+                    let chunk_end = (chunk_start + CHUNK + 1).min(end_pos);
+                    let mut samps = Array1::from_iter((chunk_start..chunk_end).map(|pos| pos as f64 * clock_period));
+                    instr.eval_inplace(&mut samps.view_mut());
+                    if let Some(calibration) = self.calibration() {
+                        calibration.apply_inplace(&mut samps.view_mut())?;
+                    }
+                    if let Some(i) = (1..samps.len()).find(|&i| (samps[i] - samps[i - 1]).abs() > max_step) {
+                        return violation(chunk_start + i, samps[i] - samps[i - 1]);
+                    }
+                }
+            }
+            start_pos = end_pos;
+        }
+        Ok(())
+    }
+
+    /// Checks a range of channel values `(min, max)` against the range of [`BaseChannel::calibration`]
+    /// and, converted to volts, against [`BaseChannel::output_limits`].
+    ///
+    /// Infinite bounds are not decided here and pass, the samples are checked by [`BaseChannel::fill_signal_nsamps`].
+    fn check_value_range(&self, (min, max): (f64, f64)) -> Result<(), String> {
+        let finite_outside = |range: (f64, f64)| {
+            [min, max].into_iter().find(|val| val.is_finite() && outside_limits(*val, range))
+        };
+        let volts = match self.calibration() {
+            Some(calibration) => {
+                if let Some(val) = finite_outside(calibration.input_range()) {
+                    let (in_min, in_max) = calibration.input_range();
+                    return Err(format!("reaches {}, outside of the calibrated range [{}, {}]", val, in_min, in_max));
+                }
+                calibration.map_bounds((min, max))
+            }
+            None => (min, max),
+        };
+        if let Some(limits) = self.output_limits() {
+            if let Some(val) = [volts.0, volts.1].into_iter().find(|val| val.is_finite() && outside_limits(*val, limits)) {
+                return Err(format!("reaches {} V, outside of the output limits [{}, {}] V", val, limits.0, limits.1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the compiled instructions with [`BaseChannel::check_value_range`] using [`Instruction::bounds`],
+    /// returning a description of the first violation.
+    ///
+    /// Segments without finite bounds (e.g. `EXPR` instructions) pass here,
+    /// their samples are checked by [`BaseChannel::fill_signal_nsamps`] instead.
+    fn check_output_limits(&self) -> Result<(), String> {
+        let mut start_pos = 0;
+        for (&end_pos, instr) in self.instr_end().iter().zip(self.instr_val().iter()) {
+            let (t_start, t_last) = (start_pos as f64 * self.clock_period(), (end_pos - 1) as f64 * self.clock_period());
+            if let Err(msg) = self.check_value_range(instr.bounds(t_start, t_last)) {
+                return Err(format!("Channel {} {} between t = {} s and t = {} s", self.name(), msg, t_start, t_last));
+            }
+            start_pos = end_pos;
+        }
+        Ok(())
+    }
+
     /// Clears the `instr_list` field of the channel.
     ///
     /// If the compiled cache is empty, it also sets the `fresh_compiled` field to `true`.
@@ -301,6 +442,125 @@ pub trait BaseChannel {
         self.last_instr_end_pos() as f64 * self.clock_period()
     }
 
+    /// Minimum and maximum value the channel takes over `[start_time, end_time]`, according to the edit cache.
+    ///
+    /// Combines [`Instruction::bounds`] of every instruction overlapping the interval with the padding values
+    /// that compilation would insert (`keep_val` or channel default), so it is available without compiling
+    /// or sampling the channel. The bounds are conservative: the channel default is always included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::channel::*;
+    /// # use nicompiler_backend::instruction::*;
+    /// let mut channel = AoChannel::new(0, 1e6, 0.);
+    /// channel.add_instr(Instruction::new_linramp(0., 2., 1., 2.), 1., Some((1., true)));
+    /// channel.add_instr(Instruction::new_const(-1.), 3., Some((1., false)));
+    /// assert_eq!(channel.value_bounds(0., 2.5), (0., 2.));
+    /// assert_eq!(channel.value_bounds(0., 5.), (-1., 2.));
+    /// ```
+    fn value_bounds(&self, start_time: f64, end_time: f64) -> (f64, f64) {
+        assert!(start_time <= end_time, "Expected start_time <= end_time, got {} and {}", start_time, end_time);
+        let (mut min, mut max) = (self.default_value(), self.default_value());
+        let mut include = |(lo, hi): (f64, f64)| {
+            min = min.min(lo);
+            max = max.max(hi);
+        };
+
+        let mut instr_list = self.instr_list().iter().peekable();
+        while let Some(instr_book) = instr_list.next() {
+            let start = instr_book.start_pos as f64 * self.clock_period();
+            let next_edge = match instr_list.peek() {
+                Some(next_instr_book) => next_instr_book.start_pos as f64 * self.clock_period(),
+                None => f64::INFINITY,
+            };
+            if start > end_time || next_edge < start_time {
+                continue;
+            }
+            let end = match instr_book.end_spec {
+                Some((end_pos, _keep_val)) => end_pos as f64 * self.clock_period(),
+                None => next_edge,
+            };
+            if end >= start_time {
+                include(instr_book.instr.bounds(start.max(start_time), end.min(end_time)));
+            }
+            // `keep_val` padding holds the value at `end_pos`, the default value is already included
+            if let Some((_end_pos, true)) = instr_book.end_spec {
+                if end < next_edge && end <= end_time {
+                    let pad_val = instr_book.instr.eval_point(end);
+                    include((pad_val, pad_val));
+                }
+            }
+        }
+        (min, max)
+    }
+
+    /// Same as [`BaseChannel::value_bounds`], converted to volts with [`BaseChannel::calibration`].
+    /// These are the bounds of the samples produced by [`BaseChannel::fill_signal_nsamps`].
+    fn output_bounds(&self, start_time: f64, end_time: f64) -> (f64, f64) {
+        let bounds = self.value_bounds(start_time, end_time);
+        match self.calibration() {
+            Some(calibration) => calibration.map_bounds(bounds),
+            None => bounds,
+        }
+    }
+
+    /// Instructions of the edit cache overlapping `[start_time, end_time]`, in time order.
+    ///
+    /// An instruction without a specified duration counts as running until the next instruction.
+    /// Use [`BaseChannel::compiled_segments`] for what the channel actually plays, including padding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::channel::*;
+    /// let mut channel = DoChannel::new(0, 0, 1e6, false);
+    /// channel.constant(1., 0., Some((1e-3, false)));
+    /// channel.constant(1., 2e-3, None);
+    /// assert_eq!(channel.instrs_in_window(0., 1.).len(), 2);
+    /// assert_eq!(channel.instrs_in_window(1e-3, 1.5e-3).len(), 0);
+    /// assert_eq!(channel.instrs_in_window(5., 6.)[0].start_pos, 2000);
+    /// ```
+    fn instrs_in_window(&self, start_time: f64, end_time: f64) -> Vec<&InstrBook> {
+        assert!(start_time <= end_time, "Expected start_time <= end_time, got {} and {}", start_time, end_time);
+        let mut instrs = Vec::new();
+        let mut instr_list = self.instr_list().iter().peekable();
+        while let Some(instr_book) = instr_list.next() {
+            let start = instr_book.start_pos as f64 * self.clock_period();
+            let end = match instr_book.end_pos() {
+                Some(end_pos) => end_pos as f64 * self.clock_period(),
+                None => instr_list
+                    .peek()
+                    .map_or(f64::INFINITY, |next_instr_book| next_instr_book.start_pos as f64 * self.clock_period()),
+            };
+            if start <= end_time && end > start_time {
+                instrs.push(instr_book);
+            }
+        }
+        instrs
+    }
+
+    /// Segments of the compiled channel overlapping `[start_time, end_time]` as `(start_pos, end_pos, instr)`,
+    /// in time order.
+    ///
+    /// This is the `instr_end` / `instr_val` table produced by [`BaseChannel::compile`]: the segments cover
+    /// the whole run without gaps, so padding and the final reset instruction show up as `CONST` segments,
+    /// and instructions with a `Local` / `Continuous` time reference or starting from the current value
+    /// are listed in their resolved form. Panics if the channel is not compiled.
+    fn compiled_segments(&self, start_time: f64, end_time: f64) -> Vec<(usize, usize, &Instruction)> {
+        assert!(self.is_compiled(), "Attempting to list compiled segments of not-compiled channel {}", self.name());
+        assert!(start_time <= end_time, "Expected start_time <= end_time, got {} and {}", start_time, end_time);
+        let mut segments = Vec::new();
+        let mut seg_start = 0;
+        for (&seg_end, instr) in self.instr_end().iter().zip(self.instr_val()) {
+            if seg_start as f64 * self.clock_period() <= end_time && seg_end as f64 * self.clock_period() > start_time {
+                segments.push((seg_start, seg_end, instr));
+            }
+            seg_start = seg_end;
+        }
+        segments
+    }
+
     /// Adds an instruction to the channel.
     ///
     /// This is the primary method for adding instructions. It computes the discrete position
@@ -319,14 +579,15 @@ pub trait BaseChannel {
     ///
     /// # Panics
     ///
-    /// This method will panic if the new instruction overlaps with any existing instruction.
+    /// This method will panic if the new instruction overlaps with any existing instruction,
+    /// or if its [`Instruction::bounds`] over the instruction interval fail [`BaseChannel::check_value_range`].
     ///
     /// # Example
     ///
     /// ```
     /// # use nicompiler_backend::channel::*;
     /// # use nicompiler_backend::instruction::*;
-    /// let mut channel = Channel::new(TaskType::DO, "port0/line0", 1e7, 0.);
+    /// let mut channel = DoChannel::new(0, 0, 1e7, false);
     ///
     /// // Ask the DO channel to go high at t=1 for 0.5 seconds, then return to default value (0)
     /// channel.add_instr(Instruction::new_const(1.), 1., Some((0.5, false)));
@@ -346,7 +607,7 @@ pub trait BaseChannel {
     /// ```should_panic
     /// # use nicompiler_backend::channel::*;
     /// # use nicompiler_backend::instruction::*;
-    /// let mut channel = Channel::new(TaskType::DO, "port0/line0", 1e7, 0.);
+    /// let mut channel = DoChannel::new(0, 0, 1e7, false);
     /// channel.add_instr(Instruction::new_const(1.), 1., Some((0.5, false)));
     /// channel.add_instr(Instruction::new_const(1.), 0.5, Some((0.001, true)));
     /// channel.add_instr(Instruction::new_const(1.), 0.5, Some((1., false))); // This will panic
@@ -358,6 +619,8 @@ pub trait BaseChannel {
     ///  Instruction InstrBook([CONST, {value: 1}], 5000000-15000000, false) overlaps with the next instruction InstrBook([CONST, {value: 1}], 5000000-5010000, true)"
     /// ```
     fn add_instr(&mut self, func: Instruction, t: f64, dur_spec: Option<(f64, bool)>) {
+        check_instr_timing(&func.instr_type, t, dur_spec.map(|(dur, _keep_val)| dur))
+            .unwrap_or_else(|err| panic!("{}", err));
         // Sanity check - non-negative start time (compare with negative clock half-period to avoid virtual panics for nominal t=0.0)
         assert!(t > -0.5*self.clock_period(), "Attempted to insert an instruction at negative start time {t}");
 
@@ -367,7 +630,7 @@ pub trait BaseChannel {
             Some((dur, keep_val)) => {
                 let end_pos = ((t + dur) * self.samp_rate()).round() as usize;
                 // Sanity check - pulse length is at leas 1 clock period or longer
-                if end_pos - start_pos < 1 {
+                if end_pos < start_pos + 1 {
                     let t_start_clock = t * self.samp_rate();
                     let t_stop = t + dur;
                     let t_stop_clock = t_stop * self.samp_rate();
@@ -385,8 +648,47 @@ pub trait BaseChannel {
             },
             None => None,
         };
-        let mut new_instr_book = InstrBook::new(start_pos, end_spec, func);
+        let t_end = dur_spec.map_or(t, |(dur, _keep_val)| t + dur);
+        if let Err(msg) = self.check_instr_values(&func, t, t_end) {
+            panic!("{}", msg);
+        }
+        let new_instr_book = self
+            .fit_instr_book(InstrBook::new(start_pos, end_spec, func))
+            .unwrap_or_else(|msg| panic!("{}", msg));
 
+        self.instr_list_().insert(new_instr_book);
+        *self.fresh_compiled_() = false;
+    }
+    /// Checks the values `func` takes between `t_start` and `t_end` before it is placed on the channel:
+    /// DO channels only accept `CONST` and `PULSETRAIN` instructions taking 0 and 1,
+    /// and the [`Instruction::bounds`] must pass [`BaseChannel::check_value_range`].
+    fn check_instr_values(&self, func: &Instruction, t_start: f64, t_end: f64) -> Result<(), String> {
+        // DO lines are merged into a port bit by bit, so anything but 0 and 1 would corrupt the other lines.
+        // Only `CONST` and `PULSETRAIN` are known to take nothing else: the bounds of other types
+        // (e.g. a clipped sine spanning [0, 1]) say nothing about the values in between.
+        if self.task_type() == TaskType::DO && !matches!(func.instr_type, InstrType::CONST | InstrType::PULSETRAIN) {
+            return Err(format!(
+                "DO channel {} only accepts CONST and PULSETRAIN instructions, got {}",
+                self.name(), func.instr_type
+            ));
+        }
+        let (min, max) = func.bounds(t_start, t_end);
+        // Checked here from the instruction bounds rather than when the port is compiled.
+        if self.task_type() == TaskType::DO && ![min, max].iter().all(|val| *val == 0.0 || *val == 1.0) {
+            return Err(format!(
+                "DO channel {} only accepts values 0 and 1, instruction {} spans [{}, {}]",
+                self.name(), func, min, max
+            ));
+        }
+        // Unbounded instructions are left to the checks on compilation and sampling
+        self.check_value_range((min, max))
+            .map_err(|msg| format!("Instruction {} on channel {} {}", func, self.name(), msg))
+    }
+    /// Checks `new_instr_book` for collisions with the instructions already on the channel.
+    ///
+    /// A collision of precisely 1 tick is resolved by trimming the new instruction (or delaying its start,
+    /// if it has no specified duration), so the adjusted book is returned. Anything else is an error.
+    fn fit_instr_book(&self, mut new_instr_book: InstrBook) -> Result<InstrBook, String> {
         // Check for any collisions with already existing instructions
         // - collision on the left
         if let Some(prev) = self.instr_list().range(..&new_instr_book).next_back() {
@@ -403,7 +705,9 @@ pub trait BaseChannel {
                 //      - no spec dur => just shift start_pos by 1 tick (if this leads to a collision with an existing neighbor to the right, next check will catch it)
                 match new_instr_book.dur() {
                     Some(dur) => {
-                        assert!(dur - 1 >= 1, "1-tick collision on the left cannot be resolved by trimming since the new instruction is only 1 tick long");
+                        if dur - 1 < 1 {
+                            return Err("1-tick collision on the left cannot be resolved by trimming since the new instruction is only 1 tick long".to_string());
+                        }
                         new_instr_book.start_pos += 1;
                     },
                     None => {
@@ -412,11 +716,11 @@ pub trait BaseChannel {
                 };
             } else {
                 // Serious collision of 2 or more ticks due to a user mistake
-                panic!("\n\
+                return Err(format!("\n\
                     Collision on the left with the following existing instruction:\n\
                     \t{prev}\n\
                     The new instruction is:\n\
-                    \t{new_instr_book}")
+                    \t{new_instr_book}"));
             }
         }
         // - collision on the right
@@ -431,31 +735,137 @@ pub trait BaseChannel {
                 //  This might be due to a rounding error for back-to-back pulses. Try to auto-fix it, if possible.
                 //  Action depends on the new instruction duration type:
                 //      - spec dur => trim the new instruction from the right by one tick (provided it is long enough to have at least 1 tick left after trimming)
-                //      - no spec dur => panic since "go_something" is not meant to be inserted right in front of some other instruction
+                //      - no spec dur => error since "go_something" is not meant to be inserted right in front of some other instruction
                 match new_instr_book.dur() {
                     Some(dur) => {
-                        assert!(dur - 1 >= 1, "1-tick collision on the right cannot be resolved by trimming since the new instruction is only 1 tick long");
+                        if dur - 1 < 1 {
+                            return Err("1-tick collision on the right cannot be resolved by trimming since the new instruction is only 1 tick long".to_string());
+                        }
                         new_instr_book.end_spec.as_mut().unwrap().0 -= 1;
                     },
-                    None => panic!("Attempt to insert go_something-type instruction {new_instr_book} right at the start of another instruction {next}"),
+                    None => return Err(format!("Attempt to insert go_something-type instruction {new_instr_book} right at the start of another instruction {next}")),
                 }
             } else {
                 // Serious collision of 2 or more ticks due to a user mistake
-                panic!("\n\
+                return Err(format!("\n\
                     The new instruction:\n\
                     \t{new_instr_book}\n\
                     collides on the right with the following existing instruction:\n\
-                    \t{next}")
+                    \t{next}"));
             };
         };
-
-        self.instr_list_().insert(new_instr_book);
-        *self.fresh_compiled_() = false;
+        Ok(new_instr_book)
     }
     /// Utility function to add a constant instruction to the channel
     fn constant(&mut self, value: f64, t: f64, dur_spec: Option<(f64, bool)>) {
         self.add_instr(Instruction::new_const(value), t, dur_spec);
     }
+    /// Returns the instruction which starts at time `t` or covers it.
+    ///
+    /// `t` is rounded to the sample clock grid. An instruction with a specified duration covers `[start, end)`,
+    /// one without a specified duration covers everything until the next instruction.
+    /// The `keep_val` padding after an instruction doesn't count.
+    fn instr_at(&self, t: f64) -> Option<&InstrBook> {
+        if !t.is_finite() {
+            return None;
+        }
+        let pos = (t * self.samp_rate()).round() as usize;
+        // Ordering of `InstrBook` only looks at `start_pos`
+        let probe = InstrBook::new(pos, None, Instruction::new_const(0.0));
+        self.instr_list()
+            .range(..=&probe)
+            .next_back()
+            .filter(|book| book.end_pos().is_none_or(|end_pos| pos < end_pos))
+    }
+    /// Start position and end specification of the instruction returned by [`BaseChannel::instr_at`].
+    /// Panics if there is none.
+    fn instr_interval_at(&self, t: f64) -> (usize, Option<(usize, bool)>) {
+        match self.instr_at(t) {
+            Some(book) => (book.start_pos, book.end_spec),
+            None => panic!("Channel {} has no instruction starting at or covering t = {} s", self.name(), t),
+        }
+    }
+    /// Removes the instruction which starts at time `t` or covers it (see [`BaseChannel::instr_at`]) and returns it.
+    ///
+    /// Panics if there is no such instruction.
+    ///
+    /// ```
+    /// # use nicompiler_backend::channel::*;
+    /// # use nicompiler_backend::instruction::*;
+    /// let mut channel = AoChannel::new(0, 1e6, 0.0);
+    /// channel.constant(1.0, 0.0, Some((1.0, false)));
+    /// channel.constant(2.0, 1.0, Some((1.0, false)));
+    ///
+    /// let removed = channel.remove_instr(1.5);
+    /// assert!(removed.instr == Instruction::new_const(2.0));
+    /// assert_eq!(channel.instr_list().len(), 1);
+    /// ```
+    fn remove_instr(&mut self, t: f64) -> InstrBook {
+        let (start_pos, _end_spec) = self.instr_interval_at(t);
+        let probe = InstrBook::new(start_pos, None, Instruction::new_const(0.0));
+        let removed = self.instr_list_().take(&probe).unwrap();
+        *self.fresh_compiled_() = false;
+        removed
+    }
+    /// Replaces the function of the instruction which starts at time `t` or covers it, keeping its interval.
+    ///
+    /// `func` is checked with the same value checks as in [`BaseChannel::add_instr`].
+    /// Note that time arguments of `func` (e.g. `LINRAMP` start and end times) are absolute, they are not adjusted
+    /// to the interval. Panics if there is no instruction at `t` or `func` fails the checks,
+    /// in which case the channel is left unchanged.
+    fn replace_instr(&mut self, t: f64, func: Instruction) {
+        let (start_pos, end_spec) = self.instr_interval_at(t);
+        let t_start = start_pos as f64 / self.samp_rate();
+        let t_end = end_spec.map_or(t_start, |(end_pos, _keep_val)| end_pos as f64 / self.samp_rate());
+        if let Err(msg) = self.check_instr_values(&func, t_start, t_end) {
+            panic!("{}", msg);
+        }
+        self.remove_instr(t);
+        self.instr_list_().insert(InstrBook::new(start_pos, end_spec, func));
+    }
+    /// Moves the instruction which starts at time `t` or covers it, such that it starts at `new_t`.
+    ///
+    /// The duration (in clock ticks) and `keep_val` are kept, and the function is delayed with
+    /// [`Instruction::shifted`] so the instruction plays the same waveform at its new place.
+    /// The moved instruction goes through the same value and collision checks as in [`BaseChannel::add_instr`]
+    /// (including the 1-tick auto-trim). Panics if there is no instruction at `t` or the checks fail,
+    /// in which case the channel is left unchanged.
+    ///
+    /// ```
+    /// # use nicompiler_backend::channel::*;
+    /// let mut channel = AoChannel::new(0, 1e6, 0.0);
+    /// channel.constant(1.0, 0.0, Some((1.0, false)));
+    /// channel.move_instr(0.5, 2.0);
+    /// assert_eq!(channel.instr_list().first().unwrap().start_pos, 2_000_000);
+    /// ```
+    fn move_instr(&mut self, t: f64, new_t: f64) {
+        assert!(new_t.is_finite(), "Attempted to move an instruction to non-finite time {new_t}");
+        assert!(new_t > -0.5*self.clock_period(), "Attempted to move an instruction to negative start time {new_t}");
+        let was_fresh_compiled = self.is_fresh_compiled();
+        let old_book = self.remove_instr(t);
+        let new_start_pos = (new_t * self.samp_rate()).round() as usize;
+        // Shift by whole ticks, so the function stays aligned with the interval
+        let shift = |pos: usize| pos - old_book.start_pos + new_start_pos;
+        let new_end_spec = old_book.end_spec.map(|(end_pos, keep_val)| (shift(end_pos), keep_val));
+        let dt = (new_start_pos as f64 - old_book.start_pos as f64) / self.samp_rate();
+        let func = old_book.instr.shifted(dt);
+
+        let t_start = new_start_pos as f64 / self.samp_rate();
+        let t_end = new_end_spec.map_or(t_start, |(end_pos, _keep_val)| end_pos as f64 / self.samp_rate());
+        let result = self
+            .check_instr_values(&func, t_start, t_end)
+            .and_then(|_| self.fit_instr_book(InstrBook::new(new_start_pos, new_end_spec, func)));
+        match result {
+            Ok(new_instr_book) => {
+                self.instr_list_().insert(new_instr_book);
+            }
+            Err(msg) => {
+                self.instr_list_().insert(old_book);
+                *self.fresh_compiled_() = was_fresh_compiled;
+                panic!("{}", msg);
+            }
+        }
+    }
     fn add_reset_instr(&mut self, reset_pos: usize) {
         if reset_pos < self.last_instr_end_pos() {
             panic!(
@@ -495,7 +905,7 @@ pub trait BaseChannel {
     ///
     /// ```
     /// # use nicompiler_backend::channel::*;
-    /// let mut channel = Channel::new(TaskType::DO, "port0/line0", 1e7, 0.);
+    /// let mut channel = DoChannel::new(0, 0, 1e7, false);
     /// channel.instr_end_().extend([10, 20, 30, 40, 50].iter());
     ///
     /// assert_eq!(channel.binfind_first_intersect_instr(15), 1);
@@ -505,24 +915,13 @@ pub trait BaseChannel {
     /// assert_eq!(channel.binfind_first_intersect_instr(5), 0);
     /// ```
     fn binfind_first_intersect_instr(&self, start_pos: usize) -> usize {
-        let mut low: i32 = 0;
-        let mut high: i32 = self.instr_end().len() as i32 - 1;
-        while low <= high {
-            let mid = ((low + high) / 2) as usize;
-            if self.instr_end()[mid] < start_pos {
-                low = mid as i32 + 1;
-            } else if self.instr_end()[mid] > start_pos {
-                high = mid as i32 - 1;
-            } else {
-                return mid as usize;
-            }
-        }
-        low as usize
+        binfind_first_intersect(self.instr_end(), start_pos)
     }
     /// Fills a buffer (1D view of array) with the signal samples derived from a channel's instructions.
     ///
     /// This method samples the float-point signal from channel's compile cache
     /// between the positions `start_pos` and `end_pos`, and replaces the contents of the buffer with results.
+    /// Channels with a [`BaseChannel::calibration`] convert the samples to volts.
     /// The number of samples is given by `num_samps`. Time-dependent instructions assume that
     /// the buffer is already populated with correctly sampled time values.
     ///
@@ -538,13 +937,16 @@ pub trait BaseChannel {
     /// * If the channel is not compiled.
     /// * If `end_pos` is not greater than `start_pos`.
     /// * If `end_pos` exceeds the duration of the channel's compiled instructions.
+    /// * If any sample lies outside of the calibrated range or, in volts, outside of [`BaseChannel::output_limits`].
+    ///   This is the last line of defence before samples are written to the hardware,
+    ///   covering instructions without analytic bounds.
     ///
     /// # Example
     ///
     /// ```
     /// # use nicompiler_backend::channel::*;
     /// # use nicompiler_backend::instruction::*;
-    /// let mut channel = Channel::new(TaskType::AO, "ao0", 1e6, 0.);
+    /// let mut channel = AoChannel::new(0, 1e6, 0.);
     /// // Sample 100 samples from t=0 to t=10s
     /// let (start_pos, end_pos, num_samps) = (0, 1e7 as usize, 100);
     ///
@@ -573,42 +975,23 @@ pub trait BaseChannel {
         num_samps: usize,
         buffer: &mut ndarray::ArrayViewMut1<f64>,
     ) {
-        assert!(
-            self.is_compiled(),
-            "Attempting to calculate signal on not-compiled channel {}",
-            self.name()
-        );
-        assert!(
-            end_pos > start_pos,
-            "Channel {} attempting to calculate signal for invalid interval {}-{}",
-            self.name(),
-            start_pos,
-            end_pos
-        );
-        assert!(
-            end_pos <= self.total_samps(),
-            "Attempting to calculate signal interval {}-{} for channel {}, which ends at {}",
-            start_pos,
-            end_pos,
-            self.name(),
-            self.total_samps()
-        );
-
-        let start_instr_idx: usize = self.binfind_first_intersect_instr(start_pos);
-        let end_instr_idx: usize = self.binfind_first_intersect_instr(end_pos);
-        // Function for converting position idx (unit of start_pos, end_pos) to buffer offset
-        // Linear function: start_pos |-> 0, end_pos |-> num_samps
-        let cvt_idx = |pos| {
-            ((pos - start_pos) as f64 / (end_pos - start_pos) as f64 * (num_samps as f64)) as usize
-        };
-
-        let mut cur_pos: usize = start_pos as usize;
-        for i in start_instr_idx..=end_instr_idx {
-            let instr_signal_length = std::cmp::min(end_pos, self.instr_end()[i]) - cur_pos;
-            let slice =
-                &mut buffer.slice_mut(s![cvt_idx(cur_pos)..cvt_idx(cur_pos + instr_signal_length)]);
-            self.instr_val()[i].eval_inplace(slice);
-            cur_pos += instr_signal_length;
+        fill_compiled_signal(self.name(), self.instr_end(), self.instr_val(), start_pos, end_pos, num_samps, buffer);
+        if let Some(calibration) = self.calibration() {
+            if let Err(msg) = calibration.apply_inplace(buffer) {
+                panic!(
+                    "Channel {} between t = {} s and t = {} s: {}",
+                    self.name(), start_pos as f64 * self.clock_period(), end_pos as f64 * self.clock_period(), msg
+                );
+            }
+        }
+        if let Some(limits) = self.output_limits() {
+            if let Some(val) = buffer.iter().find(|val| outside_limits(**val, limits)) {
+                panic!(
+                    "Channel {} reaches {} V between t = {} s and t = {} s, outside of its output limits [{}, {}] V",
+                    self.name(), val, start_pos as f64 * self.clock_period(), end_pos as f64 * self.clock_period(),
+                    limits.0, limits.1
+                );
+            }
         }
     }
     /// Calls `fill_signal_nsamps` with the appropriate buffer and returns signal vector.
@@ -631,35 +1014,132 @@ pub trait BaseChannel {
     }
 }
 
-/// Represents a physical channel on an NI device.
-///
-/// `Channel` provides a concrete implementation of the [`BaseChannel`] trait, offering
-/// straightforward and direct methods to interact with the NI device channels. Each instance of
-/// `Channel` corresponds to a physical channel on an NI device, characterized by its `name`.
+/// Binary search over compiled instruction end positions, see [`BaseChannel::binfind_first_intersect_instr`].
+fn binfind_first_intersect(instr_end: &[usize], start_pos: usize) -> usize {
+    let mut low: i32 = 0;
+    let mut high: i32 = instr_end.len() as i32 - 1;
+    while low <= high {
+        let mid = ((low + high) / 2) as usize;
+        if instr_end[mid] < start_pos {
+            low = mid as i32 + 1;
+        } else if instr_end[mid] > start_pos {
+            high = mid as i32 - 1;
+        } else {
+            return mid as usize;
+        }
+    }
+    low as usize
+}
+
+/// Whether `val` lies outside of the `(min_val, max_val)` range, allowing for rounding errors
+/// of samples right at the limit.
+fn outside_limits(val: f64, (min_val, max_val): (f64, f64)) -> bool {
+    let tol = (max_val - min_val) * 1e-9;
+    val < min_val - tol || val > max_val + tol
+}
+
+/// Samples compiled instructions into `buffer`, shared by channels and ports.
+/// See [`BaseChannel::fill_signal_nsamps`].
+fn fill_compiled_signal(
+    name: &str,
+    instr_end: &[usize],
+    instr_val: &[Instruction],
+    start_pos: usize,
+    end_pos: usize,
+    num_samps: usize,
+    buffer: &mut ndarray::ArrayViewMut1<f64>,
+) {
+    let total_samps = instr_end.last().copied().unwrap_or(0);
+    assert!(
+        !instr_end.is_empty(),
+        "Attempting to calculate signal on not-compiled channel {}",
+        name
+    );
+    assert!(
+        end_pos > start_pos,
+        "Channel {} attempting to calculate signal for invalid interval {}-{}",
+        name,
+        start_pos,
+        end_pos
+    );
+    assert!(
+        end_pos <= total_samps,
+        "Attempting to calculate signal interval {}-{} for channel {}, which ends at {}",
+        start_pos,
+        end_pos,
+        name,
+        total_samps
+    );
+
+    let start_instr_idx: usize = binfind_first_intersect(instr_end, start_pos);
+    let end_instr_idx: usize = binfind_first_intersect(instr_end, end_pos);
+    // Function for converting position idx (unit of start_pos, end_pos) to buffer offset
+    // Linear function: start_pos |-> 0, end_pos |-> num_samps
+    let cvt_idx = |pos| {
+        ((pos - start_pos) as f64 / (end_pos - start_pos) as f64 * (num_samps as f64)) as usize
+    };
+
+    let mut cur_pos: usize = start_pos as usize;
+    for i in start_instr_idx..=end_instr_idx {
+        let instr_signal_length = std::cmp::min(end_pos, instr_end[i]) - cur_pos;
+        let slice =
+            &mut buffer.slice_mut(s![cvt_idx(cur_pos)..cvt_idx(cur_pos + instr_signal_length)]);
+        instr_val[i].eval_inplace(slice);
+        cur_pos += instr_signal_length;
+    }
+}
+
+/// Represents an analogue output channel `ao(number)` on an NI device.
 ///
-/// The `Channel` struct ensures that any interactions with the NI devices are consistent with the
-/// requirements and behaviors defined by the [`BaseChannel`] trait.
+/// `AoChannel` provides a concrete implementation of the [`BaseChannel`] trait for analogue outputs.
 ///
 /// # Fields
 /// - `samp_rate`: The sampling rate of the channel, determining how often the channel updates.
-/// - `task_type`: Specifies the type of task associated with this channel.
 /// - `fresh_compiled`: A boolean indicating whether the channel's compiled results are up-to-date with the edit cache.
 /// - `name`: A string representation of the channel's identifier as recognized by the NI driver.
+/// - `default_value`: Signal value for intervals not covered by instructions.
+/// - `reset_value`: Value written by [`BaseChannel::add_reset_instr`], `0.0` unless set with [`AoChannel::set_reset_value`].
+/// - `max_slew_rate`: Optional limit of the output change per second, see [`AoChannel::set_max_slew_rate`].
+/// - `min_val`, `max_val`: Output limits in volts, `-10.0` and `10.0` unless set with [`AoChannel::set_output_limits`].
+/// - `unit`, `calibration`: Physical unit of the channel values and their conversion to volts,
+///   `"V"` and `None` unless set with [`AoChannel::set_calibration`].
 /// - `instr_list`: The edit-cache for the channel. Maintains a sorted list of instruction books.
 /// - `instr_end`: Stores the ending points of compiled instructions.
 /// - `instr_val`: Holds the values of the compiled instructions.
-pub struct Channel {
+///
+/// With the `serde` feature, a channel can be serialized together with its edit cache.
+/// The compile cache is left out, so a deserialized channel has to be compiled again.
+/// Deserialized settings and instructions are checked the same way as when they are set or added.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "serialize::AoChannelRecord")
+)]
+pub struct AoChannel {
     samp_rate: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
     fresh_compiled: bool,
-    task_type: TaskType,
     name: String,
     default_value: f64,
+    reset_value: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    max_slew_rate: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default = "AoChannel::default_min_val"))]
+    min_val: f64,
+    #[cfg_attr(feature = "serde", serde(default = "AoChannel::default_max_val"))]
+    max_val: f64,
+    #[cfg_attr(feature = "serde", serde(default = "AoChannel::default_unit"))]
+    unit: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    calibration: Option<Calibration>,
     instr_list: BTreeSet<InstrBook>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     instr_end: Vec<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     instr_val: Vec<Instruction>,
 }
 
-impl BaseChannel for Channel {
+impl BaseChannel for AoChannel {
     fn samp_rate(&self) -> f64 {
         self.samp_rate
     }
@@ -673,7 +1153,16 @@ impl BaseChannel for Channel {
         self.default_value
     }
     fn reset_value(&self) -> f64 {
-        0.0  // ToDo when splitting AO/DO types
+        self.reset_value
+    }
+    fn max_slew_rate(&self) -> Option<f64> {
+        self.max_slew_rate
+    }
+    fn output_limits(&self) -> Option<(f64, f64)> {
+        Some((self.min_val, self.max_val))
+    }
+    fn calibration(&self) -> Option<&Calibration> {
+        self.calibration.as_ref()
     }
     fn instr_list(&self) -> &BTreeSet<InstrBook> {
         &self.instr_list
@@ -697,48 +1186,470 @@ impl BaseChannel for Channel {
         &mut self.fresh_compiled
     }
     fn task_type(&self) -> TaskType {
-        self.task_type
+        TaskType::AO
     }
 }
 
-impl Channel {
-    /// Constructs a new `Channel` instance.
-    ///
-    /// Creates a new channel with the specified task type, physical name, and sampling rate.
+impl AoChannel {
+    /// Constructs a new `AoChannel` instance named `ao(id)`.
     ///
     /// # Arguments
-    /// * `task_type`: Specifies the type of task associated with this channel.
-    ///    It can be either `AO` (analogue output) or `DO` (digital output).
-    /// * `name`: The string representation of the channel's identifier as recognized by the NI driver.
+    /// * `id`: Channel number, the channel is named `ao(id)` as recognized by the NI driver.
     /// * `samp_rate`: The sampling rate for the channel, determining how often the channel updates.
+    /// * `default_value`: Signal value for intervals not covered by instructions.
+    ///   Like instruction values, it is taken in the physical unit once a calibration is set.
+    ///   It is checked against the calibration and output limits on compilation.
     ///
-    /// # Returns
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::channel::*;
+    /// let ao_channel = AoChannel::new(0, 1e6, 0.);
+    /// assert_eq!(ao_channel.name(), "ao0");
+    /// assert_eq!(ao_channel.output_limits(), Some((-10., 10.)));
+    /// ```
+    pub fn new(id: usize, samp_rate: f64, default_value: f64) -> Self {
+        Self {
+            samp_rate,
+            fresh_compiled: true,
+            name: format!("ao{}", id),
+            default_value,
+            reset_value: 0.0,
+            max_slew_rate: None,
+            min_val: Self::default_min_val(),
+            max_val: Self::default_max_val(),
+            unit: Self::default_unit(),
+            calibration: None,
+            instr_list: BTreeSet::new(),
+            instr_end: Vec::new(),
+            instr_val: Vec::new(),
+        }
+    }
+
+    /// Sets the value written by [`BaseChannel::add_reset_instr`].
+    /// Reset instructions which are already in the edit cache keep their value.
+    pub fn set_reset_value(&mut self, reset_value: f64) {
+        self.check_reset_value(reset_value).unwrap_or_else(|msg| panic!("{}", msg));
+        self.reset_value = reset_value;
+    }
+    fn check_reset_value(&self, reset_value: f64) -> Result<(), String> {
+        if !reset_value.is_finite() {
+            return Err(format!("Reset value of channel {} must be finite, got {}", self.name, reset_value));
+        }
+        Ok(())
+    }
+
+    /// Sets the largest permitted change of the output per second, `None` removes the limit.
+    /// The limit is in V/s also when a calibration is set, the calibrated output is checked.
+    ///
+    /// Drivers such as coil drivers or piezo amplifiers may trip when hit with a step. With a limit set,
+    /// [`BaseChannel::compile`] rejects any step or slope in the compiled signal which exceeds it, including the
+    /// jumps from the channel default to the first instruction and to the reset value at the end.
+    /// Such transitions have to be programmed as ramps, e.g. with [`Instruction::with_from_current`] ramps.
+    ///
+    /// Clears the compile cache, since it was not checked against the new limit.
+    ///
+    /// # Example
+    ///
+    /// ```should_panic
+    /// # use nicompiler_backend::channel::*;
+    /// # use nicompiler_backend::instruction::*;
+    /// let mut channel = AoChannel::new(0, 1e6, 0.);
+    /// channel.set_max_slew_rate(Some(1e3));
+    /// channel.add_instr(Instruction::new_linramp(0., 1., 0., 1e-3), 0., Some((1e-3, true)));
+    /// channel.add_instr(Instruction::new_const(5.), 2e-3, None);
+    /// channel.compile(3000); // Panics: step from 1 V to 5 V at t = 2 ms
+    /// ```
+    pub fn set_max_slew_rate(&mut self, max_slew_rate: Option<f64>) {
+        Self::check_max_slew_rate(max_slew_rate).unwrap_or_else(|msg| panic!("{}", msg));
+        self.max_slew_rate = max_slew_rate;
+        self.clear_compile_cache();
+    }
+    fn check_max_slew_rate(max_slew_rate: Option<f64>) -> Result<(), String> {
+        match max_slew_rate {
+            Some(max_slew_rate) if !(max_slew_rate > 0.0 && max_slew_rate.is_finite()) => {
+                Err(format!("Maximum slew rate must be positive and finite, got {}", max_slew_rate))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the range `[min_val, max_val]` the output has to stay within (V).
+    ///
+    /// The limits guard the connected hardware against programming mistakes: instructions exceeding them
+    /// are rejected by [`BaseChannel::add_instr`] where their bounds are known analytically,
+    /// by [`BaseChannel::compile`], and finally on the samples in [`BaseChannel::fill_signal_nsamps`].
+    /// They are also passed to the driver, which picks the tightest output range of the card that contains them.
+    ///
+    /// Instructions already in the edit cache, as well as the default and reset values, are checked on
+    /// the next compilation, so the compile cache is cleared.
     ///
-    /// Returns a new instance of `Channel` initialized with the provided arguments.
+    /// # Example
+    ///
+    /// ```should_panic
+    /// # use nicompiler_backend::channel::*;
+    /// # use nicompiler_backend::instruction::*;
+    /// let mut channel = AoChannel::new(0, 1e6, 0.);
+    /// channel.set_output_limits(-1., 5.);
+    /// // Mistyped end value: panics right away
+    /// channel.add_instr(Instruction::new_linramp(0., 50., 0., 1e-3), 0., Some((1e-3, true)));
+    /// ```
+    pub fn set_output_limits(&mut self, min_val: f64, max_val: f64) {
+        self.check_output_limits(min_val, max_val).unwrap_or_else(|msg| panic!("{}", msg));
+        self.min_val = min_val;
+        self.max_val = max_val;
+        self.clear_compile_cache();
+    }
+
+    fn check_output_limits(&self, min_val: f64, max_val: f64) -> Result<(), String> {
+        if !(min_val.is_finite() && max_val.is_finite() && min_val < max_val) {
+            return Err(format!(
+                "Output limits of channel {} must be finite with min_val < max_val, got [{}, {}]",
+                self.name, min_val, max_val
+            ));
+        }
+        Ok(())
+    }
+
+    /// Physical unit of the channel values, `"V"` unless set with [`AoChannel::set_calibration`].
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    /// Declares the physical unit the channel values are given in and their conversion to volts.
+    ///
+    /// Instructions, the default and the reset value are then all taken in `unit`. Waveforms are evaluated
+    /// in this unit and [`BaseChannel::fill_signal_nsamps`] converts the samples, so the output limits stay in volts.
+    /// Values outside of the calibrated range are rejected like values outside of the output limits.
+    ///
+    /// The edit cache is kept (and checked on the next compilation), the compile cache is cleared.
     ///
     /// # Example
     ///
     /// ```
     /// # use nicompiler_backend::channel::*;
-    /// let do_channel = Channel::new(TaskType::DO, "port0/line0", 1e7, 0.);
-    /// let ao_channel = Channel::new(TaskType::AO, "ao0", 1e6, 0.);
+    /// # use nicompiler_backend::instruction::*;
+    /// # use nicompiler_backend::calibration::*;
+    /// // Coil driver with 0.5 A/V
+    /// let mut channel = AoChannel::new(0, 1e6, 0.);
+    /// channel.set_calibration("A", Calibration::new_linear(2., 0.).unwrap());
+    /// channel.add_instr(Instruction::new_linramp(0., 3., 0., 1e-3), 0., Some((1e-3, true)));
+    /// channel.compile(2000);
+    /// assert_eq!(channel.calc_signal_nsamps(1e-3, 2e-3, 10)[0], 6.);
     /// ```
+    pub fn set_calibration(&mut self, unit: &str, calibration: Calibration) {
+        self.unit = unit.to_string();
+        self.calibration = Some(calibration);
+        self.clear_compile_cache();
+    }
+
+    /// Removes the calibration, channel values are in volts again.
+    pub fn clear_calibration(&mut self) {
+        self.unit = Self::default_unit();
+        self.calibration = None;
+        self.clear_compile_cache();
+    }
+
+    fn default_min_val() -> f64 {
+        -10.0
+    }
+    fn default_max_val() -> f64 {
+        10.0
+    }
+    fn default_unit() -> String {
+        "V".to_string()
+    }
+}
+
+/// Represents a digital output line `port(number)/line(number)` on an NI device.
+///
+/// `DoChannel` provides a concrete implementation of the [`BaseChannel`] trait for digital lines.
+/// Line values are booleans, which instructions represent as `0.0` and `1.0`.
+/// Lines are not streamed individually: the parent device merges them into a [`DoPort`] during compilation.
+///
+/// # Fields
+/// - `samp_rate`: The sampling rate of the channel, determining how often the channel updates.
+/// - `fresh_compiled`: A boolean indicating whether the channel's compiled results are up-to-date with the edit cache.
+/// - `name`: A string representation of the channel's identifier as recognized by the NI driver.
+/// - `port`, `line`: Port and line numbers of the channel.
+/// - `default_value`: Line state for intervals not covered by instructions.
+/// - `reset_value`: Line state written by [`BaseChannel::add_reset_instr`], low unless set with [`DoChannel::set_reset_value`].
+/// - `instr_list`: The edit-cache for the channel. Maintains a sorted list of instruction books.
+/// - `instr_end`: Stores the ending points of compiled instructions.
+/// - `instr_val`: Holds the values of the compiled instructions.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "serialize::DoChannelRecord")
+)]
+pub struct DoChannel {
+    samp_rate: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fresh_compiled: bool,
+    name: String,
+    port: usize,
+    line: usize,
+    default_value: bool,
+    reset_value: bool,
+    instr_list: BTreeSet<InstrBook>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    instr_end: Vec<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    instr_val: Vec<Instruction>,
+}
+
+impl BaseChannel for DoChannel {
+    fn samp_rate(&self) -> f64 {
+        self.samp_rate
+    }
+    fn is_fresh_compiled(&self) -> bool {
+        self.fresh_compiled
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn default_value(&self) -> f64 {
+        if self.default_value { 1.0 } else { 0.0 }
+    }
+    fn reset_value(&self) -> f64 {
+        if self.reset_value { 1.0 } else { 0.0 }
+    }
+    fn instr_list(&self) -> &BTreeSet<InstrBook> {
+        &self.instr_list
+    }
+    fn instr_end(&self) -> &Vec<usize> {
+        &self.instr_end
+    }
+    fn instr_val(&self) -> &Vec<Instruction> {
+        &self.instr_val
+    }
+    fn instr_list_(&mut self) -> &mut BTreeSet<InstrBook> {
+        &mut self.instr_list
+    }
+    fn instr_end_(&mut self) -> &mut Vec<usize> {
+        &mut self.instr_end
+    }
+    fn instr_val_(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instr_val
+    }
+    fn fresh_compiled_(&mut self) -> &mut bool {
+        &mut self.fresh_compiled
+    }
+    fn task_type(&self) -> TaskType {
+        TaskType::DO
+    }
+}
+
+impl DoChannel {
+    /// Constructs a new `DoChannel` instance named `port(port)/line(line)`.
+    ///
+    /// # Arguments
+    /// * `port`, `line`: Port and line numbers of the channel.
+    /// * `samp_rate`: The sampling rate for the channel, determining how often the channel updates.
+    /// * `default_value`: Line state for intervals not covered by instructions.
     ///
-    pub fn new(task_type: TaskType, name: &str, samp_rate: f64, default_value: f64) -> Self {
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::channel::*;
+    /// let do_channel = DoChannel::new(0, 4, 1e7, false);
+    /// assert_eq!(do_channel.name(), "port0/line4");
+    /// assert_eq!(do_channel.default_value(), 0.);
+    /// ```
+    pub fn new(port: usize, line: usize, samp_rate: f64, default_value: bool) -> Self {
         Self {
             samp_rate,
-            task_type,
             fresh_compiled: true,
-            name: name.to_string(),
-            default_value: default_value,
+            name: format!("port{}/line{}", port, line),
+            port,
+            line,
+            default_value,
+            reset_value: false,
             instr_list: BTreeSet::new(),
             instr_end: Vec::new(),
             instr_val: Vec::new(),
         }
     }
+
+    pub fn port(&self) -> usize {
+        self.port
+    }
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Sets the line state written by [`BaseChannel::add_reset_instr`].
+    /// Reset instructions which are already in the edit cache keep their value.
+    pub fn set_reset_value(&mut self, reset_value: bool) {
+        self.reset_value = reset_value;
+    }
+}
+
+/// Compiled signal of a digital port, merged from its [`DoChannel`] lines by [`BaseDevice::compile`].
+///
+/// The `n`th bit of the port value is the state of line `n`. Ports only exist in compiled form:
+/// they are rebuilt on every compilation and are not edited directly.
+///
+/// [`BaseDevice::compile`]: crate::device::BaseDevice::compile
+pub struct DoPort {
+    port: usize,
+    instr_end: Vec<usize>,
+    instr_val: Vec<Instruction>,
+}
+
+impl DoPort {
+    pub fn new(port: usize, instr_end: Vec<usize>, instr_val: Vec<Instruction>) -> Self {
+        assert_eq!(instr_end.len(), instr_val.len());
+        Self { port, instr_end, instr_val }
+    }
+
+    pub fn port(&self) -> usize {
+        self.port
+    }
+    /// Port name as recognized by the NI driver, e.g. `port0`
+    pub fn name(&self) -> String {
+        format!("port{}", self.port)
+    }
+    pub fn instr_end(&self) -> &Vec<usize> {
+        &self.instr_end
+    }
+    pub fn instr_val(&self) -> &Vec<Instruction> {
+        &self.instr_val
+    }
+
+    /// Fills `buffer` with the port values, same as [`BaseChannel::fill_signal_nsamps`].
+    pub fn fill_signal_nsamps(
+        &self,
+        start_pos: usize,
+        end_pos: usize,
+        num_samps: usize,
+        buffer: &mut ndarray::ArrayViewMut1<f64>,
+    ) {
+        fill_compiled_signal(&self.name(), &self.instr_end, &self.instr_val, start_pos, end_pos, num_samps, buffer);
+    }
 }
 
 // ==================== Unit tests ====================
+/// Serde support, enabled by the `serde` cargo feature.
+///
+/// Channels are deserialized into records first. The settings then go through the same checks as their setters,
+/// and the instruction books are inserted one by one with the checks of [`BaseChannel::add_instr`],
+/// so overlapping books or out-of-range instructions are rejected.
+#[cfg(feature = "serde")]
+mod serialize {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(rename = "AoChannel")]
+    pub struct AoChannelRecord {
+        samp_rate: f64,
+        name: String,
+        default_value: f64,
+        reset_value: f64,
+        #[serde(default)]
+        max_slew_rate: Option<f64>,
+        #[serde(default = "AoChannel::default_min_val")]
+        min_val: f64,
+        #[serde(default = "AoChannel::default_max_val")]
+        max_val: f64,
+        #[serde(default = "AoChannel::default_unit")]
+        unit: String,
+        #[serde(default)]
+        calibration: Option<Calibration>,
+        // Not a set: books with the same start would be merged silently
+        instr_list: Vec<InstrBook>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "DoChannel")]
+    pub struct DoChannelRecord {
+        samp_rate: f64,
+        name: String,
+        port: usize,
+        line: usize,
+        default_value: bool,
+        reset_value: bool,
+        // Not a set: books with the same start would be merged silently
+        instr_list: Vec<InstrBook>,
+    }
+
+    fn check_samp_rate(name: &str, samp_rate: f64) -> Result<(), String> {
+        if !(samp_rate > 0.0 && samp_rate.is_finite()) {
+            return Err(format!("Sampling rate of channel {} must be positive and finite, got {}", name, samp_rate));
+        }
+        Ok(())
+    }
+
+    /// Inserts the books into the (empty) edit cache of `chan`, checking them like [`BaseChannel::add_instr`] does
+    fn restore_instr_list(chan: &mut impl BaseChannel, instr_list: Vec<InstrBook>) -> Result<(), String> {
+        for book in instr_list {
+            let t_start = book.start_pos as f64 / chan.samp_rate();
+            let t_end = book.end_pos().map_or(t_start, |end_pos| end_pos as f64 / chan.samp_rate());
+            chan.check_instr_values(&book.instr, t_start, t_end)?;
+            let (start_pos, end_spec) = (book.start_pos, book.end_spec);
+            let book = chan.fit_instr_book(book)?;
+            // `fit_instr_book` trims 1-tick collisions, which a consistent edit cache never has
+            if book.start_pos != start_pos || book.end_spec != end_spec {
+                return Err(format!("Instruction {} on channel {} overlaps with another one", book, chan.name()));
+            }
+            chan.instr_list_().insert(book);
+        }
+        Ok(())
+    }
+
+    impl TryFrom<AoChannelRecord> for AoChannel {
+        type Error = String;
+
+        fn try_from(record: AoChannelRecord) -> Result<Self, Self::Error> {
+            check_samp_rate(&record.name, record.samp_rate)?;
+            let mut chan = AoChannel {
+                samp_rate: record.samp_rate,
+                fresh_compiled: false,
+                name: record.name,
+                default_value: record.default_value,
+                reset_value: record.reset_value,
+                max_slew_rate: record.max_slew_rate,
+                min_val: record.min_val,
+                max_val: record.max_val,
+                unit: record.unit,
+                calibration: record.calibration,
+                instr_list: BTreeSet::new(),
+                instr_end: Vec::new(),
+                instr_val: Vec::new(),
+            };
+            chan.check_reset_value(chan.reset_value)?;
+            AoChannel::check_max_slew_rate(chan.max_slew_rate)?;
+            chan.check_output_limits(chan.min_val, chan.max_val)?;
+            restore_instr_list(&mut chan, record.instr_list)?;
+            Ok(chan)
+        }
+    }
+
+    impl TryFrom<DoChannelRecord> for DoChannel {
+        type Error = String;
+
+        fn try_from(record: DoChannelRecord) -> Result<Self, Self::Error> {
+            check_samp_rate(&record.name, record.samp_rate)?;
+            let mut chan = DoChannel {
+                samp_rate: record.samp_rate,
+                fresh_compiled: false,
+                name: record.name,
+                port: record.port,
+                line: record.line,
+                default_value: record.default_value,
+                reset_value: record.reset_value,
+                instr_list: BTreeSet::new(),
+                instr_end: Vec::new(),
+                instr_val: Vec::new(),
+            };
+            restore_instr_list(&mut chan, record.instr_list)?;
+            Ok(chan)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     mod add_instr {
@@ -757,6 +1668,147 @@ mod test {
         //     // Set samp rate to 1 MSa/s and insert 1us-wide instructions
         //     todo!()
         // }
+
+        #[test]
+        #[should_panic(expected = "Argument duration of instr type CONST must be finite")]
+        fn non_finite_duration() {
+            let mut chan = AoChannel::new(0, 1e6, 0.0);
+            chan.add_instr(Instruction::new_const(1.0), 0.0, Some((f64::NAN, false)));
+        }
+
+        #[test]
+        #[should_panic(expected = "DO channel port0/line0 only accepts values 0 and 1")]
+        fn do_values() {
+            let mut chan = DoChannel::new(0, 0, 1e6, false);
+            chan.add_instr(Instruction::new_pulse_train(0.0, 1e-3, None, None, None), 0.0, Some((1e-2, false)));
+            chan.add_instr(Instruction::new_const(0.5), 1e-2, Some((1e-2, false)));
+        }
+
+        #[test]
+        #[should_panic(expected = "DO channel port0/line0 only accepts CONST and PULSETRAIN instructions, got CLIP")]
+        fn do_instr_types() {
+            // Bounds are within [0, 1], but the values in between are not
+            let mut chan = DoChannel::new(0, 0, 1e6, false);
+            let clipped = Instruction::new_clip(Instruction::new_sine(1e3, None, None, None), Some(0.0), Some(1.0));
+            chan.add_instr(clipped, 0.0, Some((1e-2, false)));
+        }
+    }
+
+    mod introspect {
+        use crate::instruction::*;
+        use crate::channel::*;
+
+        #[test]
+        fn compiled_segments() {
+            let mut chan = AoChannel::new(0, 1e6, 0.0);
+            chan.add_instr(Instruction::new_linramp(0.0, 1.0, 1e-3, 2e-3), 1e-3, Some((1e-3, true)));
+            chan.add_instr(Instruction::new_sine(1e3, None, None, None).with_time_ref(TimeRef::Local), 3e-3, Some((1e-3, false)));
+            chan.compile(5000);
+
+            let segments: Vec<(usize, usize)> = chan
+                .compiled_segments(0.0, f64::INFINITY)
+                .iter()
+                .map(|(start_pos, end_pos, _instr)| (*start_pos, *end_pos))
+                .collect();
+            // Leading padding, ramp, `keep_val` padding, sine, default padding up to the stop position
+            assert_eq!(segments.first(), Some(&(0, 1000)));
+            assert_eq!(segments[1], (1000, 2000));
+            assert_eq!(segments.last().unwrap().1, *chan.instr_end().last().unwrap());
+            assert!(segments.windows(2).all(|pair| pair[0].1 == pair[1].0));
+
+            // A zero-length window picks the segment covering it, listed in resolved form
+            let window = chan.compiled_segments(3.5e-3, 3.5e-3);
+            assert_eq!(window.len(), 1);
+            assert_eq!((window[0].0, window[0].1), (3000, 4000));
+            assert!(window[0].2.time_ref == TimeRef::Absolute);
+            assert!(window[0].2.eval_point(3e-3).abs() < 1e-12);
+        }
+
+        #[test]
+        #[should_panic(expected = "not-compiled channel ao0")]
+        fn not_compiled() {
+            let mut chan = AoChannel::new(0, 1e6, 0.0);
+            chan.constant(1.0, 0.0, Some((1e-3, false)));
+            chan.compiled_segments(0.0, 1.0);
+        }
+    }
+
+    mod edit_instr {
+        use crate::instruction::*;
+        use crate::channel::*;
+
+        #[test]
+        fn remove_replace() {
+            let mut chan = AoChannel::new(0, 1e6, 0.0);
+            chan.constant(1.0, 0.0, Some((1e-3, false)));
+            chan.add_instr(Instruction::new_sine(1e3, None, None, None), 2e-3, None);
+
+            // Instructions with a duration cover [start, end), without one - everything until the next instruction
+            assert!(chan.instr_at(1e-3).is_none());
+            assert_eq!(chan.instr_at(5.0).unwrap().start_pos, 2000);
+
+            chan.replace_instr(0.5e-3, Instruction::new_const(2.0));
+            let book = chan.instr_at(0.0).unwrap();
+            assert_eq!(book.end_spec, Some((1000, false)));
+            assert!(book.instr == Instruction::new_const(2.0));
+
+            let removed = chan.remove_instr(2e-3);
+            assert_eq!(removed.start_pos, 2000);
+            assert_eq!(chan.instr_list().len(), 1);
+        }
+
+        #[test]
+        #[should_panic(expected = "has no instruction starting at or covering t = 0.0015 s")]
+        fn remove_missing() {
+            let mut chan = AoChannel::new(0, 1e6, 0.0);
+            chan.constant(1.0, 0.0, Some((1e-3, false)));
+            chan.remove_instr(1.5e-3);
+        }
+
+        #[test]
+        fn move_ramp() {
+            let mut chan = AoChannel::new(0, 1e6, 0.0);
+            chan.add_instr(Instruction::new_linramp(0.0, 1.0, 0.0, 1e-3), 0.0, Some((1e-3, true)));
+            chan.move_instr(0.0, 2e-3);
+
+            assert!(chan.instr_at(0.5e-3).is_none());
+            let book = chan.instr_at(2e-3).unwrap();
+            assert_eq!((book.start_pos, book.end_spec), (2000, Some((3000, true))));
+            assert!(book.instr.instr_type == InstrType::LINRAMP);
+            assert!((book.instr.eval_point(2.5e-3) - 0.5).abs() < 1e-12);
+        }
+
+        #[test]
+        fn move_pulse_train() {
+            let mut chan = DoChannel::new(0, 0, 1e6, false);
+            chan.add_instr(Instruction::new_pulse_train(0.0, 1e-4, Some(0.5), None, None), 0.0, Some((1e-3, false)));
+            chan.move_instr(0.0, 2e-3);
+
+            let book = chan.instr_at(2e-3).unwrap();
+            assert_eq!((book.start_pos, book.end_spec), (2000, Some((3000, false))));
+            assert!(book.instr.instr_type == InstrType::PULSETRAIN);
+            assert_eq!(book.instr.eval_point(2e-3), 1.0);
+            assert_eq!(book.instr.eval_point(2.05e-3), 0.0);
+        }
+
+        #[test]
+        fn move_collision() {
+            let mut chan = DoChannel::new(0, 0, 1e6, false);
+            chan.constant(1.0, 0.0, Some((1e-3, false)));
+            chan.constant(1.0, 5e-3, Some((1e-3, false)));
+
+            // 1-tick collisions are trimmed as in `add_instr`
+            chan.move_instr(0.0, 4e-3 + 1e-6);
+            assert_eq!(chan.instr_at(4.5e-3).unwrap().end_spec, Some((5000, false)));
+            chan.move_instr(4.5e-3, 0.0);
+            chan.compile(10_000);
+
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| chan.move_instr(0.0, 4.5e-3)));
+            assert!(result.is_err());
+            let start_pos: Vec<usize> = chan.instr_list().iter().map(|book| book.start_pos).collect();
+            assert_eq!(start_pos, vec![0, 5000]);
+            assert!(chan.is_fresh_compiled());
+        }
     }
 
     mod misc {
@@ -765,7 +1817,7 @@ mod test {
 
         #[test]
         fn last_instr_end_pos() {
-            let mut my_chan = Channel::new(TaskType::AO, "ao0", 1e6, 0.0);
+            let mut my_chan = AoChannel::new(0, 1e6, 0.0);
             let mock_func = Instruction::new_const(1.23);
 
             // No instructions
@@ -786,10 +1838,25 @@ mod test {
             my_chan.clear_edit_cache();
             assert_eq!(my_chan.last_instr_end_pos(), 0);
         }
+
+        #[test]
+        fn reset_value() {
+            let mut ao_chan = AoChannel::new(0, 1e6, 1.0);
+            ao_chan.set_reset_value(-2.5);
+            ao_chan.add_reset_instr(10);
+            ao_chan.compile(20);
+            assert_eq!(ao_chan.instr_val().last().unwrap().eval_point(0.0), -2.5);
+
+            let mut do_chan = DoChannel::new(0, 0, 1e6, false);
+            assert_eq!(do_chan.reset_value(), 0.0);
+            do_chan.set_reset_value(true);
+            assert_eq!(do_chan.reset_value(), 1.0);
+        }
     }
 
     mod compile {
         use crate::instruction::*;
+        use crate::calibration::*;
         use crate::channel::*;
 
         #[test]
@@ -798,7 +1865,7 @@ mod test {
             // If there is no gap, no padding instruction should be inserted.
 
             let chan_dflt = -10.0;
-            let mut my_chan = Channel::new(TaskType::AO, "ao0", 1e6, chan_dflt);
+            let mut my_chan = AoChannel::new(0, 1e6, chan_dflt);
 
             // Finite gap
             my_chan.add_instr(
@@ -809,7 +1876,7 @@ mod test {
             assert_eq!(my_chan.instr_end()[0], 1000000);
             assert!(my_chan.instr_val()[0].instr_type == InstrType::CONST);
             assert!({
-                let &pad_val = my_chan.instr_val()[0].args.get("value").unwrap();
+                let &pad_val = my_chan.instr_val()[0].args().get("value").unwrap();
                 // Check for float equality with caution
                 (pad_val - chan_dflt).abs() < 1e-10
             });
@@ -832,7 +1899,7 @@ mod test {
             // Otherwise, channel default value is kept.
 
             let chan_dflt = -10.0;
-            let mut my_chan = Channel::new(TaskType::AO, "ao0", 1e6, chan_dflt);
+            let mut my_chan = AoChannel::new(0, 1e6, chan_dflt);
 
             // Convenience variables
             let freq = 0.12;
@@ -848,7 +1915,7 @@ mod test {
             let pad_func = my_chan.instr_val()[1].clone();
             assert!(pad_func.instr_type == InstrType::CONST);
             assert!({
-                let &actual_pad_val = pad_func.args.get("value").unwrap();
+                let &actual_pad_val = pad_func.args().get("value").unwrap();
                 let expected_pad_val = my_chan.instr_val[0].eval_point(pulse_dur);
                 (actual_pad_val - expected_pad_val).abs() < 1e-10
             });
@@ -863,11 +1930,166 @@ mod test {
             let pad_func = my_chan.instr_val()[1].clone();
             assert!(pad_func.instr_type == InstrType::CONST);
             assert!({
-                let &actual_pad_val = pad_func.args.get("value").unwrap();
+                let &actual_pad_val = pad_func.args().get("value").unwrap();
                 (actual_pad_val - chan_dflt).abs() < 1e-10
             });
         }
 
+        #[test]
+        fn time_ref() {
+            let mut my_chan = AoChannel::new(0, 1e6, 0.0);
+            let freq = 1e3;
+            let sine = |time_ref: TimeRef| {
+                Instruction::new_sine(freq, None, None, None).with_time_ref(time_ref)
+            };
+
+            // Local: starts at phase 0 wherever it is placed
+            my_chan.add_instr(sine(TimeRef::Local), 0.2e-3, Some((0.5e-3, false)));
+            // Continuous: picks up the phase of the previous sine across the gap
+            my_chan.add_instr(sine(TimeRef::Continuous), 1.0e-3, Some((0.5e-3, false)));
+            my_chan.compile(2000);
+            assert!(my_chan.instr_val()[1].time_ref == TimeRef::Absolute);
+
+            assert_eq!(my_chan.instr_end(), &vec![200, 700, 1000, 1500, 2000]);
+            assert!(my_chan.instr_val()[1].eval_point(0.2e-3).abs() < 1e-9);
+            let expected = (2.0 * std::f64::consts::PI * freq * (1.0e-3 - 0.2e-3)).sin();
+            assert!((my_chan.instr_val()[3].eval_point(1.0e-3) - expected).abs() < 1e-9);
+
+            // Back-to-back continuous sines are merged into one segment
+            my_chan.clear_edit_cache();
+            my_chan.add_instr(sine(TimeRef::Local), 0.2e-3, Some((0.5e-3, false)));
+            my_chan.add_instr(sine(TimeRef::Continuous), 0.7e-3, Some((0.5e-3, false)));
+            my_chan.compile(2000);
+            assert_eq!(my_chan.instr_end(), &vec![200, 1200, 2000]);
+        }
+
+        #[test]
+        fn slew_rate() {
+            let mut my_chan = AoChannel::new(0, 1e6, 0.0);
+            my_chan.set_max_slew_rate(Some(1e4));
+            my_chan.add_instr(Instruction::new_linramp(0.0, 5.0, 0.0, 1e-3).with_from_current(), 0.0, Some((1e-3, true)));
+            my_chan.add_instr(Instruction::new_sine(1e3, Some(1.0), None, None).with_from_current(), 1e-3, Some((1e-3, true)));
+            // No analytic slope bound, checked sample by sample
+            let mut params = InstrArgs::new();
+            params.insert("f".to_string(), 1e3);
+            let expr = Instruction::new_expr("sin(2 * pi * f * t)", params).unwrap();
+            my_chan.add_instr(Instruction::new_offset(Instruction::new_product(vec![expr.clone(), expr]), 5.0), 2e-3, None);
+            my_chan.compile(3000);
+            assert!(my_chan.is_fresh_compiled());
+
+            // A step to the reset value is too fast
+            my_chan.add_reset_instr(3000);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_chan.compile(3001)));
+            assert!(result.is_err());
+            assert!(!my_chan.is_compiled());
+        }
+
+        #[test]
+        #[should_panic(expected = "Channel ao0 exceeds its maximum slew rate of 10000 V/s")]
+        fn slew_rate_sampled() {
+            let mut my_chan = AoChannel::new(0, 1e6, 0.0);
+            my_chan.set_max_slew_rate(Some(1e4));
+            let expr = Instruction::new_expr("t^2 * 1e7", InstrArgs::new()).unwrap();
+            my_chan.add_instr(expr, 0.0, Some((1e-3, true)));
+            my_chan.compile(1000);
+        }
+
+        #[test]
+        fn slew_rate_calibrated() {
+            // 0.1 V per unit: the 5e4 units/s ramp is 5e3 V/s at the output
+            let mut my_chan = AoChannel::new(0, 1e6, 0.0);
+            my_chan.set_calibration("MHz", Calibration::new_linear(0.1, 0.0).unwrap());
+            my_chan.set_max_slew_rate(Some(1e4));
+            my_chan.add_instr(Instruction::new_linramp(0.0, 50.0, 0.0, 1e-3), 0.0, Some((1e-3, true)));
+            my_chan.compile(1000);
+            assert!(my_chan.is_fresh_compiled());
+
+            // Steep table section between 40 and 50: 1 V per unit, the ramp end is too fast
+            my_chan.set_calibration("MHz", Calibration::new_table(vec![0.0, 40.0, 50.0], vec![0.0, 4.0, 14.0]).unwrap());
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_chan.compile(1000)));
+            assert!(result.is_err());
+            assert!(!my_chan.is_compiled());
+        }
+
+        #[test]
+        fn output_limits() {
+            let mut my_chan = AoChannel::new(0, 1e6, 0.0);
+            my_chan.set_output_limits(-1.0, 2.0);
+            let add_instr = |chan: &mut AoChannel, instr: Instruction, t: f64| {
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| chan.add_instr(instr, t, Some((1e-3, true)))))
+            };
+            // Rejected at edit time from the analytic bounds
+            assert!(add_instr(&mut my_chan, Instruction::new_linramp(0.0, 3.0, 0.0, 1e-3), 0.0).is_err());
+            assert!(add_instr(&mut my_chan, Instruction::new_linramp(0.0, 2.0, 0.0, 1e-3), 0.0).is_ok());
+
+            // Bounds of a sine starting from the current value are only known once compiled
+            my_chan.add_instr(Instruction::new_sine(1e3, Some(0.5), None, None).with_from_current(), 1e-3, None);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_chan.compile(2000)));
+            assert!(result.is_err());
+            assert!(!my_chan.is_compiled());
+
+            // Without analytic bounds, the samples are checked
+            my_chan.clear_edit_cache();
+            let expr = Instruction::new_expr("3 * t / 1e-3", InstrArgs::new()).unwrap();
+            assert!(add_instr(&mut my_chan, expr, 0.0).is_ok());
+            my_chan.compile(1000);
+            let mut buffer = ndarray::Array1::linspace(0.0, 1e-3, 1000);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                my_chan.fill_signal_nsamps(0, 1000, 1000, &mut buffer.view_mut())
+            }));
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn calibration() {
+            let mut my_chan = AoChannel::new(0, 1e6, 80.0);
+            my_chan.set_calibration("MHz", Calibration::new_table(vec![80.0, 100.0, 120.0], vec![0.5, 1.5, 4.5]).unwrap());
+            my_chan.set_output_limits(0.0, 4.0);
+            assert_eq!(my_chan.unit(), "MHz");
+            let add_instr = |chan: &mut AoChannel, instr: Instruction| {
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| chan.add_instr(instr, 0.0, Some((1e-3, true)))))
+            };
+            // Outside of the calibrated range
+            assert!(add_instr(&mut my_chan, Instruction::new_linramp(70.0, 100.0, 0.0, 1e-3)).is_err());
+            // Calibrated, but 120 MHz needs 4.5 V
+            assert!(add_instr(&mut my_chan, Instruction::new_linramp(80.0, 120.0, 0.0, 1e-3)).is_err());
+            assert!(add_instr(&mut my_chan, Instruction::new_linramp(80.0, 110.0, 0.0, 1e-3)).is_ok());
+
+            my_chan.compile(2000);
+            let signal = my_chan.calc_signal_nsamps(0.0, 2e-3, 2000);
+            assert_eq!(signal[0], 0.5);
+            // 80 + 30 MHz/ms ramp, 0.05 V/MHz up to 100 MHz
+            let t = 500.0 * 2e-3 / 1999.0;
+            assert!((signal[500] - (0.5 + 0.05 * 30.0 * t / 1e-3)).abs() < 1e-9);
+            assert!((signal[1999] - 3.0).abs() < 1e-9);
+
+            // The default value is in MHz now as well
+            my_chan.clear_calibration();
+            assert_eq!(my_chan.unit(), "V");
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_chan.compile(2000)));
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn from_current() {
+            let mut my_chan = AoChannel::new(0, 1e6, 0.5);
+            // First instruction starts from the channel default
+            my_chan.add_instr(Instruction::new_linramp(0.0, 2.0, 0.0, 1e-3).with_from_current(), 0.0, Some((1e-3, true)));
+            // Sine after keep_val padding picks up the ramp end value
+            let sine = Instruction::new_sine(1e3, Some(0.3), Some(0.4), None).with_time_ref(TimeRef::Local).with_from_current();
+            my_chan.add_instr(sine, 1.5e-3, Some((0.25e-3, false)));
+            // Ramp after default padding starts from the default
+            my_chan.add_instr(Instruction::new_linramp(0.0, -1.0, 2e-3, 3e-3).with_from_current(), 2e-3, None);
+            my_chan.compile(4000);
+
+            assert_eq!(my_chan.instr_end(), &vec![1000, 1500, 1750, 2000, 4000]);
+            assert!(my_chan.instr_val().iter().all(|instr| !instr.from_current));
+            assert_eq!(my_chan.instr_val()[0].eval_point(0.0), 0.5);
+            assert!((my_chan.instr_val()[2].eval_point(1.5e-3) - 2.0).abs() < 1e-12);
+            assert_eq!(my_chan.instr_val()[4].eval_point(2e-3), 0.5);
+            assert_eq!(my_chan.instr_val()[4].eval_point(3e-3), -1.0);
+        }
+
         // #[test]
         // fn pad_go_something() {
         //     todo!()
@@ -883,4 +2105,49 @@ mod test {
         //     todo!()
         // }
     }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use crate::channel::*;
+
+        #[test]
+        fn edit_cache_round_trip() {
+            let mut chan = AoChannel::new(0, 1e6, 0.5);
+            chan.add_instr(Instruction::new_linramp(0.0, 1.0, 0.0, 1e-3), 0.0, Some((1e-3, true)));
+            chan.add_instr(Instruction::new_sine(1e3, None, None, None), 2e-3, None);
+            chan.compile(5000);
+
+            let restored: AoChannel = serde_json::from_str(&serde_json::to_string(&chan).unwrap()).unwrap();
+            assert!(restored.instr_list().iter().eq(chan.instr_list().iter()));
+            assert!(restored.instr_list().iter().zip(chan.instr_list()).all(|(a, b)| a.instr == b.instr && a.end_spec == b.end_spec));
+            assert!(!restored.is_compiled());
+        }
+
+        #[test]
+        fn invalid_edit_cache() {
+            let mut chan = AoChannel::new(0, 1e6, 0.0);
+            chan.add_instr(Instruction::new_const(1.0), 0.0, Some((1e-3, false)));
+            chan.add_instr(Instruction::new_const(2.0), 2e-3, Some((1e-3, false)));
+            let json = serde_json::to_string(&chan).unwrap();
+
+            // Overlapping and zero-length books
+            let err = serde_json::from_str::<AoChannel>(&json.replace("\"start_pos\":2000", "\"start_pos\":500")).err().unwrap();
+            assert!(err.to_string().contains("Collision"));
+            let err = serde_json::from_str::<AoChannel>(&json.replace("\"start_pos\":2000", "\"start_pos\":3000")).err().unwrap();
+            assert!(err.to_string().contains("start_pos + 1 <= end_pos"));
+            // Books with the same start are not merged
+            let err = serde_json::from_str::<AoChannel>(&json.replace("\"start_pos\":2000", "\"start_pos\":0")).err().unwrap();
+            assert!(err.to_string().contains("collides on the right"));
+            // Output limits and instruction values
+            let err = serde_json::from_str::<AoChannel>(&json.replace("\"min_val\":-10.0", "\"min_val\":20.0")).err().unwrap();
+            assert!(err.to_string().contains("min_val < max_val"));
+            let err = serde_json::from_str::<AoChannel>(&json.replace("\"max_val\":10.0", "\"max_val\":1.5")).err().unwrap();
+            assert!(err.to_string().contains("outside of the output limits"));
+
+            let mut chan = DoChannel::new(0, 0, 1e6, false);
+            chan.add_instr(Instruction::new_const(1.0), 0.0, Some((1e-3, false)));
+            let json = serde_json::to_string(&chan).unwrap();
+            assert!(serde_json::from_str::<DoChannel>(&json.replace("\"value\":1.0", "\"value\":0.5")).is_err());
+        }
+    }
 }
@@ -20,11 +20,11 @@
 //! For editing and compiling behavior of devices, see the [`BaseDevice`] trait.
 //!
 //!
-//! ### Editable and streamable channels in devices
-//! Library users create and edit editable channels. During compilation, based on the device's task type,
-//! the library may internally add streamable channels.
-//! For more details on editable and streamable channels, see the editable v.s. streamable section in
-//! [`channel` module].
+//! ### Channels and ports in devices
+//! AO devices hold [`AoChannel`]s and DO devices hold [`DoChannel`] lines. Library users create and edit these channels.
+//! During compilation, DO devices additionally merge their lines into [`DoPort`]s, which are kept separately
+//! and are what gets streamed to the driver.
+//! For more details, see the digital lines and ports section in [`channel` module].
 //!
 //! ### Synchronization methods for devices
 //! Each device's synchronization behavior is specified by its constructor arguments.
@@ -33,13 +33,11 @@
 //! [`channel` module]: crate::channel
 
 use ndarray::{s, Array1, Array2};
-use regex::Regex;
 use std::collections::BTreeSet;
 use indexmap::IndexMap;
 
 use crate::channel::*;
 use crate::instruction::*;
-use crate::utils::*;
 
 /// The `BaseDevice` trait defines the fundamental operations and attributes of a National Instruments (NI) device.
 ///
@@ -57,8 +55,8 @@ use crate::utils::*;
 /// - **Synchronization configuration**: Customize the synchronization behavior of devices via [`BaseDevice::cfg_trig`],
 /// [`BaseDevice::cfg_ref_clk`], [`BaseDevice::cfg_samp_clk_src`]. See [`Device`] for more details.
 ///
-/// - **Channel management**: Methods like [`BaseDevice::editable_channels`], [`BaseDevice::editable_channels_`], and
-/// [`BaseDevice::add_channel`] allow for the retrieval and manipulation of channels associated with the device.
+/// - **Channel management**: Methods like [`BaseDevice::channels`], [`BaseDevice::chan_`], [`BaseDevice::add_ao_channel`]
+/// and [`BaseDevice::add_do_channel`] allow for the retrieval and manipulation of channels associated with the device.
 ///
 /// - **Device status checks**: Methods like [`BaseDevice::is_compiled`], [`BaseDevice::is_edited`], and
 /// [`BaseDevice::is_fresh_compiled`] enable checking the compilation and editing status of the device's channels.
@@ -75,6 +73,7 @@ use crate::utils::*;
 ///
 /// - **Utility functions**: Methods like [`BaseDevice::unique_port_numbers`] offer utility functionalities specific to certain
 /// task types, aiding in operations like identifying unique ports in Digital Output (DO) devices.
+/// [`BaseDevice::streamed_channel_names`] lists the channels (AO) or ports (DO) written to the driver.
 ///
 ///
 /// # Implementing [`BaseDevice`]:
@@ -92,27 +91,77 @@ pub trait BaseDevice {
     // ToDo: this is a temporary dirty fix. Remove after crate merge
 
     // Immutable accessors (getters)
-    fn channels(&self) -> &IndexMap<String, Channel>;
+    fn ao_channels(&self) -> &IndexMap<String, AoChannel>;
+    fn do_channels(&self) -> &IndexMap<String, DoChannel>;
+    /// Ports merged from the DO lines by the last [`BaseDevice::compile`] call
+    fn do_ports(&self) -> &IndexMap<usize, DoPort>;
     fn name(&self) -> &str;
     fn task_type(&self) -> TaskType;
     fn samp_rate(&self) -> f64;
 
     // Mutable accessors
-    fn channels_(&mut self) -> &mut IndexMap<String, Channel>;
+    fn ao_channels_(&mut self) -> &mut IndexMap<String, AoChannel>;
+    fn do_channels_(&mut self) -> &mut IndexMap<String, DoChannel>;
+    fn do_ports_(&mut self) -> &mut IndexMap<usize, DoPort>;
+
+    /// Returns the channels of the device (AO channels or DO lines, depending on the task type) in the order they were added
+    fn channels(&self) -> Vec<&dyn BaseChannel> {
+        match self.task_type() {
+            TaskType::AO => self.ao_channels().values().map(|chan| chan as &dyn BaseChannel).collect(),
+            TaskType::DO => self.do_channels().values().map(|chan| chan as &dyn BaseChannel).collect(),
+        }
+    }
+    /// Mutable version of [`BaseDevice::channels`]
+    fn channels_(&mut self) -> Vec<&mut dyn BaseChannel> {
+        match self.task_type() {
+            TaskType::AO => self.ao_channels_().values_mut().map(|chan| chan as &mut dyn BaseChannel).collect(),
+            TaskType::DO => self.do_channels_().values_mut().map(|chan| chan as &mut dyn BaseChannel).collect(),
+        }
+    }
+    /// Names of the channels of the device
+    fn chan_names(&self) -> Vec<String> {
+        self.channels().iter().map(|chan| chan.name().to_string()).collect()
+    }
+    fn has_chan(&self, name: &str) -> bool {
+        match self.task_type() {
+            TaskType::AO => self.ao_channels().contains_key(name),
+            TaskType::DO => self.do_channels().contains_key(name),
+        }
+    }
 
     /// Shortcut to borrow channel instance by name
-    fn chan(&self, name: &str) -> &Channel {
-        if !self.channels().contains_key(name) {
+    fn chan(&self, name: &str) -> &dyn BaseChannel {
+        if !self.has_chan(name) {
             panic!("Device {} does not have channel {}", self.name(), name)
         }
-        self.channels().get(name).unwrap()
+        match self.task_type() {
+            TaskType::AO => self.ao_channels().get(name).unwrap(),
+            TaskType::DO => self.do_channels().get(name).unwrap(),
+        }
     }
     /// Shortcut to mutably borrow channel instance by name
-    fn chan_(&mut self, name: &str) -> &mut Channel {
-        if !self.channels().contains_key(name) {
+    fn chan_(&mut self, name: &str) -> &mut dyn BaseChannel {
+        if !self.has_chan(name) {
             panic!("Device {} does not have channel {}", self.name(), name)
         }
-        self.channels_().get_mut(name).unwrap()
+        match self.task_type() {
+            TaskType::AO => self.ao_channels_().get_mut(name).unwrap(),
+            TaskType::DO => self.do_channels_().get_mut(name).unwrap(),
+        }
+    }
+    /// Shortcut to mutably borrow AO channel instance by name
+    fn ao_chan_(&mut self, name: &str) -> &mut AoChannel {
+        if !self.ao_channels().contains_key(name) {
+            panic!("Device {} does not have AO channel {}", self.name(), name)
+        }
+        self.ao_channels_().get_mut(name).unwrap()
+    }
+    /// Shortcut to mutably borrow DO channel instance by name
+    fn do_chan_(&mut self, name: &str) -> &mut DoChannel {
+        if !self.do_channels().contains_key(name) {
+            panic!("Device {} does not have DO channel {}", self.name(), name)
+        }
+        self.do_channels_().get_mut(name).unwrap()
     }
 
     /// Returns sample clock period calculated as `1.0 / self.samp_rate()`
@@ -120,72 +169,48 @@ pub trait BaseDevice {
         1.0 / self.samp_rate()
     }
 
-    /// Returns a vector of references to editable channels
-    fn editable_channels(&self) -> Vec<&Channel> {
-        self.channels()
-            .values()
-            .filter(|&chan| chan.editable())
-            .collect()
-    }
-    /// Returns a vector of mutable references to editable channels
-    fn editable_channels_(&mut self) -> Vec<&mut Channel> {
-        self.channels_()
-            .values_mut()
-            .filter(|chan| (*chan).editable())
-            .collect()
+    fn assert_new_chan(&self, name: &str) {
+        if self.has_chan(name) {
+            panic!(
+                "Physical name of channel {} already registered. Registered channels are {:?}",
+                name,
+                self.chan_names()
+            );
+        }
     }
 
-    /// Adds a new channel to the device.
+    /// Adds a new AO channel `ao(id)` to the device.
     ///
-    /// This base method validates the provided `name` based on the device's `task_type`
-    /// to ensure it adheres to the expected naming convention for the respective task type.
+    /// # Panics
+    /// - If the device is not of task type `AO`.
+    /// - If a channel with the same name already exists within the device.
     ///
-    /// # Naming Conventions:
-    /// - For `TaskType::AO`: Channels should be named following the pattern "ao(number)"
-    ///   (e.g., "ao0", "ao1").
-    /// - For `TaskType::DO`: Channels should be named following the pattern "port(number)/line(number)"
-    ///   (e.g., "port0/line1").
+    /// # Arguments
+    /// - `id`: Channel number, the channel is named `ao(id)` as seen by the NI driver.
+    /// - `default_value`: a f64 value which specifies signal value for not explicitly defined intervals.
+    fn add_ao_channel(&mut self, id: usize, default_value: f64) {
+        assert!(self.task_type() == TaskType::AO, "Cannot add AO channel ao{} to DO device {}", id, self.name());
+        let new_channel = AoChannel::new(id, self.samp_rate(), default_value);
+        self.assert_new_chan(new_channel.name());
+        self.ao_channels_().insert(new_channel.name().to_string(), new_channel);
+    }
+    /// Adds a new DO line `port(port)/line(line)` to the device.
     ///
     /// # Panics
-    /// - If the provided `name` does not adhere to the expected naming convention for the
-    ///   associated task type.
-    /// - If a channel with the same `name` already exists within the device.
+    /// - If the device is not of task type `DO`.
+    /// - If a channel with the same name already exists within the device.
     ///
     /// # Arguments
-    /// - `name`: Name of the channel as seen by the NI driver, which must adhere to the
-    ///   naming conventions detailed above.
-    /// - `default_value`: a f64 value which specifies signal value for not explicitly defined intervals. 
-    fn add_channel(&mut self, name: &str, default_value: f64) {
-        // Check the name format
-        let (name_match_string, name_format_description) = match self.task_type() {
-            TaskType::AO => (String::from(r"^ao\d+$"), String::from("ao(number)")),
-            TaskType::DO => (
-                String::from(r"^port\d+/line\d+$"),
-                String::from("port(number)/line(number)"),
-            ),
-        };
-
-        let re = Regex::new(&name_match_string).unwrap();
-        if !re.is_match(name) {
-            panic!(
-                "Expecting channels to be of format '{}' yet received channel name {}",
-                name_format_description, name
-            );
-        }
-        for channel in self.channels().values() {
-            if channel.name() == name {
-                panic!(
-                    "Physical name of channel {} already registered. Registered channels are {:?}",
-                    name,
-                    self.channels()
-                        .values()
-                        .map(|c| c.name())
-                        .collect::<Vec<_>>()
-                );
-            }
-        }
-        let new_channel = Channel::new(self.task_type(), name, self.samp_rate(), default_value);
-        self.channels_().insert(name.to_string(), new_channel);
+    /// - `port`, `line`: Port and line numbers of the channel as seen by the NI driver.
+    /// - `default_value`: line state for not explicitly defined intervals.
+    fn add_do_channel(&mut self, port: usize, line: usize, default_value: bool) {
+        assert!(
+            self.task_type() == TaskType::DO,
+            "Cannot add DO channel port{}/line{} to AO device {}", port, line, self.name()
+        );
+        let new_channel = DoChannel::new(port, line, self.samp_rate(), default_value);
+        self.assert_new_chan(new_channel.name());
+        self.do_channels_().insert(new_channel.name().to_string(), new_channel);
     }
 
     fn add_reset_instr(&mut self, reset_time: f64) {
@@ -197,49 +222,45 @@ pub trait BaseDevice {
                 self.last_instr_end_pos()
             )
         }
-        for chan in self.editable_channels_().iter_mut() {  // ToDo when splitting AO/DO types: remove `editable` filter
+        for chan in self.channels_() {
             chan.add_reset_instr(reset_pos)
         }
     }
 
-    /// A device is compiled if any of its editable channels are compiled.
+    /// A device is compiled if any of its channels are compiled.
     /// Also see [`BaseChannel::is_compiled`]
     fn is_compiled(&self) -> bool {
-        self.editable_channels()
+        self.channels()
             .iter()
             .any(|channel| channel.is_compiled())
     }
-    /// A device is marked edited if any of its editable channels are edited.
+    /// A device is marked edited if any of its channels are edited.
     /// Also see [`BaseChannel::is_edited`]
     fn is_edited(&self) -> bool {
-        self.editable_channels()
+        self.channels()
             .iter()
             .any(|channel| channel.is_edited())
     }
-    /// A device is marked fresh-compiled if all if its editable channels are freshly compiled.
+    /// A device is marked fresh-compiled if all if its channels are freshly compiled.
     /// Also see [`BaseChannel::is_fresh_compiled`]
     fn is_fresh_compiled(&self) -> bool {
-        self.editable_channels()
+        self.channels()
             .iter()
             .all(|channel| channel.is_fresh_compiled())
     }
     /// Clears the edit-cache fields for all channels.
     /// Also see [`BaseChannel::clear_edit_cache`]
     fn clear_edit_cache(&mut self) {
-        // Remove all made-up "port" channels
-        self.channels_().retain(|_name, chan| chan.editable());
-
-        for chan in self.channels_().values_mut() {
+        self.do_ports_().clear();
+        for chan in self.channels_() {
             chan.clear_edit_cache()
         }
     }
     /// Clears the compile-cache fields for all channels.
     /// Also see [`BaseChannel::clear_compile_cache`]
     fn clear_compile_cache(&mut self) {
-        // Remove all made-up "port" channels
-        self.channels_().retain(|_name, chan| chan.editable());
-
-        for chan in self.channels_().values_mut() {
+        self.do_ports_().clear();
+        for chan in self.channels_() {
             chan.clear_compile_cache()
         }
     }
@@ -250,8 +271,7 @@ pub trait BaseDevice {
                    self.last_instr_end_pos())
         }
         self.channels()
-            .values()
-            .filter(|chan| chan.editable())  // ToDo when splitting AO/DO types: remove `editable()` filter
+            .iter()
             .filter(|chan| !chan.instr_list().is_empty())
             .any(|chan| {
                 let last_instr = chan.instr_list().last().unwrap();
@@ -262,22 +282,25 @@ pub trait BaseDevice {
             })
     }
 
-    /// Compiles all editable channels to produce a continuous instruction stream.
+    /// Compiles all channels to produce a continuous instruction stream.
     ///
-    /// The method starts by compiling each individual editable channel to obtain a continuous
+    /// The method starts by compiling each individual channel to obtain a continuous
     /// stream of instructions (also see[`BaseChannel::compile`]).
     /// If the device type is `TaskType::DO` (Digital Output), an additional
     /// processing step is performed. All the line channels belonging to the same port are merged
-    /// into a single, streamable port channel that is non-editable. This aggregated port channel
-    /// contains constant instructions whose integer values are determined by the combined state
+    /// into a single [`DoPort`], stored in [`BaseDevice::do_ports`]. The port
+    /// contains instructions whose integer values are determined by the combined state
     /// of all the lines of the corresponding port. Specifically, the `n`th bit of the integer
     /// value of the instruction corresponds to the boolean state of the `n`th line.
     ///
-    /// # Port Channel Aggregation
-    /// Each instruction inside the aggregated port channel is a constant instruction. The value of
-    /// this instruction is an integer, where its `n`th bit represents the boolean state of the
-    /// `n`th line. This way, the combined state of all lines in a port is efficiently represented
-    /// by a single integer value, allowing for streamlined execution and efficient data transfer.
+    /// # Port Aggregation
+    /// Each instruction inside the aggregated port evaluates to an integer, where its `n`th bit
+    /// represents the boolean state of the `n`th line. This way, the combined state of all lines in a port
+    /// is efficiently represented by a single integer value, allowing for streamlined execution and efficient data transfer.
+    ///
+    /// Where all lines are constant, the port instruction is a `CONST`. Where some lines run a `PULSETRAIN`,
+    /// the port instruction is the constant part plus the sum of the pulse trains scaled by their bit weights,
+    /// so a periodic segment is merged in one piece instead of being split at every edge.
     ///
     /// # Arguments
     /// - `stop_time`: The stop time used to compile the channels.
@@ -309,59 +332,68 @@ pub trait BaseDevice {
             stop_tick
         };
         // Compile all channels
-        for chan in self.editable_channels_() {
+        for chan in self.channels_() {
             chan.compile(stop_pos)
         };
 
-        // For DO channels: merge line channels into port channels
+        // For DO channels: merge line channels into ports
         if self.task_type() == TaskType::DO {
-            // Remove all made-up "port" channels left from the previous compile run
-            //  (although all port channels with new instructions coming would be replaced anyways during `self.channels_().insert()`,
-            //  this step cleans out any old port channels for which there are no instructions this time)
-            self.channels_().retain(|_name, chan| chan.editable());
+            // Drop the ports left from the previous compile run, including those without instructions this time
+            self.do_ports_().clear();
 
             for match_port in self.unique_port_numbers() {
                 // Collect a sorted list of possible intervals
                 let mut instr_end_set = BTreeSet::new();
                 instr_end_set.extend(
-                    self.editable_channels()
-                        .iter()
-                        .filter(|chan| chan.is_edited() && extract_port_line_numbers(chan.name()).0 == match_port)
+                    self.do_channels()
+                        .values()
+                        .filter(|chan| chan.is_edited() && chan.port() == match_port)
                         .flat_map(|chan| chan.instr_end().iter()),
                 );
                 let instr_end: Vec<usize> = instr_end_set.into_iter().collect();
 
+                // Per interval: the constant part of the port value and the weighted periodic lines
                 let mut instr_val = vec![0.; instr_end.len()];
-                for chan in self.editable_channels().iter().filter(|chan| chan.is_edited()) {
-                    let (port, line) = extract_port_line_numbers(chan.name());
-                    if port == match_port {
+                let mut periodic_lines: Vec<Vec<Instruction>> = vec![Vec::new(); instr_end.len()];
+                for chan in self.do_channels().values().filter(|chan| chan.is_edited()) {
+                    if chan.port() == match_port {
+                        let weight = 2.0f64.powf(chan.line() as f64);
                         let mut chan_instr_idx = 0;
                         for i in 0..instr_val.len() {
                             assert!(chan_instr_idx < chan.instr_end().len());
-                            let chan_value =
-                                chan.instr_val()[chan_instr_idx].args.get("value").unwrap();
-                            instr_val[i] += *chan_value as f64 * 2.0f64.powf(line as f64);
+                            let chan_instr = &chan.instr_val()[chan_instr_idx];
+                            match chan_instr.params {
+                                InstrParams::Const { value } => instr_val[i] += value * weight,
+                                InstrParams::PulseTrain { .. } => {
+                                    periodic_lines[i].push(Instruction::new_scale(chan_instr.clone(), weight))
+                                }
+                                _ => panic!(
+                                    "DO channel {} holds a {} instruction, only CONST and PULSETRAIN can be merged into a port",
+                                    chan.name(),
+                                    chan_instr.instr_type
+                                ),
+                            };
                             if instr_end[i] == chan.instr_end()[chan_instr_idx] {
                                 chan_instr_idx += 1;
                             }
                         }
                     }
                 }
+                // Intervals with periodic lines stay a single (composite) instruction each,
+                // so a pulse train is never expanded into one instruction per edge
                 let port_instr_val: Vec<Instruction> = instr_val
-                    .iter()
-                    .map(|&val| Instruction::new_const(val))
+                    .into_iter()
+                    .zip(periodic_lines)
+                    .map(|(val, periodic)| {
+                        if periodic.is_empty() {
+                            Instruction::new_const(val)
+                        } else {
+                            Instruction::new_offset(Instruction::new_sum(periodic), val)
+                        }
+                    })
                     .collect();
-                let mut port_channel = Channel::new(
-                    TaskType::DO,
-                    &format!("port{}", match_port),
-                    self.samp_rate(),
-                    // The default value for merged port channel does not matter since we never explicitly compile them
-                    0.
-                );
-                *port_channel.instr_val_() = port_instr_val;
-                *port_channel.instr_end_() = instr_end;
-                self.channels_()
-                    .insert(port_channel.name().to_string(), port_channel);
+                self.do_ports_()
+                    .insert(match_port, DoPort::new(match_port, instr_end, port_instr_val));
             }
         };
 
@@ -369,27 +401,21 @@ pub trait BaseDevice {
         self.total_run_time()
     }
 
-    /// Returns a vector of compiled channels based on the given criteria.
-    ///
-    /// Filters the device's channels based on their compiled state and optional properties such as
-    /// streamability and editability.
-    ///
-    /// # Arguments
-    /// - `require_streamable`: If `true`, only compiled channels marked as streamable will be included in the result.
-    /// - `require_editable`: If `true`, only compiled channels marked as editable will be included in the result.
-    ///
-    /// # Returns
-    /// A `Vec` containing references to the channels that match the provided criteria.
-    fn compiled_channels(&self, require_streamable: bool, require_editable: bool) -> Vec<&Channel> {
+    /// Returns the compiled channels (AO channels or DO lines) of the device.
+    fn compiled_channels(&self) -> Vec<&dyn BaseChannel> {
         self.channels()
-            .values()
-            .filter(|chan| {
-                chan.is_compiled()
-                    && (!require_streamable || chan.streamable())
-                    && (!require_editable || chan.editable())
-            })
+            .into_iter()
+            .filter(|chan| chan.is_compiled())
             .collect()
     }
+    /// Names of the channels written to the driver: compiled AO channels or, for DO devices, compiled ports.
+    /// The order matches the rows of [`BaseDevice::calc_signal_nsamps`] with `streamed = true`.
+    fn streamed_channel_names(&self) -> Vec<String> {
+        match self.task_type() {
+            TaskType::AO => self.compiled_channels().iter().map(|chan| chan.name().to_string()).collect(),
+            TaskType::DO => self.do_ports().values().map(|port| port.name()).collect(),
+        }
+    }
 
     /// Returns the total number of samples the card will generate according to the current compile cache.
     fn total_samps(&self) -> usize {
@@ -404,8 +430,8 @@ pub trait BaseDevice {
         let samps_per_chan: IndexMap<String, usize> =
             self.channels()
                 .into_iter()
-                .filter(|(_chan_name, chan)| !chan.instr_end().is_empty())
-                .map(|(chan_name, chan)| (chan_name.to_string(), chan.total_samps()))
+                .filter(|chan| !chan.instr_end().is_empty())
+                .map(|chan| (chan.name().to_string(), chan.total_samps()))
                 .collect();
 
         if samps_per_chan.is_empty() {
@@ -430,8 +456,7 @@ pub trait BaseDevice {
     }
     /// Calculates the maximum stop time among all compiled channels.
     ///
-    /// Iterates over all the compiled channels in the device, regardless of their streamability or
-    /// editability, and determines the maximum stop time.
+    /// Iterates over all the compiled channels in the device and determines the maximum stop time.
     /// See [`BaseChannel::total_run_time`] for more information.
     ///
     /// # Returns
@@ -442,21 +467,20 @@ pub trait BaseDevice {
 
     fn last_instr_end_pos(&self) -> usize {
         self.channels()
-            .values()
-            .filter(|chan| chan.editable())  // ToDo when splitting AO/DO types: remove `editable()` filter
+            .iter()
             .map(|chan| chan.last_instr_end_pos())
             .fold(0, usize::max)
     }
-    /// Calculates the maximum stop time among all editable channels and optionally adds an extra tick duration.
+    /// Calculates the maximum stop time among all channels and optionally adds an extra tick duration.
     ///
-    /// This function determines the maximum stop time by iterating over all editable channels. 
+    /// This function determines the maximum stop time by iterating over all channels. 
     /// If `extra_tail_tick` is `true`, an additional duration, equivalent to one tick of the device's 
     /// sampling rate, is added to the maximum stop time.
     ///
     /// See [`BaseChannel::edit_stop_time`] for how individual channel stop times are determined.
     ///
     /// # Returns
-    /// A `f64` representing the maximum stop time (in seconds) across all editable channels, 
+    /// A `f64` representing the maximum stop time (in seconds) across all channels, 
     /// optionally increased by the duration of one tick.
     fn last_instr_end_time(&self) -> f64 {
         self.last_instr_end_pos() as f64 * self.clock_period()
@@ -465,7 +489,7 @@ pub trait BaseDevice {
     /// Generates a signal by sampling float-point values from compiled instructions.
     ///
     /// This method fills a given buffer with signal values based on the compiled instructions of the device's
-    /// channels. Depending on the requirements, it can either generate signals intended for actual driver
+    /// channels. Depending on `streamed`, it can either generate signals intended for actual driver
     /// writing or for debugging editing intentions.
     ///
     /// # Arguments
@@ -474,12 +498,13 @@ pub trait BaseDevice {
     /// - `nsamps`: The number of samples to generate.
     /// - `buffer`: A mutable reference to a 2D array. The first axis corresponds to the channel index and
     ///    the second axis corresponds to the sample index.
-    /// - `require_streamable`: If `true`, only signals from channels marked as streamable will be generated.
-    /// - `require_editable`: If `true`, signals will be generated according to editing intentions for debugging purposes.
+    /// - `streamed`: If `true`, signals are generated as written to the driver, i.e. per port for DO devices
+    ///    (see [`BaseDevice::streamed_channel_names`]). Otherwise, one row per compiled channel (AO channel or DO line).
+    ///    The two only differ for DO devices.
     ///
     /// # Panics
     /// This method will panic if:
-    /// - The first dimension of the buffer does not match the number of channels that fulfill the provided requirements.
+    /// - The first dimension of the buffer does not match the number of rows selected by `streamed`.
     /// - The second dimension of the buffer does not match the provided `nsamps` value.
     ///
     /// # TODO Notes
@@ -490,19 +515,16 @@ pub trait BaseDevice {
         end_pos: usize,
         nsamps: usize,
         buffer: &mut ndarray::Array2<f64>,
-        require_streamable: bool,
-        require_editable: bool,
+        streamed: bool,
     ) {
-        // Assumes buffer of shape [num_compiled_and_streamable_channels][nsamps]
+        let ports = streamed && self.task_type() == TaskType::DO;
+        let num_rows = if ports { self.do_ports().len() } else { self.compiled_channels().len() };
+        // Assumes buffer of shape [num_rows][nsamps]
         assert!(
-            buffer.dim().0
-                == self
-                    .compiled_channels(require_streamable, require_editable)
-                    .len(),
+            buffer.dim().0 == num_rows,
             "Device {} has {} channels but passed buffer has shape {:?}",
             self.name(),
-            self.compiled_channels(require_streamable, require_editable)
-                .len(),
+            num_rows,
             buffer.dim()
         );
         assert!(
@@ -514,29 +536,33 @@ pub trait BaseDevice {
             buffer.dim()
         );
         // This can be parallelized (note)
-        for (i, chan) in self
-            .compiled_channels(require_streamable, require_editable)
-            .iter()
-            .enumerate()
-        {
-            let mut channel_slice = buffer.slice_mut(s![i, ..]);
-            chan.fill_signal_nsamps(start_pos, end_pos, nsamps, &mut channel_slice);
+        if ports {
+            for (i, port) in self.do_ports().values().enumerate() {
+                let mut port_slice = buffer.slice_mut(s![i, ..]);
+                port.fill_signal_nsamps(start_pos, end_pos, nsamps, &mut port_slice);
+            }
+        } else {
+            for (i, chan) in self.compiled_channels().iter().enumerate() {
+                let mut channel_slice = buffer.slice_mut(s![i, ..]);
+                chan.fill_signal_nsamps(start_pos, end_pos, nsamps, &mut channel_slice);
+            }
         }
     }
 
     /// Computes and returns the signal values for specified channels in a device.
     ///
     /// This method calculates the signal values by sampling float-point values from compiled instructions
-    /// of the device's channels. Depending on the requirements, the signal can be either intended for actual
-    /// driver writing or for debugging editing intentions. For AO (Analog Output) devices, the returned buffer
-    /// will contain time data.
+    /// of the device's channels. Depending on `streamed`, the signal can be either intended for actual
+    /// driver writing or for debugging editing intentions. The buffer is initialized with the sample times
+    /// `start_pos / samp_rate`, ..., `(end_pos - 1) / samp_rate` (for `nsamps == end_pos - start_pos`),
+    /// which the instructions are then evaluated at. DO devices skip this unless one of their lines runs a pulse train.
     ///
     /// # Arguments
     /// - `start_pos`: The starting position in the sequence of compiled instructions.
     /// - `end_pos`: The ending position in the sequence of compiled instructions.
     /// - `nsamps`: The number of samples to generate.
-    /// - `require_streamable`: If `true`, only signals from channels marked as streamable will be generated.
-    /// - `require_editable`: If `true`, signals will be generated according to editing intentions for debugging purposes.
+    /// - `streamed`: If `true`, one row per port for DO devices, as written to the driver. Otherwise, one row per
+    ///    compiled channel. See [`BaseDevice::fill_signal_nsamps`].
     ///
     /// # Returns
     /// A 2D array with the computed signal values. The first axis corresponds to the channel index and the
@@ -544,34 +570,43 @@ pub trait BaseDevice {
     ///
     /// # Panics
     /// This method will panic if:
-    /// - There are no channels that fulfill the provided requirements.
-    /// - The device's task type is not AO (Analog Output) when initializing the buffer with time data.
+    /// - There are no compiled channels.
     fn calc_signal_nsamps(
         &self,
         start_pos: usize,
         end_pos: usize,
         nsamps: usize,
-        require_streamable: bool,
-        require_editable: bool,
+        streamed: bool,
     ) -> Array2<f64> {
         // ToDo: look through
-        let num_chans = self
-            .compiled_channels(require_streamable, require_editable)
-            .len();
+        let num_chans = if streamed && self.task_type() == TaskType::DO {
+            self.do_ports().len()
+        } else {
+            self.compiled_channels().len()
+        };
         assert!(
             num_chans > 0,
-            "There is no channel with streamable={}, editable={}",
-            require_streamable,
-            require_editable
+            "Device {} has no compiled channels",
+            self.name()
         );
         let mut buffer = Array2::from_elem((num_chans, nsamps), 0.);
-        // Only AOChannel needs to initialize buffer with time data
-        if self.task_type() == TaskType::AO {
-            let t_values = Array1::linspace(
-                start_pos as f64 / self.samp_rate(),
-                end_pos as f64 / self.samp_rate(),
-                nsamps,
-            );
+        // DO rows only need the time data where a pulse train runs, constant lines and ports are filled directly
+        let needs_times = match self.task_type() {
+            TaskType::AO => true,
+            TaskType::DO => self
+                .compiled_channels()
+                .iter()
+                .any(|chan| chan.instr_val().iter().any(|instr| instr.instr_type != InstrType::CONST)),
+        };
+        // Initialize buffer with time data.
+        // The end point is excluded, so with `nsamps = end_pos - start_pos` every sample sits exactly on its
+        // clock tick and consecutive chunks continue each other - a linspace including `end_pos` would stretch
+        // every chunk by one sample and repeat the boundary sample.
+        if needs_times {
+            let samp_step = (end_pos - start_pos) as f64 / nsamps as f64;
+            let t_values = Array1::from_shape_fn(nsamps, |i| {
+                (start_pos as f64 + i as f64 * samp_step) / self.samp_rate()
+            });
             buffer
                 .outer_iter_mut()
                 .for_each(|mut row| row.assign(&t_values));
@@ -581,8 +616,7 @@ pub trait BaseDevice {
             end_pos,
             nsamps,
             &mut buffer,
-            require_streamable,
-            require_editable,
+            streamed,
         );
         buffer
     }
@@ -590,8 +624,7 @@ pub trait BaseDevice {
     /// Retrieves a list of unique port numbers from the device's channels.
     ///
     /// This utility function is primarily used with DO (Digital Output) devices to identify and operate
-    /// on unique ports. It scans through the compiled channels of the device, filtering for those that are
-    /// editable, and extracts the unique port numbers associated with them.
+    /// on unique ports. It scans through the compiled lines of the device and collects their port numbers.
     ///
     /// # Returns
     /// A vector of unique port numbers identified in the device's channels.
@@ -607,11 +640,12 @@ pub trait BaseDevice {
 
         let mut port_numbers = BTreeSet::new();
 
-        self.compiled_channels(false, true).iter().for_each(|chan| {
-            // Capture the port
-            let name = &chan.name();
-            port_numbers.insert(extract_port_line_numbers(name).0);
-        });
+        self.do_channels()
+            .values()
+            .filter(|chan| chan.is_compiled())
+            .for_each(|chan| {
+                port_numbers.insert(chan.port());
+            });
         port_numbers.into_iter().collect()
     }
 }
@@ -625,7 +659,8 @@ pub trait BaseDevice {
 /// For editing and compiling behavior of devices, see the [`BaseDevice`] trait.
 ///
 /// # Fields
-/// - `channels`: A collection of channels associated with this device.
+/// - `ao_channels`, `do_channels`: Channels associated with this device, only the one matching `task_type` is used.
+/// - `do_ports`: Ports merged from the DO channels during compilation.
 /// - `name`: Name of the device as seen by the NI driver.
 /// - `task_type`: Specifies the task type associated with the device.
 /// - `samp_rate`: The sampling rate of the device in Hz.
@@ -695,7 +730,7 @@ pub trait BaseDevice {
 /// # use nicompiler_backend::*;
 /// let mut exp = Experiment::new();
 /// exp.add_do_device("PXI1Slot6", 1e6);
-/// exp.add_do_channel("PXI1Slot6", 0, 4, 0.);
+/// exp.add_do_channel("PXI1Slot6", 0, 4, false);
 /// exp.device_cfg_trig("PXI1Slot6", "PXI1_Trig0", false);
 /// exp.go_high("PXI1Slot6", "port0/line4", 0.5);
 /// exp.compile_with_stoptime(1.); // Panics here
@@ -755,7 +790,9 @@ pub trait BaseDevice {
 /// exp.device_cfg_trig("PXI1Slot6", "PXI1_Trig0", false);
 /// ```
 pub struct Device {
-    channels: IndexMap<String, Channel>,
+    ao_channels: IndexMap<String, AoChannel>,
+    do_channels: IndexMap<String, DoChannel>,
+    do_ports: IndexMap<usize, DoPort>,
 
     name: String,
     task_type: TaskType,
@@ -787,7 +824,9 @@ impl Device {
     /// A new instance of `Device` with the specified configurations and all synchronization-related fields set to `None`.
     pub fn new(name: &str, task_type: TaskType, samp_rate: f64) -> Self {
         Self {
-            channels: IndexMap::new(),
+            ao_channels: IndexMap::new(),
+            do_channels: IndexMap::new(),
+            do_ports: IndexMap::new(),
 
             name: name.to_string(),
             task_type,
@@ -874,8 +913,14 @@ impl BaseDevice for Device {
     // ToDo: this is a temporary dirty fix. Remove after crate merge
 
     // Immutable accessors (getters)
-    fn channels(&self) -> &IndexMap<String, Channel> {
-        &self.channels
+    fn ao_channels(&self) -> &IndexMap<String, AoChannel> {
+        &self.ao_channels
+    }
+    fn do_channels(&self) -> &IndexMap<String, DoChannel> {
+        &self.do_channels
+    }
+    fn do_ports(&self) -> &IndexMap<usize, DoPort> {
+        &self.do_ports
     }
 
     fn name(&self) -> &str {
@@ -891,8 +936,14 @@ impl BaseDevice for Device {
     }
 
     // Mutable accessors
-    fn channels_(&mut self) -> &mut IndexMap<String, Channel> {
-        &mut self.channels
+    fn ao_channels_(&mut self) -> &mut IndexMap<String, AoChannel> {
+        &mut self.ao_channels
+    }
+    fn do_channels_(&mut self) -> &mut IndexMap<String, DoChannel> {
+        &mut self.do_channels
+    }
+    fn do_ports_(&mut self) -> &mut IndexMap<usize, DoPort> {
+        &mut self.do_ports
     }
 }
 
@@ -904,8 +955,8 @@ mod test {
     #[test]
     fn last_instr_end_pos() {
         let mut dev = Device::new("Dev1", TaskType::AO, 1e3);
-        dev.add_channel("ao0", 0.0);
-        dev.add_channel("ao1", 0.0);
+        dev.add_ao_channel(0, 0.0);
+        dev.add_ao_channel(1, 0.0);
         let mock_func = Instruction::new_const(0.0);
 
         // No instructions
@@ -936,7 +987,7 @@ mod test {
     #[test]
     fn check_end_clipped() {
         let mut dev = Device::new("Dev1", TaskType::AO, 1.0);
-        dev.add_channel("ao0", 0.0);
+        dev.add_ao_channel(0, 0.0);
         let mock_func = Instruction::new_const(0.0);
 
         // (1) No instructions
@@ -968,8 +1019,8 @@ mod test {
     #[test]
     fn compile() {
         let mut dev = Device::new("Dev1", TaskType::AO, 1e3);
-        dev.add_channel("ao0", 0.0);
-        dev.add_channel("ao1", 0.0);
+        dev.add_ao_channel(0, 0.0);
+        dev.add_ao_channel(1, 0.0);
         let mock_func = Instruction::new_const(0.0);
 
         // Not compiled yet
@@ -993,4 +1044,56 @@ mod test {
         dev.compile(2.0);
         assert_eq!(dev.total_samps(), 2001);
     }
+
+    #[test]
+    fn merge_pulse_train() {
+        let mut dev = Device::new("Dev1", TaskType::DO, 1e3);
+        dev.add_do_channel(0, 0, false);
+        dev.add_do_channel(0, 1, false);
+
+        // 2 ms high every 4 ms on line0 for 20 ms, line1 high for 10..30 ms
+        let train = Instruction::new_pulse_train(0.0, 4e-3, Some(0.5), None, None);
+        dev.chan_("port0/line0").add_instr(train, 0.0, Some((20e-3, false)));
+        dev.chan_("port0/line1").add_instr(Instruction::new_const(1.0), 10e-3, Some((20e-3, false)));
+        dev.compile(40e-3);
+
+        // The train is not split at its edges
+        assert_eq!(dev.do_ports()[&0].instr_end(), &vec![10, 20, 30, 40]);
+        assert_eq!(dev.streamed_channel_names(), vec!["port0"]);
+        assert_eq!(dev.chan_names(), vec!["port0/line0", "port0/line1"]);
+
+        let signal = dev.calc_signal_nsamps(0, 40, 40, true);
+        assert_eq!(signal.dim(), (1, 40));
+        for k in 0..40 {
+            let line0 = if k < 20 && k % 4 < 2 { 1.0 } else { 0.0 };
+            let line1 = if (10..30).contains(&k) { 1.0 } else { 0.0 };
+            assert_eq!(signal[[0, k]], line0 + 2.0 * line1, "sample {}", k);
+        }
+
+        // Chunk boundaries don't shift the edges
+        let chunk = dev.calc_signal_nsamps(13, 27, 14, true);
+        for k in 13..27 {
+            assert_eq!(chunk[[0, k - 13]], signal[[0, k]], "sample {}", k);
+        }
+    }
+
+    #[test]
+    fn ao_chunk_boundaries() {
+        let mut dev = Device::new("Dev1", TaskType::AO, 1e3);
+        dev.add_ao_channel(0, 0.0);
+        // Value equals time, so the samples show the time grid
+        dev.chan_("ao0").add_instr(Instruction::new_linramp(0.0, 1.0, 0.0, 1.0), 0.0, Some((1.0, false)));
+        dev.compile(1.0);
+
+        let whole = dev.calc_signal_nsamps(0, 100, 100, true);
+        for k in 0..100 {
+            assert!((whole[[0, k]] - k as f64 / 1e3).abs() < 1e-12, "sample {}", k);
+        }
+        // Streaming the same span in chunks gives the same samples, without repeating the boundary ones
+        let mut chunked = Vec::new();
+        for (start, end) in [(0, 37), (37, 40), (40, 100)] {
+            chunked.extend(dev.calc_signal_nsamps(start, end, end - start, true).row(0).iter().copied());
+        }
+        assert_eq!(chunked, whole.row(0).to_vec());
+    }
 }
\ No newline at end of file
@@ -22,7 +22,7 @@
 //!   provides methods for their management.
 //! - **Device**: Each device, represented by the [`Device`] struct, corresponds to a specific piece of NI hardware.
 //!   Devices contain channels, and methods in the `Experiment` struct often redirect to these devices.
-//! - **Channel**: Channels, denoted by the [`Channel`] struct, symbolize distinct physical channels on an NI device.
+//! - **Channel**: Channels, denoted by the [`AoChannel`] and [`DoChannel`] structs, symbolize distinct physical channels on an NI device.
 //!   They hold instructions and other functionalities specific to the channel.
 //! - **Instruction**: Instructions, housed within [`InstrBook`], define specific tasks or commands for channels.
 //!
@@ -39,10 +39,15 @@ use numpy;
 use pyo3::prelude::*;
 use indexmap::IndexMap;
 
+use crate::calibration::*;
 use crate::channel::*;
 use crate::device::*;
 use crate::instruction::*;
 
+/// Entry of [`BaseExperiment::channel_instr_list`]: `(start_time, end_spec, instr)`,
+/// with `end_spec = Some((end_time, keep_val))` for instructions with a specified duration
+pub type InstrListEntry = (f64, Option<(f64, bool)>, Instruction);
+
 /// This trait defines the behavior of the [`Experiment`] struct through default trait implementations.
 ///
 /// Trait methods are primary classified into the following categories:
@@ -58,13 +63,19 @@ use crate::instruction::*;
 ///     - [`device_last_instr_end_time`], [`device_compiled_stop_time`]
 ///     - [`device_clear_compile_cache`], [`device_clear_edit_cache`]
 /// 3. Channel-targeted methods which alter or query the behavior of a particular channel
-///     - [`constant`], [`sine`], [`high`], [`low`], [`go_high`], [`go_low`]
+///     - [`constant`], [`sine`], [`sine_from_current`], [`chirp`], [`pulse`], [`high`], [`low`], [`go_high`], [`go_low`], [`pulse_train`], [`linramp_from_current`], [`add_instr`], [`expr`], [`custom`], [`smooth_ramp`], [`arb`], [`pwl`], [`spline`], [`poly`], [`fourier`], [`noise`]
+///     - [`channel_remove_instr`], [`channel_replace_instr`], [`channel_move_instr`]
 ///     - [`channel_clear_compile_cache`], [`channel_clear_edit_cache`]
-///     - [`channel_calc_signal_nsamps`]
+///     - [`channel_max_slew_rate`], [`channel_set_max_slew_rate`]
+///     - [`channel_reset_value`], [`ao_channel_set_reset_value`], [`do_channel_set_reset_value`]
+///     - [`channel_output_limits`], [`ao_channel_set_output_limits`]
+///     - [`channel_unit`], [`ao_channel_set_calibration`], [`ao_channel_clear_calibration`]
+///     - [`channel_calc_signal_nsamps`], [`channel_value_bounds`], [`channel_output_bounds`]
+///     - [`channel_instr_list`], [`channel_compiled_segments`]
 /// 4. Internal helper methods which are not exposed to python
 ///     - [`devices`], [`devices_`]
 ///     - [`assert_has_device`], [`assert_device_has_channel`]
-///     - [`typed_device_op`], [`device_op`], [`typed_channel_op`], [`channel_op`]
+///     - [`typed_device_op`], [`device_op`], [`ao_channel_op`], [`do_channel_op`], [`channel_op`]
 ///
 /// [`add_ao_device`]: BaseExperiment::add_ao_device
 /// [`add_do_device`]: BaseExperiment::add_do_device
@@ -86,23 +97,55 @@ use crate::instruction::*;
 /// [`device_clear_edit_cache`]: BaseExperiment::device_clear_edit_cache
 /// [`constant`]: BaseExperiment::constant
 /// [`sine`]: BaseExperiment::sine
+/// [`sine_from_current`]: BaseExperiment::sine_from_current
+/// [`chirp`]: BaseExperiment::chirp
+/// [`pulse`]: BaseExperiment::pulse
 /// [`high`]: BaseExperiment::high
 /// [`low`]: BaseExperiment::low
 /// [`go_high`]: BaseExperiment::go_high
 /// [`go_low`]: BaseExperiment::go_low
+/// [`pulse_train`]: BaseExperiment::pulse_train
+/// [`linramp_from_current`]: BaseExperiment::linramp_from_current
+/// [`add_instr`]: BaseExperiment::add_instr
+/// [`expr`]: BaseExperiment::expr
+/// [`custom`]: BaseExperiment::custom
+/// [`smooth_ramp`]: BaseExperiment::smooth_ramp
+/// [`arb`]: BaseExperiment::arb
+/// [`pwl`]: BaseExperiment::pwl
+/// [`spline`]: BaseExperiment::spline
+/// [`poly`]: BaseExperiment::poly
+/// [`fourier`]: BaseExperiment::fourier
+/// [`noise`]: BaseExperiment::noise
 /// [`devices`]: BaseExperiment::devices
 /// [`devices_`]: BaseExperiment::devices_
 /// [`assert_has_device`]: BaseExperiment::assert_has_device
 /// [`assert_device_has_channel`]: BaseExperiment::assert_device_has_channel
 /// [`typed_device_op`]: BaseExperiment::typed_device_op
 /// [`device_op`]: BaseExperiment::device_op
-/// [`typed_channel_op`]: BaseExperiment::typed_channel_op
+/// [`ao_channel_op`]: BaseExperiment::ao_channel_op
+/// [`do_channel_op`]: BaseExperiment::do_channel_op
 /// [`channel_op`]: BaseExperiment::channel_op
+/// [`channel_remove_instr`]: BaseExperiment::channel_remove_instr
+/// [`channel_replace_instr`]: BaseExperiment::channel_replace_instr
+/// [`channel_move_instr`]: BaseExperiment::channel_move_instr
+/// [`channel_instr_list`]: BaseExperiment::channel_instr_list
+/// [`channel_compiled_segments`]: BaseExperiment::channel_compiled_segments
 /// [`channel_clear_compile_cache`]: BaseExperiment::channel_clear_compile_cache
 /// [`channel_clear_edit_cache`]: BaseExperiment::channel_clear_edit_cache
+/// [`channel_max_slew_rate`]: BaseExperiment::channel_max_slew_rate
+/// [`channel_set_max_slew_rate`]: BaseExperiment::channel_set_max_slew_rate
+/// [`channel_reset_value`]: BaseExperiment::channel_reset_value
+/// [`ao_channel_set_reset_value`]: BaseExperiment::ao_channel_set_reset_value
+/// [`do_channel_set_reset_value`]: BaseExperiment::do_channel_set_reset_value
+/// [`channel_output_limits`]: BaseExperiment::channel_output_limits
+/// [`ao_channel_set_output_limits`]: BaseExperiment::ao_channel_set_output_limits
+/// [`channel_unit`]: BaseExperiment::channel_unit
+/// [`ao_channel_set_calibration`]: BaseExperiment::ao_channel_set_calibration
+/// [`ao_channel_clear_calibration`]: BaseExperiment::ao_channel_clear_calibration
 /// [`device_compiled_channel_names`]: BaseExperiment::device_compiled_channel_names
 /// [`channel_calc_signal_nsamps`]: BaseExperiment::channel_calc_signal_nsamps
-
+/// [`channel_value_bounds`]: BaseExperiment::channel_value_bounds
+/// [`channel_output_bounds`]: BaseExperiment::channel_output_bounds
 pub trait BaseExperiment {
     // FIELD methods
     fn devices(&self) -> &IndexMap<String, Device>;
@@ -168,7 +211,7 @@ pub trait BaseExperiment {
     ///
     /// let mut exp = Experiment::new();
     /// exp.add_do_device("PXI1Slot6", 1e6,);
-    /// exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
+    /// exp.add_do_channel("PXI1Slot6", 0, 0, false);
     /// exp.assert_device_has_channel("PXI1Slot6", "port0/line0");
     ///
     /// // This will panic
@@ -180,11 +223,11 @@ pub trait BaseExperiment {
         self.assert_has_device(name);
         let device = self.devices().get(name).unwrap();
         assert!(
-            device.channels().contains_key(chan_name),
+            device.has_chan(chan_name),
             "Channel name {} not found in device {}. Registered channels are: {:?}",
             chan_name,
             name,
-            device.channels().keys().collect::<Vec<_>>()
+            device.chan_names()
         );
     }
 
@@ -280,8 +323,8 @@ pub trait BaseExperiment {
     /// # use nicompiler_backend::*;
     /// let mut exp = Experiment::new();
     /// exp.add_do_device("PXI1Slot6", 1e6);
-    /// exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
-    /// exp.add_do_channel("PXI1Slot6", 0, 4, 0.);
+    /// exp.add_do_channel("PXI1Slot6", 0, 0, false);
+    /// exp.add_do_channel("PXI1Slot6", 0, 4, false);
     /// exp.high("PXI1Slot6", "port0/line0", 1., 4.); // stop time at 5
     /// assert_eq!(exp.edit_stop_time(), 5.);
     /// exp.high("PXI1Slot6", "port0/line4", 0., 6.); // stop time at 6
@@ -333,7 +376,7 @@ pub trait BaseExperiment {
     /// # use nicompiler_backend::*;
     /// let mut exp = Experiment::new();
     /// exp.add_do_device("PXI1Slot6", 1e6);
-    /// exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
+    /// exp.add_do_channel("PXI1Slot6", 0, 0, false);
     /// exp.high("PXI1Slot6", "port0/line0", 1., 3.);
     ///
     /// exp.compile(false);
@@ -361,6 +404,38 @@ pub trait BaseExperiment {
         return self.total_run_time()
     }
 
+    /// Takes the python exceptions stashed by failed python function instructions
+    /// (see [`PyFuncWaveform`](crate::waveform::PyFuncWaveform)) on all channels, returning the first one.
+    ///
+    /// Evaluating such an instruction panics on failure. The python-facing methods catch the panic
+    /// and re-raise the original exception found here, see [`BaseExperiment::catch_pyfunc_error`].
+    fn take_pyfunc_error(&self) -> Option<PyErr> {
+        let mut first = None;
+        for dev in self.devices().values() {
+            for chan in dev.channels() {
+                for instr in chan.instr_list().iter().map(|book| &book.instr).chain(chan.instr_val().iter()) {
+                    first = first.or(crate::waveform::take_pyfunc_error(instr));
+                }
+            }
+        }
+        first
+    }
+
+    /// Runs `f`, turning a panic caused by a failed python function instruction into the python exception it raised.
+    /// Other panics are propagated unchanged.
+    fn catch_pyfunc_error<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> PyResult<T>
+    where
+        Self: Sized,
+    {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(self))) {
+            Ok(ret) => Ok(ret),
+            Err(panic_info) => match self.take_pyfunc_error() {
+                Some(err) => Err(err),
+                None => std::panic::resume_unwind(panic_info),
+            },
+        }
+    }
+
     /// Retrieves a list of devices that have been successfully compiled.
     ///
     /// # Returns
@@ -414,11 +489,12 @@ pub trait BaseExperiment {
             .for_each(|dev| dev.clear_edit_cache());
     }
 
-    /// Adds a reset tick with value of 0 across all editable channels of the experiment.
+    /// Adds a reset tick across all channels of the experiment.
     ///
     /// This function computes the last `edit_stop_time` of the experiment and uses it
     /// to determine the appropriate time to insert the reset tick. A reset tick is a
-    /// point in time where all editable channels are reset to a value of 0.
+    /// point in time where all channels are set to their reset values: `0` unless changed with
+    /// [`BaseExperiment::ao_channel_set_reset_value`] or [`BaseExperiment::do_channel_set_reset_value`].
     ///
     /// # Arguments
     ///
@@ -448,15 +524,15 @@ pub trait BaseExperiment {
     /// let mut exp = Experiment::new();
     /// // Define devices and associated channels
     /// exp.add_do_device("PXI1Slot6", 10.);
-    /// exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
-    /// exp.add_do_channel("PXI1Slot6", 0, 1, 0.);
+    /// exp.add_do_channel("PXI1Slot6", 0, 0, false);
+    /// exp.add_do_channel("PXI1Slot6", 0, 1, false);
     ///
     /// exp.high("PXI1Slot6", "port0/line0", 0., 1.);
     /// exp.go_high("PXI1Slot6", "port0/line1", 0.);
     /// exp.compile_with_stoptime(5.);
     ///
     /// // Calculate from t=0 ~ 5
-    /// let sig = exp.device_calc_signal_nsamps("PXI1Slot6", 0, 50, 50, false, true);
+    /// let sig = exp.device_calc_signal_nsamps("PXI1Slot6", 0, 50, 50, false);
     /// assert!(sig[[0, 9]] == 1. && sig[[0, 10]] == 0.); // go_high takes effect on the tick corresponding to specified time. 
     /// assert!(sig[[1, 9]] == 1. && sig[[1, 10]] == 1.); 
     /// 
@@ -464,7 +540,7 @@ pub trait BaseExperiment {
     /// // Reset tick happens at the earliest unspecified interval across all channels
     /// assert!(reset_tick_time == 1.0); 
     /// exp.compile_with_stoptime(5.);
-    /// let sig = exp.device_calc_signal_nsamps("PXI1Slot6", 0, 50, 50, false, true);
+    /// let sig = exp.device_calc_signal_nsamps("PXI1Slot6", 0, 50, 50, false);
     /// assert!(sig[[0, 9]] == 1. && sig[[0, 10]] == 0.); 
     /// assert!(sig[[1, 9]] == 1. && sig[[1, 10]] == 0.); // Also zeros channel 1 at t=1
     /// // println!("{:?}, reset_tick_time={}", sig, reset_tick_time);
@@ -574,24 +650,22 @@ pub trait BaseExperiment {
         f(dev)
     }
 
-    /// Executes a specified operation (given by the closure `f`) on a targeted channel of a specific device and `TaskType`.
+    /// Executes a specified operation (given by the closure `f`) on an AO channel of a device.
     ///
-    /// This utility method abstracts the common checks and operations performed on a channel. It ensures the device
-    /// and its channel both exist, then checks if the device's task type matches the provided `task_type`, and
+    /// This method asserts that the device and channel exist and that the device is of `TaskType::AO`,
     /// finally, invokes the provided closure on the channel.
     ///
     /// # Arguments
     ///
     /// * `name`: The name of the parent device.
     /// * `chan_name`: The name of the target channel within the device.
-    /// * `task_type`: The expected `TaskType` of the parent device.
-    /// * `f`: A closure that defines the operation to be performed on the channel. It should accept a mutable reference to a `Channel` and return a value of type `R`.
+    /// * `f`: A closure that defines the operation to be performed on the channel. It should accept a mutable reference to an [`AoChannel`] and return a value of type `R`.
     ///
     /// # Panics
     ///
     /// 1. If a device with the given `name` doesn't exist.
     /// 2. If the channel with the given `chan_name` doesn't exist within the device.
-    /// 3. If the device's task type doesn't match the provided `task_type`.
+    /// 3. If the device is not an AO device.
     ///
     /// # Returns
     ///
@@ -603,44 +677,59 @@ pub trait BaseExperiment {
     /// let mut exp = Experiment::new();
     /// exp.add_ao_device("PXI1Slot6", 1e6);
     /// exp.add_ao_channel("PXI1Slot6", 0, 0.);
-    /// exp.typed_channel_op("PXI1Slot6", "ao0", TaskType::AO, |chan| {(*chan).constant(1., 0., 1., false)});
-    /// assert_eq!(exp.typed_channel_op("PXI1Slot6", "ao0", TaskType::AO,
-    ///             |chan| {(*chan).is_edited()}), true);
+    /// exp.ao_channel_op("PXI1Slot6", "ao0", |chan| {(*chan).constant(1., 0., Some((1., false)))});
+    /// assert_eq!(exp.ao_channel_op("PXI1Slot6", "ao0", |chan| {(*chan).is_edited()}), true);
     /// ```
-    fn typed_channel_op<F, R>(
-        &mut self,
-        name: &str,
-        chan_name: &str,
-        task_type: TaskType,
-        mut f: F,
-    ) -> R
+    fn ao_channel_op<F, R>(&mut self, name: &str, chan_name: &str, mut f: F) -> R
     where
-        F: FnMut(&mut Channel) -> R,
+        F: FnMut(&mut AoChannel) -> R,
     {
-        // This helper function performs checks and asserts the required device type
-        // then executes closure `f` on the specified channel
         self.assert_device_has_channel(name, chan_name);
         let dev = self.devices_().get_mut(name).unwrap();
         assert!(
-            dev.task_type() == task_type,
+            dev.task_type() == TaskType::AO,
             "Channel {}/{} is incompatible with instruction",
             name,
             chan_name
         );
-        let chan = dev.channels_().get_mut(chan_name).unwrap();
-        f(chan)
+        f(dev.ao_chan_(chan_name))
+    }
+
+    /// Executes a specified operation (given by the closure `f`) on a DO channel of a device.
+    ///
+    /// Counterpart of [`BaseExperiment::ao_channel_op`] for DO devices, the closure receives a [`DoChannel`].
+    ///
+    /// # Panics
+    ///
+    /// 1. If a device with the given `name` doesn't exist.
+    /// 2. If the channel with the given `chan_name` doesn't exist within the device.
+    /// 3. If the device is not a DO device.
+    fn do_channel_op<F, R>(&mut self, name: &str, chan_name: &str, mut f: F) -> R
+    where
+        F: FnMut(&mut DoChannel) -> R,
+    {
+        self.assert_device_has_channel(name, chan_name);
+        let dev = self.devices_().get_mut(name).unwrap();
+        assert!(
+            dev.task_type() == TaskType::DO,
+            "Channel {}/{} is incompatible with instruction",
+            name,
+            chan_name
+        );
+        f(dev.do_chan_(chan_name))
     }
 
     /// Executes a specified operation (given by the closure `f`) on a targeted channel of a device without considering its `TaskType`.
     ///
-    /// This method is a type-agnostic version of [`BaseExperiment::typed_channel_op`]. It abstracts away the common checks and operations
-    /// performed on a channel without asserting a specific `TaskType` for the parent device.
+    /// This method is a type-agnostic version of [`BaseExperiment::ao_channel_op`] and [`BaseExperiment::do_channel_op`].
+    /// It abstracts away the common checks and operations performed on a channel without asserting a specific `TaskType`
+    /// for the parent device.
     ///
     /// # Arguments
     ///
     /// * `name`: The name of the parent device.
     /// * `chan_name`: The name of the target channel within the device.
-    /// * `f`: A closure that defines the operation to be performed on the channel. It should accept a mutable reference to a `Channel` and return a value of type `R`.
+    /// * `f`: A closure that defines the operation to be performed on the channel. It should accept a mutable reference to a [`BaseChannel`] and return a value of type `R`.
     ///
     /// # Panics
     ///
@@ -652,17 +741,15 @@ pub trait BaseExperiment {
     /// The return value of the closure `f`.
     fn channel_op<F, R>(&mut self, name: &str, chan_name: &str, mut f: F) -> R
     where
-        F: FnMut(&mut Channel) -> R,
+        F: FnMut(&mut dyn BaseChannel) -> R,
     {
-        // Type-agnostic variant of typed_channel_op
+        // Type-agnostic variant of ao_channel_op / do_channel_op
         self.assert_device_has_channel(name, chan_name);
         let chan = self
             .devices_()
             .get_mut(name)
             .unwrap()
-            .channels_()
-            .get_mut(chan_name)
-            .unwrap();
+            .chan_(chan_name);
         f(chan)
     }
 
@@ -672,7 +759,7 @@ pub trait BaseExperiment {
     /// the channel addition request to the specified device. Adds a channel of name `ao(channel_id)`
     /// to the designated device.
     ///
-    /// Refer to the [`BaseDevice::add_channel`] method for detailed information on channel addition.
+    /// Refer to the [`BaseDevice::add_ao_channel`] method for detailed information on channel addition.
     ///
     /// # Arguments
     ///
@@ -692,7 +779,7 @@ pub trait BaseExperiment {
     /// ```
     fn add_ao_channel(&mut self, name: &str, channel_id: usize, default_value: f64) {
         self.typed_device_op(name, TaskType::AO, |dev| {
-            (*dev).add_channel(&format!("ao{}", channel_id), default_value)
+            (*dev).add_ao_channel(channel_id, default_value)
         });
     }
 
@@ -701,13 +788,14 @@ pub trait BaseExperiment {
     /// This method uses the [`BaseExperiment::typed_device_op`] function to forward
     /// the channel addition request to the specified device.
     ///
-    /// For further details on how channels are added, see the [`BaseDevice::add_channel`] method.
+    /// For further details on how channels are added, see the [`BaseDevice::add_do_channel`] method.
     ///
     /// # Arguments
     ///
     /// * `name`: The name of the target device.
     /// * `port_id`: The identifier for the digital port.
     /// * `line_id`: The identifier for the digital line within the port.
+    /// * `default_value`: Line state for intervals not covered by instructions.
     ///
     /// # Panics
     ///
@@ -718,13 +806,11 @@ pub trait BaseExperiment {
     /// # use nicompiler_backend::*;
     /// let mut exp = Experiment::new();
     /// exp.add_do_device("PXI1Slot6", 1e7);
-    /// exp.add_do_channel("PXI1Slot6", 0, 0, 0.); // adds channel "port0/line0"
+    /// exp.add_do_channel("PXI1Slot6", 0, 0, false); // adds channel "port0/line0"
     /// ```
-    fn add_do_channel(&mut self, name: &str, port_id: usize, line_id: usize, default_value: f64) {
-        assert!(default_value == 0. || default_value == 1., 
-            "Expected default value 0 or 1 for device {} DO channel {} but received {}", name, port_id, default_value);
+    fn add_do_channel(&mut self, name: &str, port_id: usize, line_id: usize, default_value: bool) {
         self.typed_device_op(name, TaskType::DO, |dev| {
-            (*dev).add_channel(&format!("port{}/line{}", port_id, line_id), default_value)
+            (*dev).add_do_channel(port_id, line_id, default_value)
         });
     }
 
@@ -739,8 +825,7 @@ pub trait BaseExperiment {
     /// * `start_pos`: The start position for the calculation.
     /// * `end_pos`: The end position for the calculation.
     /// * `nsamps`: The number of samples for the calculation.
-    /// * `require_streamable`: Flag to indicate if the signal should be streamable.
-    /// * `require_editable`: Flag to indicate if the signal should be editable.
+    /// * `streamed`: If `true`, DO devices give one row per port as written to the driver instead of one row per line.
     ///
     /// # Returns
     ///
@@ -751,16 +836,14 @@ pub trait BaseExperiment {
         start_pos: usize,
         end_pos: usize,
         nsamps: usize,
-        require_streamable: bool,
-        require_editable: bool,
+        streamed: bool,
     ) -> Array2<f64> {
         self.device_op(dev_name, |dev| {
             (*dev).calc_signal_nsamps(
                 start_pos,
                 end_pos,
                 nsamps,
-                require_streamable,
-                require_editable,
+                streamed,
             )
         })
     }
@@ -913,26 +996,24 @@ pub trait BaseExperiment {
         self.device_op(name, |dev| (*dev).clear_edit_cache())
     }
 
-    /// Retrieves the names of compiled channels from the specified device based on the given requirements.
+    /// Retrieves the names of compiled channels from the specified device.
     ///
-    /// This method fetches the names of all channels from the designated device that have been compiled
-    /// and meet the criteria specified by `require_streamable` and `require_editable`: see
-    /// [`BaseDevice::compiled_channels`]. The order of the
-    /// returned names will match that in [`BaseExperiment::device_calc_signal_nsamps`].
-    /// Set `require_editable=true` to see the signals as they are written into the experiment object.
-    /// Set `require_streamable=true` to see signals from channels as they are written in to the NI-DAQmx driver.
-    /// Also see the `channel` module on streamable and editable channel properties.
+    /// The order of the returned names will match the rows of [`BaseExperiment::device_calc_signal_nsamps`]
+    /// with the same `streamed` flag.
+    /// Set `streamed=false` to see the signals as they are written into the experiment object.
+    /// Set `streamed=true` to see signals as they are written in to the NI-DAQmx driver, i.e. ports instead of lines
+    /// for DO devices (see [`BaseDevice::streamed_channel_names`]).
+    /// Also see the `channel` module on digital lines and ports.
     ///
     ///
     /// # Arguments
     ///
     /// * `name`: The name of the target device.
-    /// * `require_streamable`: If set to `true`, only channels that are streamable will be considered.
-    /// * `require_editable`: If set to `true`, only channels that are editable will be considered.
+    /// * `streamed`: If set to `true`, lists the DO ports instead of the DO lines.
     ///
     /// # Returns
     ///
-    /// A vector of strings, each representing the name of a channel that matches the given criteria.
+    /// A vector of strings, each representing the name of a compiled channel.
     ///
     /// # Example
     ///
@@ -940,38 +1021,37 @@ pub trait BaseExperiment {
     /// # use nicompiler_backend::*;
     /// let mut exp = Experiment::new();
     /// exp.add_do_device("PXI1Slot6", 1e6,);
-    /// exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
-    /// exp.add_do_channel("PXI1Slot6", 2, 0, 0.);
-    /// exp.add_do_channel("PXI1Slot6", 2, 1, 0.);
+    /// exp.add_do_channel("PXI1Slot6", 0, 0, false);
+    /// exp.add_do_channel("PXI1Slot6", 2, 0, false);
+    /// exp.add_do_channel("PXI1Slot6", 2, 1, false);
     /// exp.go_high("PXI1Slot6", "port0/line0", 0.);
     /// exp.go_high("PXI1Slot6", "port2/line0", 1.);
     /// exp.go_high("PXI1Slot6", "port2/line1", 2.);
     /// exp.compile_with_stoptime(3.);
-    /// let compiled_streamable_channels = exp.device_compiled_channel_names("PXI1Slot6", true, false);
-    /// // 2 strealable channels: "port0" and "port2"
-    /// assert_eq!(compiled_streamable_channels.len(), 2);
-    /// // 3 editable channels: "port0/line0", "port2/line0", "port2/line1"
-    /// let compiled_editable_channels = exp.device_compiled_channel_names("PXI1Slot6", false, true);
-    /// assert_eq!(compiled_editable_channels.len(), 3);
-    /// ```
-    fn device_compiled_channel_names(
-        &mut self,
-        name: &str,
-        require_streamable: bool,
-        require_editable: bool,
-    ) -> Vec<String> {
+    /// let compiled_ports = exp.device_compiled_channel_names("PXI1Slot6", true);
+    /// // 2 streamed ports: "port0" and "port2"
+    /// assert_eq!(compiled_ports.len(), 2);
+    /// // 3 lines: "port0/line0", "port2/line0", "port2/line1"
+    /// let compiled_lines = exp.device_compiled_channel_names("PXI1Slot6", false);
+    /// assert_eq!(compiled_lines.len(), 3);
+    /// ```
+    fn device_compiled_channel_names(&mut self, name: &str, streamed: bool) -> Vec<String> {
         self.device_op(name, |dev| {
-            (*dev)
-                .compiled_channels(require_streamable, require_editable)
-                .iter()
-                .map(|chan| chan.name().to_string())
-                .collect()
+            if streamed {
+                (*dev).streamed_channel_names()
+            } else {
+                (*dev)
+                    .compiled_channels()
+                    .iter()
+                    .map(|chan| chan.name().to_string())
+                    .collect()
+            }
         })
     }
 
     /// Adds a constant value instruction to the specified analogue output (AO) channel.
     ///
-    /// This method leverages the [`BaseExperiment::typed_channel_op`] function to forward the constant value
+    /// This method leverages the [`BaseExperiment::ao_channel_op`] function to forward the constant value
     /// instruction request to the targeted AO channel using the [`BaseChannel::constant`] method.
     ///
     /// # Arguments
@@ -995,19 +1075,19 @@ pub trait BaseExperiment {
     /// # use nicompiler_backend::*;
     /// let mut exp = Experiment::new();
     /// exp.add_do_device("PXI1Slot6", 1e6,);
-    /// exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
-    /// exp.add_do_channel("PXI1Slot6", 2, 0, 0.);
-    /// exp.add_do_channel("PXI1Slot6", 2, 1, 0.);
+    /// exp.add_do_channel("PXI1Slot6", 0, 0, false);
+    /// exp.add_do_channel("PXI1Slot6", 2, 0, false);
+    /// exp.add_do_channel("PXI1Slot6", 2, 1, false);
     /// exp.go_high("PXI1Slot6", "port0/line0", 0.);
     /// exp.go_high("PXI1Slot6", "port2/line0", 1.);
     /// exp.go_high("PXI1Slot6", "port2/line1", 2.);
     /// exp.compile_with_stoptime(3.);
-    /// let compiled_streamable_channels = exp.device_compiled_channel_names("PXI1Slot6", true, false);
-    /// // 2 strealable channels: "port0" and "port2"
-    /// assert_eq!(compiled_streamable_channels.len(), 2);
-    /// // 3 editable channels: "port0/line0", "port2/line0", "port2/line1"
-    /// let compiled_editable_channels = exp.device_compiled_channel_names("PXI1Slot6", false, true);
-    /// assert_eq!(compiled_editable_channels.len(), 3);
+    /// let compiled_ports = exp.device_compiled_channel_names("PXI1Slot6", true);
+    /// // 2 streamed ports: "port0" and "port2"
+    /// assert_eq!(compiled_ports.len(), 2);
+    /// // 3 lines: "port0/line0", "port2/line0", "port2/line1"
+    /// let compiled_lines = exp.device_compiled_channel_names("PXI1Slot6", false);
+    /// assert_eq!(compiled_lines.len(), 3);
     /// ```
     fn constant(
         &mut self,
@@ -1017,7 +1097,7 @@ pub trait BaseExperiment {
         duration: f64,
         value: f64,
     ) {
-        self.typed_channel_op(dev_name, chan_name, TaskType::AO, |chan| {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
             (*chan).constant(value, t, Some((duration, false)));
         });
     }
@@ -1039,14 +1119,14 @@ pub trait BaseExperiment {
     ///
     /// This method will panic if the channel is not of type AO.
     fn go_constant(&mut self, dev_name: &str, chan_name: &str, t: f64, value: f64) {
-        self.typed_channel_op(dev_name, chan_name, TaskType::AO, |chan| {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
             (*chan).constant(value, t, None);
         });
     }
 
     /// Adds a sine waveform instruction to the specified analogue output (AO) channel.
     ///
-    /// This method uses the [`BaseExperiment::typed_channel_op`] function to relay the sine instruction
+    /// This method uses the [`BaseExperiment::ao_channel_op`] function to relay the sine instruction
     /// request to the appropriate AO channel via the [`BaseChannel::add_instr`] method.
     /// See [`Instruction::new_sine`] for more detailed explanation of the sine arguments.
     ///
@@ -1061,6 +1141,9 @@ pub trait BaseExperiment {
     /// * `amplitude`: Optional amplitude of the sine waveform.
     /// * `phase`: Optional phase shift for the sine waveform.
     /// * `dc_offset`: Optional DC offset for the sine waveform.
+    /// * `time_ref`: Optional reference of the sine phase, [`TimeRef::Absolute`] by default.
+    ///   With [`TimeRef::Local`] the sine starts at `phase` wherever it is placed,
+    ///   with [`TimeRef::Continuous`] it continues the phase of the previous sine of the same frequency.
     ///
     /// # Panics
     ///
@@ -1073,7 +1156,7 @@ pub trait BaseExperiment {
     /// exp.add_ao_device("PXI1Slot3", 1e6,);
     /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
     /// // t=0, duration=1, keep_val=false, freq=10Hz, amplitude=10, phase=0(default), dc_offset=0(default)
-    /// exp.sine("PXI1Slot3", "ao0", 0., 1., false, 10., Some(10.), None, None);
+    /// exp.sine("PXI1Slot3", "ao0", 0., 1., false, 10., Some(10.), None, None, None);
     /// ```
     fn sine(
         &mut self,
@@ -1086,9 +1169,11 @@ pub trait BaseExperiment {
         amplitude: Option<f64>,
         phase: Option<f64>,
         dc_offset: Option<f64>,
+        time_ref: Option<TimeRef>,
     ) {
-        self.typed_channel_op(dev_name, chan_name, TaskType::AO, |chan| {
-            let instr = Instruction::new_sine(freq, amplitude, phase, dc_offset);
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_sine(freq, amplitude, phase, dc_offset)
+                .with_time_ref(time_ref.unwrap_or(TimeRef::Absolute));
             (*chan).add_instr(instr, t, Some((duration, keep_val)))
         });
     }
@@ -1102,13 +1187,166 @@ pub trait BaseExperiment {
         amplitude: Option<f64>,
         phase: Option<f64>,
         dc_offset: Option<f64>,
+        time_ref: Option<TimeRef>,
     ) {
-        self.typed_channel_op(dev_name, chan_name, TaskType::AO, |chan| {
-            let instr = Instruction::new_sine(freq, amplitude, phase, dc_offset);
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_sine(freq, amplitude, phase, dc_offset)
+                .with_time_ref(time_ref.unwrap_or(TimeRef::Absolute));
             (*chan).add_instr(instr, t, None)
         });
     }
 
+    /// Same as [`BaseExperiment::sine`], but the DC offset is chosen such that the sine starts from whatever the channel
+    /// outputs at `t` (the preceding instruction or its `keep_val` padding, otherwise the channel default).
+    /// See [`Instruction::with_from_current`].
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// exp.go_constant("PXI1Slot3", "ao0", 0., 2.);
+    /// // Wiggles around 2.5, starting from 2
+    /// exp.sine_from_current("PXI1Slot3", "ao0", 2e-3, 1e-3, false, 1e3, Some(0.5), Some(-0.5 * std::f64::consts::PI), Some("local")).unwrap();
+    /// ```
+    fn sine_from_current(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        keep_val: bool,
+        freq: f64,
+        amplitude: Option<f64>,
+        phase: Option<f64>,
+        time_ref: Option<TimeRef>,
+    ) {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_sine(freq, amplitude, phase, None)
+                .with_time_ref(time_ref.unwrap_or(TimeRef::Absolute))
+                .with_from_current();
+            (*chan).add_instr(instr, t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Adds a frequency chirp instruction to the specified analogue output (AO) channel.
+    ///
+    /// The frequency is swept from `f_start` at `t` to `f_end` at `t + duration`, linearly or exponentially.
+    /// The phase is continuous throughout the sweep. See [`Instruction::new_chirp`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the chirp.
+    /// * `duration`: Duration of the chirp.
+    /// * `keep_val`: Flag indicating whether to maintain the waveform's value beyond the specified duration.
+    /// * `f_start`: Frequency at the start of the chirp.
+    /// * `f_end`: Frequency at the end of the chirp.
+    /// * `exponential`: Sweep exponentially instead of linearly.
+    /// * `amplitude`: Optional amplitude of the waveform.
+    /// * `phase`: Optional phase at the start of the chirp.
+    /// * `dc_offset`: Optional DC offset of the waveform.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the designated channel is not of type AO,
+    /// or if an exponential sweep is requested with non-positive frequencies.
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6,);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// // 10 kHz -> 50 kHz linear sweep over 1 ms with amplitude 2
+    /// exp.chirp("PXI1Slot3", "ao0", 0., 1e-3, false, 1e4, 5e4, false, Some(2.), None, None);
+    /// ```
+    fn chirp(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        keep_val: bool,
+        f_start: f64,
+        f_end: f64,
+        exponential: bool,
+        amplitude: Option<f64>,
+        phase: Option<f64>,
+        dc_offset: Option<f64>,
+    ) {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_chirp(
+                f_start, f_end, t, t + duration, exponential, amplitude, phase, dc_offset,
+            );
+            (*chan).add_instr(instr, t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Adds a windowed pulse to the specified analogue output (AO) channel.
+    ///
+    /// The pulse is a carrier (sine of frequency `freq`, or a DC level if `freq` is `None`)
+    /// multiplied by a window envelope. See [`Instruction::new_pulse`] for details on the windows.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the pulse.
+    /// * `duration`: Duration of the pulse.
+    /// * `keep_val`: Flag indicating whether to keep the end value (the DC offset) after the pulse.
+    /// * `window`: Pulse window, see [`PulseWindow`]. The python wrapper accepts `"gauss"`, `"blackman"`, `"cos2"` or `"flattop"`.
+    /// * `amplitude`: Optional peak amplitude of the envelope.
+    /// * `freq`: Optional carrier frequency.
+    /// * `phase`: Optional carrier phase.
+    /// * `dc_offset`: Optional DC offset.
+    /// * `sigma`: Optional Gaussian width, only for `"gauss"`.
+    /// * `rise_time`, `fall_time`: Optional edge durations, only for `"flattop"`.
+    /// * `time_ref`: Optional reference of the carrier phase, see [`BaseExperiment::sine`].
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the designated channel is not of type AO,
+    /// if window-specific arguments are given for another window, or if `time_ref` is not absolute for a pulse without carrier.
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6,);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// // 20 us Gaussian pulse on a 100 kHz carrier with sigma = 2 us
+    /// BaseExperiment::pulse(&mut exp, "PXI1Slot3", "ao0", 0., 20e-6, false, PulseWindow::Gauss, Some(1.), Some(1e5), None, None, Some(2e-6), None, None, None);
+    /// ```
+    fn pulse(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        keep_val: bool,
+        window: PulseWindow,
+        amplitude: Option<f64>,
+        freq: Option<f64>,
+        phase: Option<f64>,
+        dc_offset: Option<f64>,
+        sigma: Option<f64>,
+        rise_time: Option<f64>,
+        fall_time: Option<f64>,
+        time_ref: Option<TimeRef>,
+    ) {
+        let instr_type = window.instr_type();
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_pulse(
+                instr_type.clone(), t, t + duration, amplitude, freq, phase, dc_offset, sigma, rise_time, fall_time,
+            )
+            .with_time_ref(time_ref.unwrap_or(TimeRef::Absolute));
+            (*chan).add_instr(instr, t, Some((duration, keep_val)))
+        });
+    }
+
     /// Sets the specified digital output (DO) channel to a high state for the given duration.
     ///
     /// # Arguments
@@ -1122,7 +1360,7 @@ pub trait BaseExperiment {
     ///
     /// This method will panic if the channel is not of type DO.
     fn high(&mut self, dev_name: &str, chan_name: &str, t: f64, duration: f64) {
-        self.typed_channel_op(dev_name, chan_name, TaskType::DO, |chan| {
+        self.do_channel_op(dev_name, chan_name, |chan| {
             (*chan).constant(1., t, Some((duration, false)));
         });
     }
@@ -1139,7 +1377,7 @@ pub trait BaseExperiment {
     ///
     /// This method will panic if the channel is not of type DO.
     fn low(&mut self, dev_name: &str, chan_name: &str, t: f64, duration: f64) {
-        self.typed_channel_op(dev_name, chan_name, TaskType::DO, |chan| {
+        self.do_channel_op(dev_name, chan_name, |chan| {
             (*chan).constant(0., t, Some((duration, false)));
         });
     }
@@ -1158,7 +1396,7 @@ pub trait BaseExperiment {
     ///
     /// This method will panic if the channel is not of type DO.
     fn go_high(&mut self, dev_name: &str, chan_name: &str, t: f64) {
-        self.typed_channel_op(dev_name, chan_name, TaskType::DO, |chan| {
+        self.do_channel_op(dev_name, chan_name, |chan| {
             (*chan).constant(1., t, None);
         });
     }
@@ -1177,10 +1415,63 @@ pub trait BaseExperiment {
     ///
     /// This method will panic if the channel is not of type DO.
     fn go_low(&mut self, dev_name: &str, chan_name: &str, t: f64) {
-        self.typed_channel_op(dev_name, chan_name, TaskType::DO, |chan| {
+        self.do_channel_op(dev_name, chan_name, |chan| {
             (*chan).constant(0., t, None);
         });
     }
+    /// Plays a periodic train of high pulses on the specified digital output (DO) channel.
+    ///
+    /// Each period starts high for `duty * period`. The train is compiled as a single instruction
+    /// (also after merging lines into ports), however many pulses it contains.
+    /// See [`Instruction::new_pulse_train`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target DO channel within the device.
+    /// * `t`: The start time of the train.
+    /// * `period`: Pulse period.
+    /// * `duty`: Duty cycle in `[0, 1]`.
+    /// * `duration`: Optional duration of the train. If `None`, the train lasts for `count` periods.
+    /// * `phase`: Optional phase as a fraction of the period.
+    /// * `count`: Optional number of pulses, the line stays low after the last one.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type DO, if neither `duration` nor `count` is given,
+    /// or if the train parameters are out of range.
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_do_device("PXI1Slot6", 1e6);
+    /// exp.add_do_channel("PXI1Slot6", 0, 0, false);
+    /// // 1000 pulses of 2 us every 10 us
+    /// exp.pulse_train("PXI1Slot6", "port0/line0", 0., 10e-6, 0.2, None, None, Some(1000));
+    /// assert_eq!(exp.channel_last_instr_end_time("PXI1Slot6", "port0/line0"), 10e-3);
+    /// ```
+    fn pulse_train(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        period: f64,
+        duty: f64,
+        duration: Option<f64>,
+        phase: Option<f64>,
+        count: Option<usize>,
+    ) {
+        let duration = match (duration, count) {
+            (Some(duration), _) => duration,
+            (None, Some(count)) => (count as f64 - phase.unwrap_or(0.0)) * period,
+            (None, None) => panic!("Pulse train on channel {} needs either a duration or a pulse count", chan_name),
+        };
+        let instr = Instruction::new_pulse_train(t, period, Some(duty), phase, count);
+        self.do_channel_op(dev_name, chan_name, |chan| {
+            (*chan).add_instr(instr.clone(), t, Some((duration, false)))
+        });
+    }
 
     /// Ramps the specified analogue output (AO) channel linearly between two values over a specified duration.
     ///
@@ -1211,116 +1502,929 @@ pub trait BaseExperiment {
         end_val: f64,
         keep_val: bool,
     ) {
-        self.typed_channel_op(dev_name, chan_name, TaskType::AO, |chan| {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
             let instr = Instruction::new_linramp(start_val, end_val, t, t+duration);
             (*chan).add_instr(instr, t, Some((duration, keep_val)))
         });
     }
 
-    /// Clears the edit cache of the specified channel.
-    ///
-    /// This method resets the channel to its pre-edit state. Clearing the edit cache can be helpful
-    /// when a sequence of edits needs to be discarded without affecting the compiled state of the channel.
-    /// Also see [`BaseExperiment::channel_op`] and [`BaseChannel::clear_edit_cache`].
-    ///
-    /// # Arguments
+    /// Same as [`BaseExperiment::linramp`], but the ramp starts from whatever the channel outputs at `t`
+    /// (the preceding instruction or its `keep_val` padding, otherwise the channel default).
     ///
-    /// * `dev_name`: The name of the target device.
-    /// * `chan_name`: The name of the target channel within the device.
+    /// This lets reusable sequence blocks ramp to a target without knowing the previous channel state.
+    /// The start value is only known once the channel is compiled, see [`Instruction::with_from_current`].
     ///
     /// # Example
-    ///
     /// ```
     /// # use nicompiler_backend::*;
     /// let mut exp = Experiment::new();
-    /// exp.add_do_device("PXI1Slot7", 1e6);
-    /// exp.add_do_channel("PXI1Slot7", 0, 7, 0.);
-    /// exp.go_high("PXI1Slot7", "port0/line7", 0.);
-    /// assert_eq!(exp.is_fresh_compiled(), false);
-    /// exp.channel_clear_edit_cache("PXI1Slot7", "port0/line7");
-    /// assert_eq!(exp.is_fresh_compiled(), true);
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// exp.linramp_from_current("PXI1Slot3", "ao0", 2e-3, 1e-3, 5., true);
+    /// // Placed before the ramp, but added afterwards - the ramp still starts from 2
+    /// exp.go_constant("PXI1Slot3", "ao0", 0., 2.);
+    /// exp.compile(Some(4e-3));
+    /// let sig = BaseExperiment::channel_calc_signal_nsamps(&mut exp, "PXI1Slot3", "ao0", 0., 4e-3, 4001);
+    /// assert_eq!(sig[2000], 2.);
+    /// assert!((sig[2500] - 3.5).abs() < 1e-9);
     /// ```
-    fn channel_clear_edit_cache(&mut self, dev_name: &str, chan_name: &str) {
-        self.channel_op(dev_name, chan_name, |chan| (*chan).clear_edit_cache());
+    fn linramp_from_current(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        end_val: f64,
+        keep_val: bool,
+    ) {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_linramp(end_val, end_val, t, t + duration).with_from_current();
+            (*chan).add_instr(instr, t, Some((duration, keep_val)))
+        });
     }
 
-    /// Calculates the sampled signal for a given channel over a specified time interval.
+    /// Smoothly ramps the specified analogue output (AO) channel between two values over a specified duration.
     ///
-    /// The function computes the signal values based on the given start and end times,
-    /// along with the number of samples desired.
+    /// Same as [`BaseExperiment::linramp`], but the value follows a smooth S-shaped profile with vanishing
+    /// (or, for `"tanh"`, small) slope at both ends, which is what adiabatic transfers call for.
     ///
     /// # Arguments
     ///
-    /// * `dev_name`: The name of the device associated with the channel.
-    /// * `chan_name`: The name of the channel for which the signal is calculated.
-    /// * `start_time`: The starting time of the sampling interval (in seconds).
-    /// * `end_time`: The ending time of the sampling interval (in seconds).
-    /// * `num_samps`: The number of samples to be computed over the specified interval.
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the ramp.
+    /// * `duration`: The duration of the ramp.
+    /// * `start_val`: The value at the start of the ramp.
+    /// * `end_val`: The value at the end of the ramp.
+    /// * `shape`: Ramp profile, see [`RampShape`]. The python wrapper accepts `"tanh"`, `"minjerk"`, `"cos"` or `"blackman"`.
+    /// * `keep_val`: Flag indicating whether to maintain `end_val` after the ramp has completed.
+    /// * `steepness`: Optional steepness of the `"tanh"` ramp. Must be `None` for other shapes.
     ///
-    /// # Returns
+    /// # Panics
     ///
-    /// Returns a vector of `f64` containing the signal values sampled over the interval.
+    /// This method will panic if the channel is not of type AO, or if `steepness` is given for a shape other than `Tanh`.
     ///
     /// # Example
-    ///
     /// ```
     /// # use nicompiler_backend::*;
     /// let mut exp = Experiment::new();
-    /// exp.add_do_device("PXI1Slot7", 1e6);
-    /// exp.add_do_channel("PXI1Slot7", 0, 7, 0.);
-    /// exp.go_high("PXI1Slot7", "port0/line7", 0.5);
-    /// exp.compile_with_stoptime(1.);
-    /// let sig = exp.channel_calc_signal_nsamps("PXI1Slot7", "port0/line7", 0., 1., 10);
-    /// assert_eq!(sig[0], 0.);
-    /// assert_eq!(sig[sig.len() - 1], 1.);
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// BaseExperiment::smooth_ramp(&mut exp, "PXI1Slot3", "ao0", 0., 1e-3, 0., 5., RampShape::MinJerk, true, None);
+    /// BaseExperiment::smooth_ramp(&mut exp, "PXI1Slot3", "ao0", 2e-3, 1e-3, 5., 0., RampShape::Tanh, true, Some(3.));
     /// ```
-    fn channel_calc_signal_nsamps(
+    fn smooth_ramp(
         &mut self,
         dev_name: &str,
         chan_name: &str,
-        start_time: f64,
-        end_time: f64,
-        num_samps: usize,
-    ) -> Vec<f64> {
-        self.channel_op(dev_name, chan_name, |chan| {
-            (*chan).calc_signal_nsamps(start_time, end_time, num_samps)
-        })
+        t: f64,
+        duration: f64,
+        start_val: f64,
+        end_val: f64,
+        shape: RampShape,
+        keep_val: bool,
+        steepness: Option<f64>,
+    ) {
+        let instr = shape
+            .try_new_ramp(start_val, end_val, t, t + duration, steepness)
+            .unwrap_or_else(|err| panic!("{}", err));
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            (*chan).add_instr(instr.clone(), t, Some((duration, keep_val)))
+        });
     }
 
-    /// Clears the compile cache of the specified channel.
+    /// Ramps the specified analogue output (AO) channel exponentially between two values over a specified duration.
     ///
-    /// By invoking this method, any compiled data related to the channel will be removed. This is useful when
-    /// the compiled state of a channel needs to be invalidated, such as after a series of edits or changes.
-    /// Also see [`BaseExperiment::channel_op`] and [`BaseChannel::clear_compile_cache`].
+    /// The value changes by a constant factor per unit time, hitting `start_val` at `t` and `end_val` at `t + duration`.
+    /// Both values must be non-zero and have the same sign. See [`Instruction::new_expramp`].
     ///
     /// # Arguments
     ///
     /// * `dev_name`: The name of the target device.
-    /// * `chan_name`: The name of the target channel within the device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the ramp.
+    /// * `duration`: The duration of the ramp.
+    /// * `start_val`: The value at the start of the ramp.
+    /// * `end_val`: The value at the end of the ramp.
+    /// * `keep_val`: Flag indicating whether to maintain `end_val` after the ramp has completed.
     ///
-    /// # Example
+    /// # Panics
     ///
-    /// ```
-    /// # use nicompiler_backend::*;
-    /// let mut exp = Experiment::new();
-    /// exp.add_do_device("PXI1Slot7", 1e6);
-    /// exp.add_do_channel("PXI1Slot7", 0, 7, 0.);
-    /// exp.go_high("PXI1Slot7", "port0/line7", 0.);
-    /// exp.compile(false);
-    /// assert_eq!(exp.is_compiled(), true);
-    /// exp.channel_clear_compile_cache("PXI1Slot7", "port0/line7");
-    /// assert_eq!(exp.is_compiled(), false);
-    /// ```
-    fn channel_clear_compile_cache(&mut self, dev_name: &str, chan_name: &str) {
-        self.channel_op(dev_name, chan_name, |chan| (*chan).clear_compile_cache());
-    }
-
-    fn channel_last_instr_end_time(&mut self, dev_name: &str, chan_name: &str) -> f64 {
-        self.channel_op(dev_name, chan_name, |chan| {
-            (*chan).last_instr_end_time()
-        })
+    /// This method will panic if the channel is not of type AO, or if `start_val` and `end_val` differ in sign.
+    fn expramp(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        start_val: f64,
+        end_val: f64,
+        keep_val: bool,
+    ) {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_expramp(start_val, end_val, t, t + duration);
+            (*chan).add_instr(instr, t, Some((duration, keep_val)))
+        });
     }
-}
+
+    /// Exponential relaxation from `start_val` towards `target_val` with time constant `tau`
+    /// on the specified analogue output (AO) channel. See [`Instruction::new_expramp_tau`].
+    ///
+    /// Unlike [`BaseExperiment::expramp`], `target_val` is only approached asymptotically.
+    /// With `keep_val = true` the value reached at `t + duration` is kept.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO, or if `tau` is not positive.
+    fn expramp_tau(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        start_val: f64,
+        target_val: f64,
+        tau: f64,
+        keep_val: bool,
+    ) {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_expramp_tau(start_val, target_val, tau, t);
+            (*chan).add_instr(instr, t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Ramps the specified analogue output (AO) channel logarithmically between two values.
+    ///
+    /// `tau` sets the time scale of the logarithm: the shorter it is compared to `duration`,
+    /// the faster the initial change. See [`Instruction::new_logramp`].
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO, or if `tau` is not positive.
+    fn logramp(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        start_val: f64,
+        end_val: f64,
+        tau: f64,
+        keep_val: bool,
+    ) {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_logramp(start_val, end_val, t, t + duration, tau);
+            (*chan).add_instr(instr, t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Ramps the specified analogue output (AO) channel between two values following a power law
+    /// `start_val + (end_val - start_val) * ((t' - t) / duration)^exponent`. See [`Instruction::new_powramp`].
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO, or if `exponent` is not positive.
+    fn powramp(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        start_val: f64,
+        end_val: f64,
+        exponent: f64,
+        keep_val: bool,
+    ) {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_powramp(start_val, end_val, t, t + duration, exponent);
+            (*chan).add_instr(instr, t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Adds an arbitrary, already constructed [`Instruction`] to the specified analogue output (AO) channel.
+    ///
+    /// This is the way to place composite instructions (see [`Instruction::new_sum`], [`Instruction::new_scale`], etc.)
+    /// or any other instruction without a dedicated method. Instruction time arguments (e.g. `start_time` of
+    /// `LINRAMP`) are absolute and are not adjusted to `t`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `instr`: The instruction to add.
+    /// * `t`: The start time of the instruction.
+    /// * `dur_spec`: `Some((duration, keep_val))` for instructions with a specific end, or `None`
+    ///   to run until the next instruction (same as `go_*` methods).
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO.
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// // Sine riding on a ramp, clipped to 1V
+    /// let instr = Instruction::new_clip(
+    ///     Instruction::new_sum(vec![
+    ///         Instruction::new_linramp(0., 1., 0., 1e-3),
+    ///         Instruction::new_sine(1e4, Some(0.1), None, None),
+    ///     ]),
+    ///     None,
+    ///     Some(1.),
+    /// );
+    /// exp.add_instr("PXI1Slot3", "ao0", instr, 0., Some((1e-3, true)));
+    /// ```
+    fn add_instr(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        instr: Instruction,
+        t: f64,
+        dur_spec: Option<(f64, bool)>,
+    ) {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            (*chan).add_instr(instr.clone(), t, dur_spec)
+        });
+    }
+
+    /// Removes the instruction which starts at time `t` or covers it from the specified channel.
+    /// See [`BaseChannel::remove_instr`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_do_device("PXI1Slot7", 1e6);
+    /// exp.add_do_channel("PXI1Slot7", 0, 7, false);
+    /// exp.high("PXI1Slot7", "port0/line7", 0., 1e-3);
+    /// exp.high("PXI1Slot7", "port0/line7", 2e-3, 1e-3);
+    /// exp.channel_remove_instr("PXI1Slot7", "port0/line7", 2.5e-3);
+    /// assert_eq!(exp.channel_last_instr_end_time("PXI1Slot7", "port0/line7"), 1e-3);
+    /// ```
+    fn channel_remove_instr(&mut self, dev_name: &str, chan_name: &str, t: f64) {
+        self.channel_op(dev_name, chan_name, |chan| {
+            (*chan).remove_instr(t);
+        });
+    }
+
+    /// Replaces the function of the instruction which starts at time `t` or covers it, keeping its interval.
+    /// See [`BaseChannel::replace_instr`].
+    fn channel_replace_instr(&mut self, dev_name: &str, chan_name: &str, t: f64, instr: Instruction) {
+        self.channel_op(dev_name, chan_name, |chan| {
+            (*chan).replace_instr(t, instr.clone())
+        });
+    }
+
+    /// Moves the instruction which starts at time `t` or covers it to start at `new_t`,
+    /// with the same collision checks as [`BaseExperiment::add_instr`]. See [`BaseChannel::move_instr`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot6", 1e6);
+    /// exp.add_ao_channel("PXI1Slot6", 0, 0.);
+    /// exp.linramp("PXI1Slot6", "ao0", 0., 1e-3, 0., 1., true);
+    /// exp.channel_move_instr("PXI1Slot6", "ao0", 0., 2e-3);
+    /// assert_eq!(exp.channel_value_bounds("PXI1Slot6", "ao0", 0., 2e-3), (0., 0.));
+    /// assert_eq!(exp.channel_value_bounds("PXI1Slot6", "ao0", 2e-3, 3e-3), (0., 1.));
+    /// ```
+    fn channel_move_instr(&mut self, dev_name: &str, chan_name: &str, t: f64, new_t: f64) {
+        self.channel_op(dev_name, chan_name, |chan| (*chan).move_instr(t, new_t));
+    }
+
+    /// Adds a math-expression waveform to the specified analogue output (AO) channel.
+    ///
+    /// `expr` is a function of the absolute time `t` (in seconds) and the named parameters `params`,
+    /// e.g. `"A*exp(-(t-t0)^2/(2*s^2)) + c"`. See [`crate::expression`] for the supported syntax
+    /// and [`Instruction::new_expr`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the instruction.
+    /// * `duration`: Duration of the instruction.
+    /// * `expr`: The expression string.
+    /// * `params`: Values of the named parameters used in `expr`.
+    /// * `keep_val`: Flag indicating whether to maintain the last value after the instruction ends.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO, or if `expr` can't be parsed
+    /// (syntax error, unknown function or undefined variable).
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// let mut params = InstrArgs::new();
+    /// params.insert("A".to_string(), 1.5);
+    /// params.insert("t0".to_string(), 0.5e-3);
+    /// params.insert("s".to_string(), 0.1e-3);
+    /// BaseExperiment::expr(&mut exp, "PXI1Slot3", "ao0", 0., 1e-3, "A*exp(-(t-t0)^2/(2*s^2))", params, false);
+    /// ```
+    fn expr(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        expr: &str,
+        params: InstrArgs,
+        keep_val: bool,
+    ) {
+        let instr = Instruction::new_expr(expr, params)
+            .unwrap_or_else(|msg| panic!("Failed to parse expression \"{}\": {}", expr, msg));
+        self.add_instr(dev_name, chan_name, instr, t, Some((duration, keep_val)));
+    }
+
+    /// Adds an instruction of a registered [`Waveform`](crate::waveform::Waveform) kind to the specified analogue output (AO) channel.
+    ///
+    /// See [`crate::waveform`] for how downstream crates define and register waveform kinds.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the instruction.
+    /// * `duration`: Duration of the instruction.
+    /// * `kind`: Name the waveform kind was registered with.
+    /// * `args`: Arguments passed to the waveform factory.
+    /// * `keep_val`: Flag indicating whether to maintain the last value after the instruction ends.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO, if `kind` is not registered,
+    /// or if a required argument is missing.
+    fn custom(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        kind: &str,
+        args: InstrArgs,
+        keep_val: bool,
+    ) {
+        let instr = Instruction::new_custom(kind, args);
+        self.add_instr(dev_name, chan_name, instr, t, Some((duration, keep_val)));
+    }
+
+    /// Plays back an arbitrary sampled waveform on the specified analogue output (AO) channel.
+    ///
+    /// Sample `i` of `samples` is played at time `t + i / samp_rate`. The buffer is resampled onto the
+    /// channel clock grid with the `interp` scheme, so `samp_rate` doesn't need to match the device sample rate.
+    /// The instruction spans `samples.len() / samp_rate` seconds. See [`Instruction::new_arb`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the waveform.
+    /// * `samples`: The waveform samples.
+    /// * `samp_rate`: Sample rate of the `samples` buffer.
+    /// * `interp`: Interpolation scheme used for resampling.
+    /// * `keep_val`: Flag indicating whether to maintain the last sample value after the waveform ends.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO, or if `samples` is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// // 3 samples at 1 kHz rate, linearly interpolated on the 1 MHz grid.
+    /// // The python-exposed `Experiment::arb` takes a numpy array instead, so call the trait method explicitly
+    /// BaseExperiment::arb(&mut exp, "PXI1Slot3", "ao0", 0., vec![0., 1., 0.5], 1e3, Interp::Linear, true);
+    /// assert_eq!(exp.channel_last_instr_end_time("PXI1Slot3", "ao0"), 3e-3);
+    /// ```
+    fn arb(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        samples: Vec<f64>,
+        samp_rate: f64,
+        interp: Interp,
+        keep_val: bool,
+    ) {
+        let duration = samples.len() as f64 / samp_rate;
+        // Cloning the instruction inside the closure only copies the `Arc` to the sample buffer
+        let instr = Instruction::new_arb(samples, samp_rate, t, interp);
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            (*chan).add_instr(instr.clone(), t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Piecewise-linear waveform through `(time, value)` breakpoints on the specified analogue output (AO) channel.
+    ///
+    /// Breakpoint times are relative to `t` and must be strictly increasing. The instruction spans until
+    /// the last breakpoint. A single `pwl` instruction replaces a chain of [`BaseExperiment::linramp`] calls.
+    /// See [`Instruction::new_pwl`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the waveform.
+    /// * `breakpoints`: List of `(time, value)` pairs.
+    /// * `keep_val`: Flag indicating whether to maintain the last breakpoint value after the waveform ends.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO, if fewer than two breakpoints are given
+    /// or if breakpoint times are not strictly increasing.
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// exp.pwl("PXI1Slot3", "ao0", 0., vec![(0., 0.), (1e-3, 5.), (3e-3, 1.)], true);
+    /// assert_eq!(exp.channel_last_instr_end_time("PXI1Slot3", "ao0"), 3e-3);
+    /// ```
+    fn pwl(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        breakpoints: Vec<(f64, f64)>,
+        keep_val: bool,
+    ) {
+        let duration = breakpoints.last().map_or(0.0, |(time, _val)| *time);
+        let instr = Instruction::new_pwl(breakpoints, t);
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            (*chan).add_instr(instr.clone(), t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Same as [`BaseExperiment::pwl`], but interpolates the breakpoints with a natural cubic spline.
+    /// See [`Instruction::new_spline`] for details.
+    fn spline(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        breakpoints: Vec<(f64, f64)>,
+        keep_val: bool,
+    ) {
+        let duration = breakpoints.last().map_or(0.0, |(time, _val)| *time);
+        let instr = Instruction::new_spline(breakpoints, t);
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            (*chan).add_instr(instr.clone(), t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Polynomial waveform `sum_k coefs[k] * (time - t)^k` on the specified analogue output (AO) channel.
+    ///
+    /// Coefficients are listed lowest order first and refer to the time elapsed since `t`.
+    /// See [`Instruction::new_poly`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the waveform.
+    /// * `duration`: Duration of the waveform.
+    /// * `coefs`: Polynomial coefficients, lowest order first.
+    /// * `keep_val`: Flag indicating whether to maintain the end value after the waveform ends.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO or if `coefs` is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// // Parabola from 1 V, curving down over 1 ms
+    /// exp.poly("PXI1Slot3", "ao0", 0., 1e-3, vec![1., 0., -1e6], true);
+    /// ```
+    fn poly(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        coefs: Vec<f64>,
+        keep_val: bool,
+    ) {
+        let instr = Instruction::new_poly(coefs, t);
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            (*chan).add_instr(instr.clone(), t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Fourier series `dc_offset + sum_n amplitudes[n-1] * sin(2 pi n freq t + phases[n-1])` on the
+    /// specified analogue output (AO) channel. See [`Instruction::new_fourier`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the waveform.
+    /// * `duration`: Duration of the waveform.
+    /// * `keep_val`: Flag indicating whether to maintain the waveform's value beyond the specified duration.
+    /// * `freq`: Fundamental frequency.
+    /// * `amplitudes`: Amplitudes of the fundamental and the following harmonics.
+    /// * `phases`: Optional phases, one per amplitude.
+    /// * `dc_offset`: Optional DC offset of the waveform.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO, if `amplitudes` is empty,
+    /// or if `phases` has a different length than `amplitudes`.
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// // Square-wave approximation from the first three odd harmonics
+    /// let amps = vec![1., 0., 1. / 3., 0., 1. / 5.];
+    /// exp.fourier("PXI1Slot3", "ao0", 0., 1e-3, false, 1e4, amps, None, None);
+    /// ```
+    fn fourier(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        keep_val: bool,
+        freq: f64,
+        amplitudes: Vec<f64>,
+        phases: Option<Vec<f64>>,
+        dc_offset: Option<f64>,
+    ) {
+        let instr = Instruction::new_fourier(freq, amplitudes, phases, dc_offset);
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            (*chan).add_instr(instr.clone(), t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Adds seeded pseudo-random noise to the specified analogue output (AO) channel.
+    ///
+    /// The noise is drawn per sample index of the channel clock, so the streamed waveform matches
+    /// the [`BaseExperiment::channel_calc_signal_nsamps`] preview exactly and is reproduced by reusing the seed.
+    /// See [`Instruction::new_noise`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target AO channel within the device.
+    /// * `t`: The start time of the noise.
+    /// * `duration`: Duration of the noise.
+    /// * `keep_val`: Flag indicating whether to maintain the last noise value beyond the specified duration.
+    /// * `amplitude`: Half-width of the uniform distribution, or standard deviation of the Gaussian one.
+    /// * `seed`: Seed of the noise stream, at most [`MAX_INT_ARG`] (`2^53`).
+    /// * `gaussian`: Draw from a normal instead of a uniform distribution.
+    /// * `bandwidth`: Optional bandwidth limit.
+    /// * `dc_offset`: Optional DC offset of the waveform.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the channel is not of type AO or if `bandwidth` is not positive.
+    ///
+    /// # Example
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// // 10 mV rms Gaussian noise limited to 10 kHz around 1 V
+    /// exp.noise("PXI1Slot3", "ao0", 0., 1e-3, false, 0.01, 1234, true, Some(1e4), Some(1.));
+    /// ```
+    fn noise(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        t: f64,
+        duration: f64,
+        keep_val: bool,
+        amplitude: f64,
+        seed: u64,
+        gaussian: bool,
+        bandwidth: Option<f64>,
+        dc_offset: Option<f64>,
+    ) {
+        self.ao_channel_op(dev_name, chan_name, |chan| {
+            let instr = Instruction::new_noise(chan.samp_rate(), amplitude, seed, gaussian, bandwidth, dc_offset);
+            (*chan).add_instr(instr, t, Some((duration, keep_val)))
+        });
+    }
+
+    /// Clears the edit cache of the specified channel.
+    ///
+    /// This method resets the channel to its pre-edit state. Clearing the edit cache can be helpful
+    /// when a sequence of edits needs to be discarded without affecting the compiled state of the channel.
+    /// Also see [`BaseExperiment::channel_op`] and [`BaseChannel::clear_edit_cache`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target channel within the device.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_do_device("PXI1Slot7", 1e6);
+    /// exp.add_do_channel("PXI1Slot7", 0, 7, false);
+    /// exp.go_high("PXI1Slot7", "port0/line7", 0.);
+    /// assert_eq!(exp.is_fresh_compiled(), false);
+    /// exp.channel_clear_edit_cache("PXI1Slot7", "port0/line7");
+    /// assert_eq!(exp.is_fresh_compiled(), true);
+    /// ```
+    fn channel_clear_edit_cache(&mut self, dev_name: &str, chan_name: &str) {
+        self.channel_op(dev_name, chan_name, |chan| (*chan).clear_edit_cache());
+    }
+
+    /// Calculates the sampled signal for a given channel over a specified time interval.
+    ///
+    /// The function computes the signal values based on the given start and end times,
+    /// along with the number of samples desired.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the device associated with the channel.
+    /// * `chan_name`: The name of the channel for which the signal is calculated.
+    /// * `start_time`: The starting time of the sampling interval (in seconds).
+    /// * `end_time`: The ending time of the sampling interval (in seconds).
+    /// * `num_samps`: The number of samples to be computed over the specified interval.
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector of `f64` containing the signal values sampled over the interval.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_do_device("PXI1Slot7", 1e6);
+    /// exp.add_do_channel("PXI1Slot7", 0, 7, false);
+    /// exp.go_high("PXI1Slot7", "port0/line7", 0.5);
+    /// exp.compile_with_stoptime(1.);
+    /// let sig = BaseExperiment::channel_calc_signal_nsamps(&mut exp, "PXI1Slot7", "port0/line7", 0., 1., 10);
+    /// assert_eq!(sig[0], 0.);
+    /// assert_eq!(sig[sig.len() - 1], 1.);
+    /// ```
+    fn channel_calc_signal_nsamps(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        start_time: f64,
+        end_time: f64,
+        num_samps: usize,
+    ) -> Vec<f64> {
+        self.channel_op(dev_name, chan_name, |chan| {
+            (*chan).calc_signal_nsamps(start_time, end_time, num_samps)
+        })
+    }
+
+    /// Minimum and maximum value of a channel over `[start_time, end_time]`, computed from the edit cache
+    /// without compiling or sampling. See [`BaseChannel::value_bounds`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot6", 1e6);
+    /// exp.add_ao_channel("PXI1Slot6", 0, 0.);
+    /// exp.sine("PXI1Slot6", "ao0", 0., 1., false, 10., Some(2.), None, Some(1.), None);
+    /// assert_eq!(exp.channel_value_bounds("PXI1Slot6", "ao0", 0., 1.), (-1., 3.));
+    /// ```
+    fn channel_value_bounds(&mut self, dev_name: &str, chan_name: &str, start_time: f64, end_time: f64) -> (f64, f64) {
+        self.channel_op(dev_name, chan_name, |chan| {
+            (*chan).value_bounds(start_time, end_time)
+        })
+    }
+
+    /// Bounds of the sampled output of a channel in volts, see [`BaseChannel::output_bounds`].
+    /// Same as [`BaseExperiment::channel_value_bounds`] unless the channel is calibrated.
+    fn channel_output_bounds(&mut self, dev_name: &str, chan_name: &str, start_time: f64, end_time: f64) -> (f64, f64) {
+        self.channel_op(dev_name, chan_name, |chan| {
+            (*chan).output_bounds(start_time, end_time)
+        })
+    }
+
+    /// Edit-cache instructions of a channel overlapping `[start_time, end_time]`, as
+    /// `(start_time, end_spec, instr)` with `end_spec = Some((end_time, keep_val))` for instructions with
+    /// a specified duration. See [`BaseChannel::instrs_in_window`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot6", 1e6);
+    /// exp.add_ao_channel("PXI1Slot6", 0, 0.);
+    /// exp.linramp("PXI1Slot6", "ao0", 0., 1e-3, 0., 1., true);
+    /// exp.go_constant("PXI1Slot6", "ao0", 2e-3, 0.5);
+    /// let instrs = BaseExperiment::channel_instr_list(&mut exp, "PXI1Slot6", "ao0", 0., f64::INFINITY);
+    /// assert_eq!((instrs[0].0, instrs[0].1), (0., Some((1e-3, true))));
+    /// assert_eq!((instrs[1].0, instrs[1].1), (2e-3, None));
+    /// ```
+    fn channel_instr_list(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        start_time: f64,
+        end_time: f64,
+    ) -> Vec<InstrListEntry> {
+        self.channel_op(dev_name, chan_name, |chan| {
+            let clock_period = (*chan).clock_period();
+            (*chan)
+                .instrs_in_window(start_time, end_time)
+                .into_iter()
+                .map(|instr_book| (
+                    instr_book.start_pos as f64 * clock_period,
+                    instr_book.end_spec.map(|(end_pos, keep_val)| (end_pos as f64 * clock_period, keep_val)),
+                    instr_book.instr.clone(),
+                ))
+                .collect()
+        })
+    }
+
+    /// Compiled segments of a channel overlapping `[start_time, end_time]`, as `(start_time, end_time, instr)`.
+    /// See [`BaseChannel::compiled_segments`].
+    fn channel_compiled_segments(
+        &mut self,
+        dev_name: &str,
+        chan_name: &str,
+        start_time: f64,
+        end_time: f64,
+    ) -> Vec<(f64, f64, Instruction)> {
+        self.channel_op(dev_name, chan_name, |chan| {
+            let clock_period = (*chan).clock_period();
+            (*chan)
+                .compiled_segments(start_time, end_time)
+                .into_iter()
+                .map(|(start_pos, end_pos, instr)| (start_pos as f64 * clock_period, end_pos as f64 * clock_period, instr.clone()))
+                .collect()
+        })
+    }
+
+    /// Clears the compile cache of the specified channel.
+    ///
+    /// By invoking this method, any compiled data related to the channel will be removed. This is useful when
+    /// the compiled state of a channel needs to be invalidated, such as after a series of edits or changes.
+    /// Also see [`BaseExperiment::channel_op`] and [`BaseChannel::clear_compile_cache`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dev_name`: The name of the target device.
+    /// * `chan_name`: The name of the target channel within the device.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_do_device("PXI1Slot7", 1e6);
+    /// exp.add_do_channel("PXI1Slot7", 0, 7, false);
+    /// exp.go_high("PXI1Slot7", "port0/line7", 0.);
+    /// exp.compile(false);
+    /// assert_eq!(exp.is_compiled(), true);
+    /// exp.channel_clear_compile_cache("PXI1Slot7", "port0/line7");
+    /// assert_eq!(exp.is_compiled(), false);
+    /// ```
+    fn channel_clear_compile_cache(&mut self, dev_name: &str, chan_name: &str) {
+        self.channel_op(dev_name, chan_name, |chan| (*chan).clear_compile_cache());
+    }
+
+    fn channel_last_instr_end_time(&mut self, dev_name: &str, chan_name: &str) -> f64 {
+        self.channel_op(dev_name, chan_name, |chan| {
+            (*chan).last_instr_end_time()
+        })
+    }
+
+    /// Maximum slew rate of an AO channel, `None` if it is not limited. See [`BaseChannel::max_slew_rate`].
+    fn channel_max_slew_rate(&mut self, dev_name: &str, chan_name: &str) -> Option<f64> {
+        self.ao_channel_op(dev_name, chan_name, |chan| (*chan).max_slew_rate())
+    }
+
+    /// Limits how fast the output of an AO channel may change (in V/s), `None` removes the limit.
+    ///
+    /// Compilation then panics if any step or slope of the channel signal exceeds the limit.
+    /// See [`AoChannel::set_max_slew_rate`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// exp.channel_set_max_slew_rate("PXI1Slot3", "ao0", Some(1e4));
+    /// exp.linramp_from_current("PXI1Slot3", "ao0", 0., 1e-3, 5., true);
+    /// exp.linramp_from_current("PXI1Slot3", "ao0", 2e-3, 1e-3, 0., true);
+    /// exp.compile(None);
+    /// ```
+    fn channel_set_max_slew_rate(&mut self, dev_name: &str, chan_name: &str, max_slew_rate: Option<f64>) {
+        self.ao_channel_op(dev_name, chan_name, |chan| (*chan).set_max_slew_rate(max_slew_rate));
+    }
+
+    /// Value a channel takes at the reset tick, see [`BaseExperiment::add_reset_instr`]. DO lines give `0` or `1`.
+    fn channel_reset_value(&mut self, dev_name: &str, chan_name: &str) -> f64 {
+        self.channel_op(dev_name, chan_name, |chan| (*chan).reset_value())
+    }
+
+    /// Sets the value an AO channel takes at the reset tick, see [`AoChannel::set_reset_value`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// assert_eq!(exp.channel_reset_value("PXI1Slot3", "ao0"), 0.);
+    /// exp.ao_channel_set_reset_value("PXI1Slot3", "ao0", 1.5);
+    /// assert_eq!(exp.channel_reset_value("PXI1Slot3", "ao0"), 1.5);
+    /// ```
+    fn ao_channel_set_reset_value(&mut self, dev_name: &str, chan_name: &str, reset_value: f64) {
+        self.ao_channel_op(dev_name, chan_name, |chan| (*chan).set_reset_value(reset_value));
+    }
+
+    /// Sets the state a DO line takes at the reset tick, see [`DoChannel::set_reset_value`].
+    fn do_channel_set_reset_value(&mut self, dev_name: &str, chan_name: &str, reset_value: bool) {
+        self.do_channel_op(dev_name, chan_name, |chan| (*chan).set_reset_value(reset_value));
+    }
+
+    /// Output limits `(min_val, max_val)` of a channel, `None` for DO lines. See [`BaseChannel::output_limits`].
+    fn channel_output_limits(&mut self, dev_name: &str, chan_name: &str) -> Option<(f64, f64)> {
+        self.channel_op(dev_name, chan_name, |chan| (*chan).output_limits())
+    }
+
+    /// Restricts the output of an AO channel to `[min_val, max_val]` (in V), see [`AoChannel::set_output_limits`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 0.);
+    /// exp.ao_channel_set_output_limits("PXI1Slot3", "ao0", 0., 5.);
+    /// assert_eq!(exp.channel_output_limits("PXI1Slot3", "ao0"), Some((0., 5.)));
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///     exp.linramp("PXI1Slot3", "ao0", 0., 1., 0., 50., true)
+    /// }));
+    /// assert!(result.is_err());
+    /// ```
+    fn ao_channel_set_output_limits(&mut self, dev_name: &str, chan_name: &str, min_val: f64, max_val: f64) {
+        self.ao_channel_op(dev_name, chan_name, |chan| (*chan).set_output_limits(min_val, max_val));
+    }
+
+    /// Physical unit instructions on an AO channel are written in, see [`AoChannel::unit`].
+    fn channel_unit(&mut self, dev_name: &str, chan_name: &str) -> String {
+        self.ao_channel_op(dev_name, chan_name, |chan| (*chan).unit().to_string())
+    }
+
+    /// Lets instructions on an AO channel be written in `unit`, converted to volts with `calibration`.
+    /// See [`AoChannel::set_calibration`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nicompiler_backend::*;
+    /// let mut exp = Experiment::new();
+    /// exp.add_ao_device("PXI1Slot3", 1e6);
+    /// exp.add_ao_channel("PXI1Slot3", 0, 80.);
+    /// // AOM driver frequency, measured between 80 and 120 MHz
+    /// let calibration = Calibration::new_table(vec![80., 100., 120.], vec![0.5, 1.5, 4.5]).unwrap();
+    /// exp.ao_channel_set_calibration("PXI1Slot3", "ao0", "MHz", calibration);
+    /// exp.linramp("PXI1Slot3", "ao0", 0., 1e-3, 80., 110., true);
+    /// exp.compile(Some(2e-3));
+    /// assert_eq!(BaseExperiment::channel_calc_signal_nsamps(&mut exp, "PXI1Slot3", "ao0", 1e-3, 2e-3, 10)[0], 3.);
+    /// ```
+    fn ao_channel_set_calibration(&mut self, dev_name: &str, chan_name: &str, unit: &str, calibration: Calibration) {
+        self.ao_channel_op(dev_name, chan_name, |chan| (*chan).set_calibration(unit, calibration.clone()));
+    }
+
+    /// Removes the calibration of an AO channel, instructions are in volts again.
+    fn ao_channel_clear_calibration(&mut self, dev_name: &str, chan_name: &str) {
+        self.ao_channel_op(dev_name, chan_name, |chan| (*chan).clear_calibration());
+    }
+}
 
 /// A concrete struct consisting of a collection of devices.
 ///
@@ -1369,253 +2473,836 @@ pub struct Experiment {
 ///
 /// This will generate the required implementations and additional Python bindings for `CustomExperiment`.
 
-#[macro_export]
-macro_rules! impl_exp_boilerplate {
-    ($exp_type: ty) => {
-        impl BaseExperiment for $exp_type {
-            fn devices(&self) -> &IndexMap<String, Device> {
-                &self.devices
+#[macro_export]
+macro_rules! impl_exp_boilerplate {
+    ($exp_type: ty) => {
+        impl BaseExperiment for $exp_type {
+            fn devices(&self) -> &IndexMap<String, Device> {
+                &self.devices
+            }
+            fn devices_(&mut self) -> &mut IndexMap<String, Device> {
+                &mut self.devices
+            }
+        }
+
+        #[pymethods]
+        impl $exp_type {
+            fn add_ao_device(&mut self, name: &str, samp_rate: f64) {
+                BaseExperiment::add_ao_device(self, name, samp_rate);
+            }
+
+            fn add_do_device(&mut self, name: &str, samp_rate: f64) {
+                BaseExperiment::add_do_device(self, name, samp_rate);
+            }
+
+            pub fn last_instr_end_time(&self) -> f64 {
+                BaseExperiment::last_instr_end_time(self)
+            }
+
+            pub fn total_run_time(&self) -> f64 {
+                BaseExperiment::total_run_time(self)
+            }
+
+            /* pub fn check_trig_config(&self) {
+                BaseExperiment::check_trig_config(self)
+            } */
+
+            pub fn compile(&mut self, stop_time: Option<f64>) -> PyResult<f64> {
+                // Compiling evaluates instructions (padding values, slew rate checks), which may call python functions
+                self.catch_pyfunc_error(|exp| BaseExperiment::compile(exp, stop_time))
+            }
+
+            pub fn is_edited(&self) -> bool {
+                BaseExperiment::is_edited(self)
+            }
+
+            pub fn is_compiled(&self) -> bool {
+                BaseExperiment::is_compiled(self)
+            }
+
+            pub fn is_fresh_compiled(&self) -> bool {
+                BaseExperiment::is_fresh_compiled(self)
+            }
+
+            pub fn clear_edit_cache(&mut self) {
+                BaseExperiment::clear_edit_cache(self);
+            }
+
+            pub fn add_reset_instr(&mut self, reset_time: Option<f64>) {
+                BaseExperiment::add_reset_instr(self, reset_time)
+            }
+
+            pub fn clear_compile_cache(&mut self) {
+                BaseExperiment::clear_compile_cache(self);
+            }
+
+            // DEVICE METHODS
+            pub fn add_ao_channel(&mut self, name: &str, channel_id: usize, default_value: f64) {
+                BaseExperiment::add_ao_channel(self, name, channel_id, default_value);
+            }
+
+            pub fn add_do_channel(&mut self, name: &str, port_id: usize, line_id: usize, default_value: bool) {
+                BaseExperiment::add_do_channel(self, name, port_id, line_id, default_value);
+            }
+
+            /* pub fn device_cfg_samp_clk_src(&mut self, name: &str, src: &str) {
+                BaseExperiment::device_cfg_samp_clk_src(self, name, src);
+            } */
+
+            /* pub fn device_set_start_trig_term(&mut self, name: &str, terminal: &str) {
+                BaseExperiment::device_set_start_trig_term(self, name, terminal);
+            } */
+
+            /* pub fn device_import_ref_clk(
+                &mut self,
+                name: &str,
+                src: &str,
+                rate: f64,
+            ) {
+                BaseExperiment::device_import_ref_clk(
+                    self,
+                    name,
+                    src,
+                    rate,
+                );
+            } */
+
+            pub fn device_compiled_channel_names(&mut self, name: &str, streamed: bool) -> Vec<String> {
+                BaseExperiment::device_compiled_channel_names(self, name, streamed)
+            }
+
+            pub fn calc_signal(
+                &mut self,
+                name: &str,
+                t_start: f64,
+                t_end: f64,
+                nsamps: usize,
+                streamed: bool,
+                py: Python,
+            ) -> PyResult<PyObject> {
+                self.assert_has_device(name);
+                let samp_rate = self.devices().get(name).unwrap().samp_rate();
+                let arr = self.catch_pyfunc_error(|exp| {
+                    BaseExperiment::device_calc_signal_nsamps(
+                        exp,
+                        name,
+                        (t_start * samp_rate) as usize,
+                        (t_end * samp_rate) as usize,
+                        nsamps,
+                        streamed,
+                    )
+                })?;
+                Ok(numpy::PyArray::from_array(py, &arr).to_object(py))
+            }
+
+            pub fn device_last_instr_end_time(&mut self, name: &str) -> f64 {
+                BaseExperiment::device_last_instr_end_time(self, name)
+            }
+
+            pub fn device_total_run_time(&mut self, name: &str) -> f64 {
+                BaseExperiment::device_total_run_time(self, name)
+            }
+
+            pub fn device_clear_compile_cache(&mut self, name: &str) {
+                BaseExperiment::device_clear_compile_cache(self, name)
+            }
+
+            pub fn device_clear_edit_cache(&mut self, name: &str) {
+                BaseExperiment::device_clear_edit_cache(self, name)
+            }
+
+            // INSTRUCTION METHODS
+            // Instructions are built with the fallible constructors here, so invalid arguments
+            // raise a `ValueError` instead of a panic
+            pub fn constant(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                value: f64,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::CONST, t, Some(duration))?;
+                let instr = Instruction::try_new_const(value)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, false)));
+                Ok(())
+            }
+            pub fn go_constant(&mut self, dev_name: &str, chan_name: &str, t: f64, value:f64) -> PyResult<()> {
+                check_instr_timing(&InstrType::CONST, t, None)?;
+                let instr = Instruction::try_new_const(value)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, None);
+                Ok(())
+            }
+
+            pub fn sine(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                keep_val: bool,
+                freq: f64,
+                amplitude: Option<f64>,
+                phase: Option<f64>,
+                dc_offset: Option<f64>,
+                time_ref: Option<&str>,
+            ) -> PyResult<()> {
+                // Accepts the time reference as a string ("absolute", "local" or "continuous")
+                let time_ref = time_ref
+                    .map(|time_ref| time_ref.parse::<TimeRef>())
+                    .transpose()
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                check_instr_timing(&InstrType::SINE, t, Some(duration))?;
+                let instr = Instruction::try_new_sine(freq, amplitude, phase, dc_offset)?
+                    .with_time_ref(time_ref.unwrap_or(TimeRef::Absolute));
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
+            }
+            pub fn go_sine(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                freq: f64,
+                amplitude: Option<f64>,
+                phase: Option<f64>,
+                dc_offset: Option<f64>,
+                time_ref: Option<&str>,
+            ) -> PyResult<()> {
+                let time_ref = time_ref
+                    .map(|time_ref| time_ref.parse::<TimeRef>())
+                    .transpose()
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                check_instr_timing(&InstrType::SINE, t, None)?;
+                let instr = Instruction::try_new_sine(freq, amplitude, phase, dc_offset)?
+                    .with_time_ref(time_ref.unwrap_or(TimeRef::Absolute));
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, None);
+                Ok(())
+            }
+            pub fn sine_from_current(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                keep_val: bool,
+                freq: f64,
+                amplitude: Option<f64>,
+                phase: Option<f64>,
+                time_ref: Option<&str>,
+            ) -> PyResult<()> {
+                let time_ref = time_ref
+                    .map(|time_ref| time_ref.parse::<TimeRef>())
+                    .transpose()
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                check_instr_timing(&InstrType::SINE, t, Some(duration))?;
+                let instr = Instruction::try_new_sine(freq, amplitude, phase, None)?
+                    .with_time_ref(time_ref.unwrap_or(TimeRef::Absolute))
+                    .with_from_current();
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
+            }
+
+            pub fn chirp(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                keep_val: bool,
+                f_start: f64,
+                f_end: f64,
+                exponential: bool,
+                amplitude: Option<f64>,
+                phase: Option<f64>,
+                dc_offset: Option<f64>,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::CHIRP, t, Some(duration))?;
+                let instr = Instruction::try_new_chirp(
+                    f_start, f_end, t, t + duration, exponential, amplitude, phase, dc_offset,
+                )?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
+            }
+
+            pub fn pulse(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                keep_val: bool,
+                window: &str,
+                amplitude: Option<f64>,
+                freq: Option<f64>,
+                phase: Option<f64>,
+                dc_offset: Option<f64>,
+                sigma: Option<f64>,
+                rise_time: Option<f64>,
+                fall_time: Option<f64>,
+                time_ref: Option<&str>,
+            ) -> PyResult<()> {
+                let time_ref = time_ref
+                    .map(|time_ref| time_ref.parse::<TimeRef>())
+                    .transpose()
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                let window = window
+                    .parse::<PulseWindow>()
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                check_instr_timing(&window.instr_type(), t, Some(duration))?;
+                let instr = Instruction::try_new_pulse(
+                    window.instr_type(), t, t + duration, amplitude, freq, phase, dc_offset, sigma, rise_time, fall_time,
+                )?
+                .with_time_ref(time_ref.unwrap_or(TimeRef::Absolute));
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
+            }
+
+            pub fn high(&mut self, dev_name: &str, chan_name: &str, t: f64, duration: f64) -> PyResult<()> {
+                check_instr_timing(&InstrType::CONST, t, Some(duration))?;
+                BaseExperiment::high(self, dev_name, chan_name, t, duration);
+                Ok(())
+            }
+
+            pub fn low(&mut self, dev_name: &str, chan_name: &str, t: f64, duration: f64) -> PyResult<()> {
+                check_instr_timing(&InstrType::CONST, t, Some(duration))?;
+                BaseExperiment::low(self, dev_name, chan_name, t, duration);
+                Ok(())
+            }
+
+            pub fn go_high(&mut self, dev_name: &str, chan_name: &str, t: f64) -> PyResult<()> {
+                check_instr_timing(&InstrType::CONST, t, None)?;
+                BaseExperiment::go_high(self, dev_name, chan_name, t);
+                Ok(())
+            }
+
+            pub fn go_low(&mut self, dev_name: &str, chan_name: &str, t: f64) -> PyResult<()> {
+                check_instr_timing(&InstrType::CONST, t, None)?;
+                BaseExperiment::go_low(self, dev_name, chan_name, t);
+                Ok(())
+            }
+
+            pub fn pulse_train(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                period: f64,
+                duty: f64,
+                duration: Option<f64>,
+                phase: Option<f64>,
+                count: Option<usize>,
+            ) -> PyResult<()> {
+                let duration = match (duration, count) {
+                    (Some(duration), _) => duration,
+                    (None, Some(count)) => (count as f64 - phase.unwrap_or(0.0)) * period,
+                    (None, None) => {
+                        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                            "Pulse train on channel {} needs either a duration or a pulse count",
+                            chan_name
+                        )))
+                    }
+                };
+                check_instr_timing(&InstrType::PULSETRAIN, t, Some(duration))?;
+                let instr = Instruction::try_new_pulse_train(t, period, Some(duty), phase, count)?;
+                BaseExperiment::do_channel_op(self, dev_name, chan_name, |chan| {
+                    (*chan).add_instr(instr.clone(), t, Some((duration, false)))
+                });
+                Ok(())
+            }
+
+            pub fn linramp(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                start_val: f64,
+                end_val: f64,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::LINRAMP, t, Some(duration))?;
+                let instr = Instruction::try_new_linramp(start_val, end_val, t, t + duration)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
+            }
+
+            pub fn linramp_from_current(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                end_val: f64,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::LINRAMP, t, Some(duration))?;
+                let instr = Instruction::try_new_linramp(end_val, end_val, t, t + duration)?.with_from_current();
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
+            }
+
+            pub fn smooth_ramp(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                start_val: f64,
+                end_val: f64,
+                shape: &str,
+                keep_val: bool,
+                steepness: Option<f64>,
+            ) -> PyResult<()> {
+                let shape = shape
+                    .parse::<RampShape>()
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                check_instr_timing(&shape.instr_type(), t, Some(duration))?;
+                let instr = shape.try_new_ramp(start_val, end_val, t, t + duration, steepness)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
+            }
+
+            pub fn expramp(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                start_val: f64,
+                end_val: f64,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::EXPRAMP, t, Some(duration))?;
+                let instr = Instruction::try_new_expramp(start_val, end_val, t, t + duration)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
+            }
+
+            pub fn expramp_tau(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                start_val: f64,
+                target_val: f64,
+                tau: f64,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::EXPRAMP, t, Some(duration))?;
+                let instr = Instruction::try_new_expramp_tau(start_val, target_val, tau, t)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
-            fn devices_(&mut self) -> &mut IndexMap<String, Device> {
-                &mut self.devices
+
+            pub fn logramp(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                start_val: f64,
+                end_val: f64,
+                tau: f64,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::LOGRAMP, t, Some(duration))?;
+                let instr = Instruction::try_new_logramp(start_val, end_val, t, t + duration, tau)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
-        }
 
-        #[pymethods]
-        impl $exp_type {
-            fn add_ao_device(&mut self, name: &str, samp_rate: f64) {
-                BaseExperiment::add_ao_device(self, name, samp_rate);
+            pub fn powramp(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                start_val: f64,
+                end_val: f64,
+                exponent: f64,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::POWRAMP, t, Some(duration))?;
+                let instr = Instruction::try_new_powramp(start_val, end_val, t, t + duration, exponent)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            fn add_do_device(&mut self, name: &str, samp_rate: f64) {
-                BaseExperiment::add_do_device(self, name, samp_rate);
+            pub fn expr(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                expr: &str,
+                params: std::collections::HashMap<String, f64>,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                // Parse here rather than in `BaseExperiment::expr` to raise a `ValueError` instead of a panic
+                let mut params: Vec<(String, f64)> = params.into_iter().collect();
+                params.sort_by(|a, b| a.0.cmp(&b.0));
+                check_instr_timing(&InstrType::EXPR, t, Some(duration))?;
+                let instr = Instruction::new_expr(expr, params.into_iter().collect())
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            pub fn last_instr_end_time(&self) -> f64 {
-                BaseExperiment::last_instr_end_time(self)
+            pub fn custom(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                kind: &str,
+                args: std::collections::HashMap<String, f64>,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                let mut args: Vec<(String, f64)> = args.into_iter().collect();
+                args.sort_by(|a, b| a.0.cmp(&b.0));
+                let args: InstrArgs = args.into_iter().collect();
+                check_instr_timing(&InstrType::CUSTOM, t, Some(duration))?;
+                let waveform = $crate::waveform::build_waveform(kind, &args)
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                let instr = Instruction::from_waveform(waveform, args);
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            pub fn total_run_time(&self) -> f64 {
-                BaseExperiment::total_run_time(self)
+            pub fn add_instr_spec(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                spec: &PyAny,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                // Nested `{"type", "args", "children"}` dictionaries, see `Instruction::from_py_spec`
+                let instr = Instruction::from_py_spec(spec)?;
+                check_instr_timing(&instr.instr_type, t, Some(duration))?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            /* pub fn check_trig_config(&self) {
-                BaseExperiment::check_trig_config(self)
-            } */
+            pub fn pyfunc(
+                &mut self,
+                py: Python,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                func: PyObject,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::CUSTOM, t, Some(duration))?;
+                // The callable is evaluated lazily by `calc_signal` / the streamer, see `PyFuncWaveform`
+                let waveform = $crate::waveform::PyFuncWaveform::new(py, func)
+                    .map_err(pyo3::exceptions::PyTypeError::new_err)?;
+                let instr = Instruction::from_waveform(Box::new(waveform), InstrArgs::new());
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
+            }
 
-            pub fn compile(&mut self, stop_time: Option<f64>) -> f64 {
-                BaseExperiment::compile(self, stop_time)
+            pub fn registered_waveforms(&self) -> Vec<String> {
+                $crate::waveform::registered_waveforms()
             }
 
-            pub fn is_edited(&self) -> bool {
-                BaseExperiment::is_edited(self)
+            pub fn arb(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                samples: numpy::PyReadonlyArray1<f64>,
+                samp_rate: f64,
+                interp: &str,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                // Accepts the interpolation scheme as a string ("nearest", "linear" or "cubic")
+                let interp = interp
+                    .parse::<Interp>()
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                let samples = samples.as_array().to_vec();
+                let duration = samples.len() as f64 / samp_rate;
+                check_instr_timing(&InstrType::ARB, t, Some(duration))?;
+                let instr = Instruction::try_new_arb(samples, samp_rate, t, interp)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            pub fn is_compiled(&self) -> bool {
-                BaseExperiment::is_compiled(self)
+            pub fn pwl(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                breakpoints: Vec<(f64, f64)>,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                let duration = breakpoints.last().map_or(0.0, |(time, _val)| *time);
+                check_instr_timing(&InstrType::PWL, t, Some(duration))?;
+                let instr = Instruction::try_new_pwl(breakpoints, t)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            pub fn is_fresh_compiled(&self) -> bool {
-                BaseExperiment::is_fresh_compiled(self)
+            pub fn spline(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                breakpoints: Vec<(f64, f64)>,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                let duration = breakpoints.last().map_or(0.0, |(time, _val)| *time);
+                check_instr_timing(&InstrType::SPLINE, t, Some(duration))?;
+                let instr = Instruction::try_new_spline(breakpoints, t)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            pub fn clear_edit_cache(&mut self) {
-                BaseExperiment::clear_edit_cache(self);
+            pub fn poly(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                coefs: Vec<f64>,
+                keep_val: bool,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::POLY, t, Some(duration))?;
+                let instr = Instruction::try_new_poly(coefs, t)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            pub fn add_reset_instr(&mut self, reset_time: Option<f64>) {
-                BaseExperiment::add_reset_instr(self, reset_time)
+            pub fn fourier(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                keep_val: bool,
+                freq: f64,
+                amplitudes: Vec<f64>,
+                phases: Option<Vec<f64>>,
+                dc_offset: Option<f64>,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::FOURIER, t, Some(duration))?;
+                let instr = Instruction::try_new_fourier(freq, amplitudes, phases, dc_offset)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            pub fn clear_compile_cache(&mut self) {
-                BaseExperiment::clear_compile_cache(self);
+            pub fn noise(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                t: f64,
+                duration: f64,
+                keep_val: bool,
+                amplitude: f64,
+                seed: u64,
+                gaussian: bool,
+                bandwidth: Option<f64>,
+                dc_offset: Option<f64>,
+            ) -> PyResult<()> {
+                check_instr_timing(&InstrType::NOISE, t, Some(duration))?;
+                let samp_rate = BaseExperiment::channel_op(self, dev_name, chan_name, |chan| chan.samp_rate());
+                let instr = Instruction::try_new_noise(samp_rate, amplitude, seed, gaussian, bandwidth, dc_offset)?;
+                BaseExperiment::add_instr(self, dev_name, chan_name, instr, t, Some((duration, keep_val)));
+                Ok(())
             }
 
-            // DEVICE METHODS
-            pub fn add_ao_channel(&mut self, name: &str, channel_id: usize, default_value: f64) {
-                BaseExperiment::add_ao_channel(self, name, channel_id, default_value);
+            // CHANNEL METHODS
+            pub fn channel_clear_compile_cache(&mut self, dev_name: &str, chan_name: &str) {
+                BaseExperiment::channel_clear_compile_cache(self, dev_name, chan_name);
             }
 
-            pub fn add_do_channel(&mut self, name: &str, port_id: usize, line_id: usize, default_value: f64) {
-                BaseExperiment::add_do_channel(self, name, port_id, line_id, default_value);
+            pub fn channel_clear_edit_cache(&mut self, dev_name: &str, chan_name: &str) {
+                BaseExperiment::channel_clear_edit_cache(self, dev_name, chan_name);
             }
 
-            /* pub fn device_cfg_samp_clk_src(&mut self, name: &str, src: &str) {
-                BaseExperiment::device_cfg_samp_clk_src(self, name, src);
-            } */
+            pub fn channel_last_instr_end_time(&mut self, dev_name: &str, chan_name: &str) -> f64 {
+                BaseExperiment::channel_last_instr_end_time(self, dev_name, chan_name)
+            }
 
-            /* pub fn device_set_start_trig_term(&mut self, name: &str, terminal: &str) {
-                BaseExperiment::device_set_start_trig_term(self, name, terminal);
-            } */
+            pub fn channel_max_slew_rate(&mut self, dev_name: &str, chan_name: &str) -> Option<f64> {
+                BaseExperiment::channel_max_slew_rate(self, dev_name, chan_name)
+            }
 
-            /* pub fn device_import_ref_clk(
-                &mut self,
-                name: &str,
-                src: &str,
-                rate: f64,
-            ) {
-                BaseExperiment::device_import_ref_clk(
-                    self,
-                    name,
-                    src,
-                    rate,
-                );
-            } */
+            pub fn channel_set_max_slew_rate(&mut self, dev_name: &str, chan_name: &str, max_slew_rate: Option<f64>) {
+                BaseExperiment::channel_set_max_slew_rate(self, dev_name, chan_name, max_slew_rate);
+            }
 
-            pub fn device_compiled_channel_names(
-                &mut self,
-                name: &str,
-                require_streamable: bool,
-                require_editable: bool,
-            ) -> Vec<String> {
-                BaseExperiment::device_compiled_channel_names(
-                    self,
-                    name,
-                    require_streamable,
-                    require_editable,
-                )
+            pub fn channel_reset_value(&mut self, dev_name: &str, chan_name: &str) -> f64 {
+                BaseExperiment::channel_reset_value(self, dev_name, chan_name)
             }
 
-            pub fn calc_signal(
-                &mut self,
-                name: &str,
-                t_start: f64,
-                t_end: f64,
-                nsamps: usize,
-                require_streamable: bool,
-                require_editable: bool,
-                py: Python,
-            ) -> PyResult<PyObject> {
-                self.assert_has_device(name);
-                let samp_rate = self.devices().get(name).unwrap().samp_rate();
-                let arr = BaseExperiment::device_calc_signal_nsamps(
-                    self,
-                    name,
-                    (t_start * samp_rate) as usize,
-                    (t_end * samp_rate) as usize,
-                    nsamps,
-                    require_streamable,
-                    require_editable,
-                );
-                Ok(numpy::PyArray::from_array(py, &arr).to_object(py))
+            pub fn ao_channel_set_reset_value(&mut self, dev_name: &str, chan_name: &str, reset_value: f64) {
+                BaseExperiment::ao_channel_set_reset_value(self, dev_name, chan_name, reset_value);
             }
 
-            pub fn device_last_instr_end_time(&mut self, name: &str) -> f64 {
-                BaseExperiment::device_last_instr_end_time(self, name)
+            pub fn do_channel_set_reset_value(&mut self, dev_name: &str, chan_name: &str, reset_value: bool) {
+                BaseExperiment::do_channel_set_reset_value(self, dev_name, chan_name, reset_value);
             }
 
-            pub fn device_total_run_time(&mut self, name: &str) -> f64 {
-                BaseExperiment::device_total_run_time(self, name)
+            pub fn channel_output_limits(&mut self, dev_name: &str, chan_name: &str) -> Option<(f64, f64)> {
+                BaseExperiment::channel_output_limits(self, dev_name, chan_name)
             }
 
-            pub fn device_clear_compile_cache(&mut self, name: &str) {
-                BaseExperiment::device_clear_compile_cache(self, name)
+            pub fn ao_channel_set_output_limits(&mut self, dev_name: &str, chan_name: &str, min_val: f64, max_val: f64) {
+                BaseExperiment::ao_channel_set_output_limits(self, dev_name, chan_name, min_val, max_val);
             }
 
-            pub fn device_clear_edit_cache(&mut self, name: &str) {
-                BaseExperiment::device_clear_edit_cache(self, name)
+            pub fn channel_unit(&mut self, dev_name: &str, chan_name: &str) -> String {
+                BaseExperiment::channel_unit(self, dev_name, chan_name)
             }
 
-            // INSTRUCTION METHODS
-            pub fn constant(
+            pub fn ao_channel_set_linear_calibration(
                 &mut self,
                 dev_name: &str,
                 chan_name: &str,
-                t: f64,
-                duration: f64,
-                value: f64,
-            ) {
-                BaseExperiment::constant(self, dev_name, chan_name, t, duration, value);
+                unit: &str,
+                gain: f64,
+                offset: f64,
+            ) -> PyResult<()> {
+                let calibration = $crate::calibration::Calibration::new_linear(gain, offset)
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                BaseExperiment::ao_channel_set_calibration(self, dev_name, chan_name, unit, calibration);
+                Ok(())
             }
-            pub fn go_constant(&mut self, dev_name: &str, chan_name: &str, t: f64, value:f64) {
-                BaseExperiment::go_constant(self, dev_name, chan_name, t, value);
+
+            pub fn ao_channel_set_poly_calibration(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                unit: &str,
+                coeffs: Vec<f64>,
+            ) -> PyResult<()> {
+                let calibration = $crate::calibration::Calibration::new_poly(coeffs)
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                BaseExperiment::ao_channel_set_calibration(self, dev_name, chan_name, unit, calibration);
+                Ok(())
             }
 
-            pub fn sine(
+            pub fn ao_channel_set_table_calibration(
                 &mut self,
                 dev_name: &str,
                 chan_name: &str,
-                t: f64,
-                duration: f64,
-                keep_val: bool,
-                freq: f64,
-                amplitude: Option<f64>,
-                phase: Option<f64>,
-                dc_offset: Option<f64>,
-            ) {
-                BaseExperiment::sine(
-                    self, dev_name, chan_name, t, duration, keep_val, freq, amplitude, phase,
-                    dc_offset,
-                );
+                unit: &str,
+                input: Vec<f64>,
+                output: Vec<f64>,
+            ) -> PyResult<()> {
+                let calibration = $crate::calibration::Calibration::new_table(input, output)
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                BaseExperiment::ao_channel_set_calibration(self, dev_name, chan_name, unit, calibration);
+                Ok(())
             }
-            pub fn go_sine(
+
+            pub fn ao_channel_load_calibration_csv(
                 &mut self,
                 dev_name: &str,
                 chan_name: &str,
-                t: f64,
-                freq: f64,
-                amplitude: Option<f64>,
-                phase: Option<f64>,
-                dc_offset: Option<f64>,
-            ) {
-                BaseExperiment::go_sine(
-                    self, dev_name, chan_name, t, freq, amplitude, phase, dc_offset,
-                );
+                unit: &str,
+                path: &str,
+            ) -> PyResult<()> {
+                let calibration = $crate::calibration::Calibration::from_csv(path)
+                    .map_err(pyo3::exceptions::PyValueError::new_err)?;
+                BaseExperiment::ao_channel_set_calibration(self, dev_name, chan_name, unit, calibration);
+                Ok(())
             }
 
-            pub fn high(&mut self, dev_name: &str, chan_name: &str, t: f64, duration: f64) {
-                BaseExperiment::high(self, dev_name, chan_name, t, duration);
+            pub fn ao_channel_clear_calibration(&mut self, dev_name: &str, chan_name: &str) {
+                BaseExperiment::ao_channel_clear_calibration(self, dev_name, chan_name);
             }
 
-            pub fn low(&mut self, dev_name: &str, chan_name: &str, t: f64, duration: f64) {
-                BaseExperiment::low(self, dev_name, chan_name, t, duration);
+            pub fn channel_calc_signal_nsamps(
+                &mut self,
+                dev_name: &str,
+                chan_name: &str,
+                start_time: f64,
+                end_time: f64,
+                num_samps: usize,
+            ) -> PyResult<Vec<f64>> {
+                self.catch_pyfunc_error(|exp| {
+                    BaseExperiment::channel_calc_signal_nsamps(
+                        exp, dev_name, chan_name, start_time, end_time, num_samps,
+                    )
+                })
             }
 
-            pub fn go_high(&mut self, dev_name: &str, chan_name: &str, t: f64) {
-                BaseExperiment::go_high(self, dev_name, chan_name, t);
+            pub fn channel_value_bounds(&mut self, dev_name: &str, chan_name: &str, start_time: f64, end_time: f64) -> (f64, f64) {
+                BaseExperiment::channel_value_bounds(self, dev_name, chan_name, start_time, end_time)
             }
 
-            pub fn go_low(&mut self, dev_name: &str, chan_name: &str, t: f64) {
-                BaseExperiment::go_low(self, dev_name, chan_name, t);
+            pub fn channel_output_bounds(&mut self, dev_name: &str, chan_name: &str, start_time: f64, end_time: f64) -> (f64, f64) {
+                BaseExperiment::channel_output_bounds(self, dev_name, chan_name, start_time, end_time)
             }
 
-            pub fn linramp(
+            pub fn channel_remove_instr(&mut self, dev_name: &str, chan_name: &str, t: f64) {
+                BaseExperiment::channel_remove_instr(self, dev_name, chan_name, t);
+            }
+
+            pub fn channel_replace_instr(
                 &mut self,
                 dev_name: &str,
                 chan_name: &str,
                 t: f64,
-                duration: f64,
-                start_val: f64,
-                end_val: f64,
-                keep_val: bool,
-            ) {
-                BaseExperiment::linramp(self, dev_name, chan_name, t, duration, start_val, end_val, keep_val);
+                instr_type: &str,
+                args: std::collections::HashMap<String, f64>,
+            ) -> PyResult<()> {
+                // Built from the type name and argument dictionary, as accepted by `Instruction::new`
+                let instr_type: InstrType = instr_type.parse().map_err(pyo3::exceptions::PyValueError::new_err)?;
+                let mut args: Vec<(String, f64)> = args.into_iter().collect();
+                args.sort_by(|a, b| a.0.cmp(&b.0));
+                let instr = Instruction::try_new(instr_type, args.into_iter().collect())?;
+                BaseExperiment::channel_replace_instr(self, dev_name, chan_name, t, instr);
+                Ok(())
             }
 
-            // CHANNEL METHODS
-            pub fn channel_clear_compile_cache(&mut self, dev_name: &str, chan_name: &str) {
-                BaseExperiment::channel_clear_compile_cache(self, dev_name, chan_name);
-            }
-
-            pub fn channel_clear_edit_cache(&mut self, dev_name: &str, chan_name: &str) {
-                BaseExperiment::channel_clear_edit_cache(self, dev_name, chan_name);
+            pub fn channel_move_instr(&mut self, dev_name: &str, chan_name: &str, t: f64, new_t: f64) {
+                BaseExperiment::channel_move_instr(self, dev_name, chan_name, t, new_t);
             }
 
-            pub fn channel_last_instr_end_time(&mut self, dev_name: &str, chan_name: &str) -> f64 {
-                BaseExperiment::channel_last_instr_end_time(self, dev_name, chan_name)
+            pub fn channel_instr_list(
+                &mut self,
+                py: Python,
+                dev_name: &str,
+                chan_name: &str,
+                start_time: f64,
+                end_time: f64,
+            ) -> PyResult<Vec<PyObject>> {
+                BaseExperiment::channel_instr_list(self, dev_name, chan_name, start_time, end_time)
+                    .into_iter()
+                    .map(|(start_time, end_spec, instr)| {
+                        let dict = instr.to_py_spec(py)?;
+                        dict.set_item("start_time", start_time)?;
+                        dict.set_item("end_time", end_spec.map(|(end_time, _keep_val)| end_time))?;
+                        dict.set_item("keep_val", end_spec.map(|(_end_time, keep_val)| keep_val))?;
+                        Ok(dict.into())
+                    })
+                    .collect()
             }
 
-            pub fn channel_calc_signal_nsamps(
+            pub fn channel_compiled_segments(
                 &mut self,
+                py: Python,
                 dev_name: &str,
                 chan_name: &str,
                 start_time: f64,
                 end_time: f64,
-                num_samps: usize,
-            ) -> Vec<f64> {
-                BaseExperiment::channel_calc_signal_nsamps(
-                    self, dev_name, chan_name, start_time, end_time, num_samps,
-                )
+            ) -> PyResult<Vec<PyObject>> {
+                BaseExperiment::channel_compiled_segments(self, dev_name, chan_name, start_time, end_time)
+                    .into_iter()
+                    .map(|(start_time, end_time, instr)| {
+                        let dict = instr.to_py_spec(py)?;
+                        dict.set_item("start_time", start_time)?;
+                        dict.set_item("end_time", end_time)?;
+                        Ok(dict.into())
+                    })
+                    .collect()
             }
         }
     };
@@ -1698,8 +3385,8 @@ mod test {
             let mut exp = Experiment::new();
             exp.add_do_device("Dev1", 1000.0);
             exp.add_do_device("Dev2", 123.0);
-            exp.dev_("Dev1").add_channel("port0/line0", 0.0);
-            exp.dev_("Dev2").add_channel("port0/line0", 0.0);
+            exp.dev_("Dev1").add_do_channel(0, 0, false);
+            exp.dev_("Dev2").add_do_channel(0, 0, false);
 
             let mock_func = Instruction::new_const(1.0);  // actual function doesn't matter
 
@@ -1712,7 +3399,7 @@ mod test {
 
             for stop_time in stop_time_arr {
                 for dev in exp.devices_().values_mut() {
-                    for chan in dev.editable_channels_() {
+                    for chan in dev.channels_() {
                         chan.add_instr(
                             mock_func.clone(),
                             stop_time - dur, Some((dur, false))
@@ -1720,7 +3407,7 @@ mod test {
                     }
                 }
                 // The actual test - this call should not panic due to any last instructions being clipped:
-                exp.compile(None);
+                BaseExperiment::compile(&mut exp, None);
 
                 // Additional (not really necessary) check to ensure no instructions were clipped
                 //  (hard to check for the exact individual total_run_times
@@ -1763,8 +3450,8 @@ mod test {
             let mut exp = Experiment::new();
             exp.add_do_device("Dev1", 1000.0);
             exp.add_do_device("Dev2", 123.0);
-            exp.dev_("Dev1").add_channel("port0/line0", 0.0);
-            exp.dev_("Dev2").add_channel("port0/line0", 0.0);
+            exp.dev_("Dev1").add_do_channel(0, 0, false);
+            exp.dev_("Dev2").add_do_channel(0, 0, false);
 
             let mock_func = Instruction::new_const(0.0);  // actual function doesn't matter
 
@@ -1777,7 +3464,7 @@ mod test {
                 0.0, Some((0.5, false))
             );
 
-            exp.compile(Some(1.0));
+            BaseExperiment::compile(&mut exp, Some(1.0));
             assert!(
                 f64::abs(exp.total_run_time() - 1.0) < 1e-10
             );
@@ -1795,8 +3482,8 @@ mod test {
             let mut exp = Experiment::new();
             exp.add_do_device("Dev1", 1000.0);
             exp.add_do_device("Dev2", 123.0);
-            exp.dev_("Dev1").add_channel("port0/line0", 0.0);
-            exp.dev_("Dev2").add_channel("port0/line0", 0.0);
+            exp.dev_("Dev1").add_do_channel(0, 0, false);
+            exp.dev_("Dev2").add_do_channel(0, 0, false);
 
             let mock_func = Instruction::new_const(0.0);
 
@@ -1810,7 +3497,7 @@ mod test {
             for stop_time in stop_time_arr {
                 exp.clear_edit_cache();
                 for dev in exp.devices_().values_mut() {
-                    for chan in dev.editable_channels_() {  // ToDo when splitting AO/DO types: remove `editable` filter
+                    for chan in dev.channels_() {
                         chan.add_instr(
                             mock_func.clone(),
                             stop_time - dur, Some((dur, true))
@@ -1819,7 +3506,7 @@ mod test {
                 }
                 // Neither of the following calls should panic:
                 exp.add_reset_instr(None);
-                exp.compile(None);
+                BaseExperiment::compile(&mut exp, None);
             }
         }
 
@@ -1830,23 +3517,23 @@ mod test {
             let mut exp = Experiment::new();
             exp.add_do_device("Dev1", 1000.0);
             exp.add_do_device("Dev2", 123.0);
-            exp.dev_("Dev1").add_channel("port0/line0", 0.0);
-            exp.dev_("Dev2").add_channel("port0/line0", 0.0);
+            exp.dev_("Dev1").add_do_channel(0, 0, false);
+            exp.dev_("Dev2").add_do_channel(0, 0, false);
 
             let mock_func = Instruction::new_const(1.0);
             for dev in exp.devices_().values_mut() {
-                for chan in dev.editable_channels_() {
+                for chan in dev.channels_() {
                     chan.add_instr(mock_func.clone(), 0.0, None)
                 }
             }
             // In this test, clock grids align at `t = 1.0s`
             let reset_time = 1.0;
             exp.add_reset_instr(Some(reset_time));
-            exp.compile(None);
+            BaseExperiment::compile(&mut exp, None);
 
             // Confirm that all channels actually give their reset values at `t = match_time`
             for dev in exp.devices().values() {
-                for chan in dev.editable_channels() {
+                for chan in dev.channels() {
                     // Reset is the last instruction, so its' start position is the end of the previous instruction:
                     let actual_reset_pos = chan.instr_end()[chan.instr_end().len() - 2];
                     let expected_reset_pos = (reset_time * chan.samp_rate()).round() as usize;
@@ -1862,4 +3549,37 @@ mod test {
             }
         }
     }
+    mod python_errors {
+        use crate::experiment::*;
+
+        #[test]
+        /// Invalid instruction arguments and non-finite times reach the python methods as errors, not panics
+        fn invalid_args() {
+            let mut exp = Experiment::new();
+            exp.add_ao_device("Dev1", 1e6);
+            exp.add_ao_channel("Dev1", 0, 0.0);
+            exp.add_do_device("Dev2", 1e6);
+            exp.add_do_channel("Dev2", 0, 0, false);
+
+            assert!(exp.expramp("Dev1", "ao0", 0.0, 1e-3, 0.0, 1.0, false).is_err());
+            assert!(exp.expramp_tau("Dev1", "ao0", 0.0, 1e-3, 1.0, 0.0, f64::NAN, false).is_err());
+            assert!(exp.linramp("Dev1", "ao0", f64::NAN, 1e-3, 0.0, 1.0, false).is_err());
+            assert!(exp.sine("Dev1", "ao0", 0.0, f64::INFINITY, false, 1e3, None, None, None, None).is_err());
+            assert!(exp.smooth_ramp("Dev1", "ao0", 0.0, 1e-3, 0.0, 1.0, "cos", false, Some(2.0)).is_err());
+            assert!(exp.fourier("Dev1", "ao0", 0.0, 1e-3, false, 1e3, vec![1.0, 0.5], Some(vec![0.0]), None).is_err());
+            assert!(exp.go_high("Dev2", "port0/line0", f64::NAN).is_err());
+            assert!(exp.pulse_train("Dev2", "port0/line0", 0.0, 1e-3, 1.5, None, None, Some(3)).is_err());
+            assert!(exp.pulse_train("Dev2", "port0/line0", 0.0, 1e-3, 0.5, None, None, None).is_err());
+            assert!(!exp.is_edited());
+
+            exp.expramp("Dev1", "ao0", 0.0, 1e-3, 1.0, 2.0, false).unwrap();
+            exp.pulse_train("Dev2", "port0/line0", 0.0, 1e-3, 0.5, None, None, Some(3)).unwrap();
+            assert_eq!(exp.last_instr_end_time(), 3e-3);
+
+            assert!(exp.ao_channel_set_linear_calibration("Dev1", "ao0", "mW", 0.0, 1.0).is_err());
+            assert!(exp.ao_channel_set_poly_calibration("Dev1", "ao0", "mW", vec![]).is_err());
+            assert!(exp.ao_channel_set_table_calibration("Dev1", "ao0", "mW", vec![0.0], vec![0.0]).is_err());
+            assert_eq!(exp.channel_unit("Dev1", "ao0"), "V");
+        }
+    }
 }
@@ -0,0 +1,589 @@
+//! Math-expression waveforms for the `EXPR` instruction type.
+//!
+//! A [`MathExpr`] is parsed once from a string such as `"A*exp(-(t-t0)^2/(2*s^2)) + c"`.
+//! Named parameters (`A`, `t0`, `s`, `c` above) are bound at parse time and every sub-expression
+//! which does not depend on time is folded into a constant. Any syntax error or undefined variable
+//! is therefore reported when the instruction is created, never at compile time.
+//!
+//! Evaluation is vectorized: each node of the expression tree is applied to the whole time array at once,
+//! so the per-sample cost is a tight loop over `f64` values rather than a tree walk.
+//!
+//! ## Syntax
+//!
+//! - Numbers: `2`, `0.5`, `1e-3`
+//! - Operators: `+`, `-`, `*`, `/`, `^` (or `**`, right-associative), unary `-`, parentheses
+//! - Variables: `t` (absolute time in seconds), named parameters, constants `pi` and `e`
+//! - Functions: `sin`, `cos`, `tan`, `asin`, `acos`, `atan`, `sinh`, `cosh`, `tanh`, `exp`, `ln`, `log`
+//!   (natural), `log10`, `log2`, `sqrt`, `abs`, `sign`, `floor`, `ceil`, `round`, `step` (1 for `x >= 0`, else 0),
+//!   and the two-argument `atan2`, `min`, `max`, `pow`
+
+use ndarray::{Array1, ArrayView1, ArrayViewMut1};
+use std::f64::consts::{E, PI};
+use std::fmt;
+
+use crate::instruction::InstrArgs;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+impl BinOp {
+    fn apply(&self, a: f64, b: f64) -> f64 {
+        match self {
+            BinOp::Add => a + b,
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            BinOp::Div => a / b,
+            BinOp::Pow => a.powf(b),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Log10,
+    Log2,
+    Sqrt,
+    Abs,
+    Sign,
+    Floor,
+    Ceil,
+    Round,
+    Step,
+    Atan2,
+    Min,
+    Max,
+    Pow,
+}
+impl Func {
+    fn from_name(name: &str) -> Option<Func> {
+        Some(match name {
+            "sin" => Func::Sin,
+            "cos" => Func::Cos,
+            "tan" => Func::Tan,
+            "asin" => Func::Asin,
+            "acos" => Func::Acos,
+            "atan" => Func::Atan,
+            "sinh" => Func::Sinh,
+            "cosh" => Func::Cosh,
+            "tanh" => Func::Tanh,
+            "exp" => Func::Exp,
+            "ln" | "log" => Func::Ln,
+            "log10" => Func::Log10,
+            "log2" => Func::Log2,
+            "sqrt" => Func::Sqrt,
+            "abs" => Func::Abs,
+            "sign" => Func::Sign,
+            "floor" => Func::Floor,
+            "ceil" => Func::Ceil,
+            "round" => Func::Round,
+            "step" => Func::Step,
+            "atan2" => Func::Atan2,
+            "min" => Func::Min,
+            "max" => Func::Max,
+            "pow" => Func::Pow,
+            _ => return None,
+        })
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Func::Atan2 | Func::Min | Func::Max | Func::Pow => 2,
+            _ => 1,
+        }
+    }
+
+    fn apply_unary(&self, x: f64) -> f64 {
+        match self {
+            Func::Sin => x.sin(),
+            Func::Cos => x.cos(),
+            Func::Tan => x.tan(),
+            Func::Asin => x.asin(),
+            Func::Acos => x.acos(),
+            Func::Atan => x.atan(),
+            Func::Sinh => x.sinh(),
+            Func::Cosh => x.cosh(),
+            Func::Tanh => x.tanh(),
+            Func::Exp => x.exp(),
+            Func::Ln => x.ln(),
+            Func::Log10 => x.log10(),
+            Func::Log2 => x.log2(),
+            Func::Sqrt => x.sqrt(),
+            Func::Abs => x.abs(),
+            Func::Sign => {
+                if x == 0.0 {
+                    0.0
+                } else {
+                    x.signum()
+                }
+            }
+            Func::Floor => x.floor(),
+            Func::Ceil => x.ceil(),
+            Func::Round => x.round(),
+            Func::Step => {
+                if x >= 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            _ => unreachable!("{:?} is not a unary function", self),
+        }
+    }
+
+    fn apply_binary(&self, a: f64, b: f64) -> f64 {
+        match self {
+            Func::Atan2 => a.atan2(b),
+            Func::Min => a.min(b),
+            Func::Max => a.max(b),
+            Func::Pow => a.powf(b),
+            _ => unreachable!("{:?} is not a binary function", self),
+        }
+    }
+}
+
+/// Expression tree node. Time-independent sub-trees are folded into `Const` during parsing.
+#[derive(Clone, PartialEq, Debug)]
+enum Node {
+    Const(f64),
+    Time,
+    Neg(Box<Node>),
+    Binary(BinOp, Box<Node>, Box<Node>),
+    Call(Func, Vec<Node>),
+}
+impl Node {
+    fn as_const(&self) -> Option<f64> {
+        match self {
+            Node::Const(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    fn neg(operand: Node) -> Node {
+        match operand.as_const() {
+            Some(val) => Node::Const(-val),
+            None => Node::Neg(Box::new(operand)),
+        }
+    }
+
+    fn binary(op: BinOp, lhs: Node, rhs: Node) -> Node {
+        match (lhs.as_const(), rhs.as_const()) {
+            (Some(a), Some(b)) => Node::Const(op.apply(a, b)),
+            _ => Node::Binary(op, Box::new(lhs), Box::new(rhs)),
+        }
+    }
+
+    fn call(func: Func, args: Vec<Node>) -> Node {
+        let consts: Vec<f64> = args.iter().filter_map(|arg| arg.as_const()).collect();
+        if consts.len() == args.len() {
+            match func.arity() {
+                1 => Node::Const(func.apply_unary(consts[0])),
+                _ => Node::Const(func.apply_binary(consts[0], consts[1])),
+            }
+        } else {
+            Node::Call(func, args)
+        }
+    }
+
+    /// Evaluates the node over the whole time array
+    fn eval(&self, t_arr: &ArrayView1<f64>) -> Value {
+        match self {
+            Node::Const(val) => Value::Scalar(*val),
+            Node::Time => Value::Array(t_arr.to_owned()),
+            Node::Neg(operand) => operand.eval(t_arr).map(|x| -x),
+            Node::Binary(op, lhs, rhs) => {
+                Value::combine(lhs.eval(t_arr), rhs.eval(t_arr), |a, b| op.apply(a, b))
+            }
+            Node::Call(func, args) => match func.arity() {
+                1 => args[0].eval(t_arr).map(|x| func.apply_unary(x)),
+                _ => Value::combine(args[0].eval(t_arr), args[1].eval(t_arr), |a, b| {
+                    func.apply_binary(a, b)
+                }),
+            },
+        }
+    }
+}
+
+/// Intermediate evaluation result. Constants are kept scalar to avoid filling arrays with them.
+enum Value {
+    Scalar(f64),
+    Array(Array1<f64>),
+}
+impl Value {
+    fn map<F: Fn(f64) -> f64>(self, f: F) -> Value {
+        match self {
+            Value::Scalar(x) => Value::Scalar(f(x)),
+            Value::Array(mut arr) => {
+                arr.mapv_inplace(f);
+                Value::Array(arr)
+            }
+        }
+    }
+
+    fn combine<F: Fn(f64, f64) -> f64>(lhs: Value, rhs: Value, f: F) -> Value {
+        match (lhs, rhs) {
+            (Value::Scalar(a), Value::Scalar(b)) => Value::Scalar(f(a, b)),
+            (Value::Array(mut a), Value::Scalar(b)) => {
+                a.mapv_inplace(|x| f(x, b));
+                Value::Array(a)
+            }
+            (Value::Scalar(a), Value::Array(mut b)) => {
+                b.mapv_inplace(|y| f(a, y));
+                Value::Array(b)
+            }
+            (Value::Array(mut a), Value::Array(b)) => {
+                a.zip_mut_with(&b, |x, y| *x = f(*x, *y));
+                Value::Array(a)
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// Splits the source into tokens, paired with their character position for error messages
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        let c = chars[pos];
+        let start = pos;
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+        let token = if c.is_ascii_digit() || c == '.' {
+            while pos < chars.len() && (chars[pos].is_ascii_digit() || chars[pos] == '.') {
+                pos += 1;
+            }
+            // Exponent part, e.g. `1e-3`
+            if pos < chars.len() && (chars[pos] == 'e' || chars[pos] == 'E') {
+                let mut exp_end = pos + 1;
+                if exp_end < chars.len() && (chars[exp_end] == '+' || chars[exp_end] == '-') {
+                    exp_end += 1;
+                }
+                if exp_end < chars.len() && chars[exp_end].is_ascii_digit() {
+                    pos = exp_end;
+                    while pos < chars.len() && chars[pos].is_ascii_digit() {
+                        pos += 1;
+                    }
+                }
+            }
+            let literal: String = chars[start..pos].iter().collect();
+            let value = literal
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid number \"{}\" at position {}", literal, start))?;
+            Token::Num(value)
+        } else if c.is_alphabetic() || c == '_' {
+            while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '_') {
+                pos += 1;
+            }
+            Token::Ident(chars[start..pos].iter().collect())
+        } else {
+            pos += 1;
+            match c {
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                ',' => Token::Comma,
+                '*' if pos < chars.len() && chars[pos] == '*' => {
+                    pos += 1;
+                    Token::Op('^')
+                }
+                '+' | '-' | '*' | '/' | '^' => Token::Op(c),
+                _ => return Err(format!("Unexpected character '{}' at position {}", c, start)),
+            }
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser producing a constant-folded [`Node`] tree
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    idx: usize,
+    source_len: usize,
+    params: &'a InstrArgs,
+}
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.idx).map(|(_, token)| token)
+    }
+
+    fn pos(&self) -> usize {
+        self.tokens.get(self.idx).map_or(self.source_len, |(pos, _)| *pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.idx).map(|(_, token)| token.clone());
+        self.idx += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token, what: &str) -> Result<(), String> {
+        let pos = self.pos();
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(format!("Expected {} at position {}", what, pos)),
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<Node, String> {
+        let mut node = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.idx += 1;
+            let rhs = self.term()?;
+            let op = if op == '+' { BinOp::Add } else { BinOp::Sub };
+            node = Node::binary(op, node, rhs);
+        }
+        Ok(node)
+    }
+
+    // term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> Result<Node, String> {
+        let mut node = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek().cloned() {
+            self.idx += 1;
+            let rhs = self.unary()?;
+            let op = if op == '*' { BinOp::Mul } else { BinOp::Div };
+            node = Node::binary(op, node, rhs);
+        }
+        Ok(node)
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Result<Node, String> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.idx += 1;
+                Ok(Node::neg(self.unary()?))
+            }
+            Some(Token::Op('+')) => {
+                self.idx += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    // power := atom ('^' unary)?, so that `-x^2 = -(x^2)` and `2^-1 = 0.5`
+    fn power(&mut self) -> Result<Node, String> {
+        let base = self.atom()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.idx += 1;
+            let exponent = self.unary()?;
+            return Ok(Node::binary(BinOp::Pow, base, exponent));
+        }
+        Ok(base)
+    }
+
+    // atom := number | variable | function '(' args ')' | '(' expr ')'
+    fn atom(&mut self) -> Result<Node, String> {
+        let pos = self.pos();
+        match self.next() {
+            Some(Token::Num(val)) => Ok(Node::Const(val)),
+            Some(Token::LParen) => {
+                let node = self.expr()?;
+                self.expect(Token::RParen, "')'")?;
+                Ok(node)
+            }
+            Some(Token::Ident(name)) => {
+                if let Some(Token::LParen) = self.peek() {
+                    let func = Func::from_name(&name)
+                        .ok_or_else(|| format!("Unknown function \"{}\" at position {}", name, pos))?;
+                    self.idx += 1;
+                    let mut args = vec![self.expr()?];
+                    while let Some(Token::Comma) = self.peek() {
+                        self.idx += 1;
+                        args.push(self.expr()?);
+                    }
+                    self.expect(Token::RParen, "')'")?;
+                    if args.len() != func.arity() {
+                        return Err(format!(
+                            "Function \"{}\" at position {} expects {} argument(s), got {}",
+                            name,
+                            pos,
+                            func.arity(),
+                            args.len()
+                        ));
+                    }
+                    return Ok(Node::call(func, args));
+                }
+                if name == "t" {
+                    return Ok(Node::Time);
+                }
+                // Parameters shadow the built-in constants
+                if let Some(val) = self.params.get(&name) {
+                    return Ok(Node::Const(*val));
+                }
+                match name.as_str() {
+                    "pi" => Ok(Node::Const(PI)),
+                    "e" => Ok(Node::Const(E)),
+                    _ => Err(format!("Undefined variable \"{}\" at position {}", name, pos)),
+                }
+            }
+            Some(_) => Err(format!("Unexpected token at position {}", pos)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+/// Parsed and compiled math expression of time `t`.
+///
+/// Two expressions compare equal if they have the same source and were compiled to the same tree
+/// (i.e. with the same parameter values), which is what `BaseChannel::compile` needs for segment merging.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MathExpr {
+    source: String,
+    root: Node,
+}
+impl MathExpr {
+    /// Parses `source` binding the named `params`.
+    ///
+    /// Returns a descriptive error for syntax errors, unknown functions or undefined variables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nicompiler_backend::expression::*;
+    /// # use nicompiler_backend::instruction::InstrArgs;
+    /// let mut params = InstrArgs::new();
+    /// params.insert("A".to_string(), 2.0);
+    /// let expr = MathExpr::parse("A * t^2 + 1", &params).unwrap();
+    /// assert_eq!(expr.eval_point(3.0), 19.0);
+    ///
+    /// assert!(MathExpr::parse("A * t + B", &params).unwrap_err().contains("Undefined variable \"B\""));
+    /// ```
+    pub fn parse(source: &str, params: &InstrArgs) -> Result<MathExpr, String> {
+        if params.contains_key("t") {
+            return Err("Parameter name \"t\" is reserved for time".to_string());
+        }
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            idx: 0,
+            source_len: source.chars().count(),
+            params,
+        };
+        let root = parser.expr()?;
+        if parser.idx < parser.tokens.len() {
+            return Err(format!("Unexpected token at position {}", parser.pos()));
+        }
+        Ok(MathExpr {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    /// The source string the expression was parsed from
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Replaces the time values in `t_arr` with the expression values
+    pub fn eval_inplace(&self, t_arr: &mut ArrayViewMut1<f64>) {
+        match self.root.eval(&t_arr.view()) {
+            Value::Scalar(val) => t_arr.fill(val),
+            Value::Array(arr) => t_arr.assign(&arr),
+        }
+    }
+
+    /// Evaluates the expression at a single time point
+    pub fn eval_point(&self, t: f64) -> f64 {
+        let mut t_arr = ndarray::array![t];
+        self.eval_inplace(&mut t_arr.view_mut());
+        t_arr[0]
+    }
+}
+impl fmt::Display for MathExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.source)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::expression::*;
+
+    fn params(pairs: &[(&str, f64)]) -> InstrArgs {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn precedence() {
+        let no_params = InstrArgs::new();
+        let eval = |source: &str, t: f64| MathExpr::parse(source, &no_params).unwrap().eval_point(t);
+        assert_eq!(eval("1 + 2 * 3", 0.0), 7.0);
+        assert_eq!(eval("(1 + 2) * 3", 0.0), 9.0);
+        assert_eq!(eval("-t^2", 3.0), -9.0);
+        assert_eq!(eval("2^3^2", 0.0), 512.0);
+        assert_eq!(eval("2**-1", 0.0), 0.5);
+        assert_eq!(eval("8 / 4 / 2", 0.0), 1.0);
+        assert_eq!(eval("1e-3 * 2E3", 0.0), 2.0);
+        assert_eq!(eval("max(t, 1) + min(t, 1)", 3.0), 4.0);
+        assert_eq!(eval("step(t - 1)", 0.5), 0.0);
+        assert!((eval("sin(pi / 2) * e", 0.0) - E).abs() < 1e-15);
+    }
+
+    #[test]
+    fn gaussian() {
+        let expr = MathExpr::parse(
+            "A*exp(-(t-t0)^2/(2*s^2)) + c",
+            &params(&[("A", 2.0), ("t0", 1.0), ("s", 0.5), ("c", -1.0)]),
+        )
+        .unwrap();
+        let mut t_arr = ndarray::Array1::linspace(0.0, 2.0, 11);
+        let expected = t_arr.mapv(|t| 2.0 * (-(t - 1.0_f64).powi(2) / 0.5).exp() - 1.0);
+        expr.eval_inplace(&mut t_arr.view_mut());
+        for (val, expected) in t_arr.iter().zip(expected.iter()) {
+            assert!((val - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn constant_folding() {
+        let expr = MathExpr::parse("sqrt(a) * (1 + 2)", &params(&[("a", 4.0)])).unwrap();
+        assert_eq!(expr.root, Node::Const(6.0));
+        // Same source with different parameters compiles to a different tree
+        let other = MathExpr::parse("sqrt(a) * (1 + 2)", &params(&[("a", 9.0)])).unwrap();
+        assert!(expr != other);
+    }
+
+    #[test]
+    fn errors() {
+        let no_params = InstrArgs::new();
+        let err = |source: &str| MathExpr::parse(source, &no_params).unwrap_err();
+        assert!(err("t * x").contains("Undefined variable \"x\" at position 4"));
+        assert!(err("foo(t)").contains("Unknown function \"foo\""));
+        assert!(err("(t + 1").contains("Expected ')'"));
+        assert!(err("t +").contains("Unexpected end"));
+        assert!(err("t $ 2").contains("Unexpected character '$'"));
+        assert!(err("atan2(t)").contains("expects 2 argument(s), got 1"));
+        assert!(err("t t").contains("Unexpected token at position 2"));
+        assert!(MathExpr::parse("t", &params(&[("t", 1.0)])).is_err());
+    }
+}
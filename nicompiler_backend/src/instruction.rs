@@ -4,7 +4,13 @@
 //!
 //! - [`InstrType`]: An enumeration that defines the types of instructions supported, including `CONST` for constant values and `SINE` for sinusoidal waves.
 //!
-//! - [`Instruction`]: Represents a general instruction composed of a type (`InstrType`) and a set of arguments (`InstrArgs`). It offers methods for creating specific instruction types conveniently and for evaluating them.
+//! - [`InstrData`]: Non-scalar instruction payload (e.g. the sample buffer of an `ARB` waveform) which does not fit into `InstrArgs`.
+//!
+//! - [`InstrParams`]: Typed parameters of each instruction type, so that evaluation doesn't need any argument lookups.
+//!
+//! - [`Instruction`]: Represents a general instruction composed of a type (`InstrType`) and its parameters (`InstrParams`). It offers methods for creating specific instruction types conveniently and for evaluating them.
+//!
+//! - [`InstrError`]: Reasons an instruction can't be constructed, returned by the `try_*` constructors.
 //!
 //! - [`InstrBook`]: Manages an instruction along with its associated metadata during the experiment editing phase, capturing details like the defined interval and whether to retain a value after the defined interval.
 //!
@@ -24,18 +30,117 @@ use std::cmp::Ordering;
 use indexmap::IndexMap;
 use std::f64::consts::PI;
 use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::expression::MathExpr;
+use crate::waveform::{build_waveform, Waveform};
 use ndarray::array;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyDict};
 
 /// Type alias for instruction arguments: a dictionary with key-value pairs of
 /// string (argument name) and float (value)
 pub type InstrArgs = IndexMap<String, f64>;
 
-/// Enum type for different instructions. Supported instructions: `CONST`, `SINE`
+/// Largest value of an integer argument (`PULSETRAIN` `count`, `NOISE` `seed`): every integer up to `2^53`
+/// is exactly representable in [`InstrArgs`], so these survive [`Instruction::args`] round trips.
+pub const MAX_INT_ARG: u64 = 1 << 53;
+
+/// Error returned by the fallible instruction constructors ([`Instruction::try_new`], [`InstrBook::try_new`], ...).
+///
+/// The panicking constructors panic with the `Display` message of this error.
+/// At the python boundary it converts into a `ValueError`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum InstrError {
+    /// A required key is missing from the argument dictionary
+    MissingArg { instr_type: String, key: String },
+    /// An argument or data value is outside of its allowed range, or doesn't fit the instruction type
+    InvalidValue { instr_type: String, msg: String },
+    /// An instruction interval with `end_pos <= start_pos`
+    ZeroLengthInterval { start_pos: usize, end_pos: usize },
+    /// A NaN or infinite argument
+    NonFinite { instr_type: String, name: String, value: f64 },
+}
+impl fmt::Display for InstrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InstrError::MissingArg { instr_type, key } => {
+                write!(f, "Expected instr type {} to contain key {}", instr_type, key)
+            }
+            InstrError::InvalidValue { instr_type, msg } => write!(f, "Invalid {} instruction: {}", instr_type, msg),
+            InstrError::ZeroLengthInterval { start_pos, end_pos } => write!(
+                f,
+                "Instruction must satisfy `start_pos + 1 <= end_pos`, got start_pos = {} and end_pos = {}",
+                start_pos, end_pos
+            ),
+            InstrError::NonFinite { instr_type, name, value } => {
+                write!(f, "Argument {} of instr type {} must be finite, got {}", name, instr_type, value)
+            }
+        }
+    }
+}
+impl std::error::Error for InstrError {}
+impl From<InstrError> for PyErr {
+    fn from(err: InstrError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+/// Checks that the start time `t` and the `duration` (if any) of an instruction placed on a channel are finite.
+///
+/// NaN and infinite times would silently turn into tick 0 (or saturate) when rounded to the clock grid.
+pub fn check_instr_timing(instr_type: &InstrType, t: f64, duration: Option<f64>) -> Result<(), InstrError> {
+    for (name, value) in [("t", Some(t)), ("duration", duration)] {
+        if let Some(value) = value.filter(|value| !value.is_finite()) {
+            return Err(InstrError::NonFinite {
+                instr_type: instr_type.to_string(),
+                name: name.to_string(),
+                value,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Enum type for different instructions. Supported instructions: `CONST`, `SINE`, `CHIRP`, `LINRAMP`, `EXPRAMP`, `LOGRAMP`,
+/// `POWRAMP`, `TANHRAMP`, `MINJERKRAMP`, `COSRAMP`, `BLACKMANRAMP`, `GAUSSPULSE`, `BLACKMANPULSE`, `COS2PULSE`,
+/// `FLATTOPPULSE`, `PULSETRAIN`, `NOISE`, `ARB`, `PWL`, `SPLINE`, `POLY`, `FOURIER`, `EXPR`, `CUSTOM` (see [`crate::waveform`]), and the composite `SUM`, `PRODUCT`, `SCALE`, `OFFSET`, `CLIP`, `TIMESHIFT`
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InstrType {
     CONST,
     SINE,
+    CHIRP,   // Sine with swept frequency
     LINRAMP, // Linear ramp
+    EXPRAMP, // Exponential ramp
+    LOGRAMP, // Logarithmic ramp
+    POWRAMP, // Power-law ramp
+    TANHRAMP,     // Hyperbolic tangent ramp
+    MINJERKRAMP,  // Minimum-jerk (5th order polynomial) ramp
+    COSRAMP,      // Raised-cosine ramp
+    BLACKMANRAMP, // Ramp following the integral of the Blackman window
+    GAUSSPULSE,    // Gaussian-windowed pulse
+    BLACKMANPULSE, // Blackman-windowed pulse
+    COS2PULSE,     // cos^2-windowed pulse
+    FLATTOPPULSE,  // Flat-top pulse with cos^2 rise and fall
+    PULSETRAIN,    // Periodic 0/1 square wave for digital lines
+    NOISE,   // Seeded pseudo-random noise
+    ARB,     // Arbitrary sampled waveform
+    PWL,     // Piecewise-linear through breakpoints
+    SPLINE,  // Natural cubic spline through breakpoints
+    POLY,    // Polynomial in instruction-local time
+    FOURIER, // Fourier series over a fundamental frequency
+    EXPR,    // Math expression string
+    CUSTOM,  // Externally defined `Waveform`
+    // Composite instructions built from child instructions
+    SUM,
+    PRODUCT,
+    SCALE,
+    OFFSET,
+    CLIP,
+    TIMESHIFT,
 }
 impl fmt::Display for InstrType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -45,22 +150,822 @@ impl fmt::Display for InstrType {
             match self {
                 InstrType::CONST => "CONST",
                 InstrType::SINE => "SINE",
+                InstrType::CHIRP => "CHIRP",
                 InstrType::LINRAMP => "LINRAMP",
+                InstrType::EXPRAMP => "EXPRAMP",
+                InstrType::LOGRAMP => "LOGRAMP",
+                InstrType::POWRAMP => "POWRAMP",
+                InstrType::TANHRAMP => "TANHRAMP",
+                InstrType::MINJERKRAMP => "MINJERKRAMP",
+                InstrType::COSRAMP => "COSRAMP",
+                InstrType::BLACKMANRAMP => "BLACKMANRAMP",
+                InstrType::GAUSSPULSE => "GAUSSPULSE",
+                InstrType::BLACKMANPULSE => "BLACKMANPULSE",
+                InstrType::COS2PULSE => "COS2PULSE",
+                InstrType::FLATTOPPULSE => "FLATTOPPULSE",
+                InstrType::PULSETRAIN => "PULSETRAIN",
+                InstrType::NOISE => "NOISE",
+                InstrType::ARB => "ARB",
+                InstrType::PWL => "PWL",
+                InstrType::SPLINE => "SPLINE",
+                InstrType::POLY => "POLY",
+                InstrType::FOURIER => "FOURIER",
+                InstrType::EXPR => "EXPR",
+                InstrType::CUSTOM => "CUSTOM",
+                InstrType::SUM => "SUM",
+                InstrType::PRODUCT => "PRODUCT",
+                InstrType::SCALE => "SCALE",
+                InstrType::OFFSET => "OFFSET",
+                InstrType::CLIP => "CLIP",
+                InstrType::TIMESHIFT => "TIMESHIFT",
+            }
+        )
+    }
+}
+impl FromStr for InstrType {
+    type Err = String;
+
+    /// Parses the upper-case names printed by `Display`, e.g. `"LINRAMP"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let instr_type = match s {
+            "CONST" => InstrType::CONST,
+            "SINE" => InstrType::SINE,
+            "CHIRP" => InstrType::CHIRP,
+            "LINRAMP" => InstrType::LINRAMP,
+            "EXPRAMP" => InstrType::EXPRAMP,
+            "LOGRAMP" => InstrType::LOGRAMP,
+            "POWRAMP" => InstrType::POWRAMP,
+            "TANHRAMP" => InstrType::TANHRAMP,
+            "MINJERKRAMP" => InstrType::MINJERKRAMP,
+            "COSRAMP" => InstrType::COSRAMP,
+            "BLACKMANRAMP" => InstrType::BLACKMANRAMP,
+            "GAUSSPULSE" => InstrType::GAUSSPULSE,
+            "BLACKMANPULSE" => InstrType::BLACKMANPULSE,
+            "COS2PULSE" => InstrType::COS2PULSE,
+            "FLATTOPPULSE" => InstrType::FLATTOPPULSE,
+            "PULSETRAIN" => InstrType::PULSETRAIN,
+            "NOISE" => InstrType::NOISE,
+            "ARB" => InstrType::ARB,
+            "PWL" => InstrType::PWL,
+            "SPLINE" => InstrType::SPLINE,
+            "POLY" => InstrType::POLY,
+            "FOURIER" => InstrType::FOURIER,
+            "EXPR" => InstrType::EXPR,
+            "CUSTOM" => InstrType::CUSTOM,
+            "SUM" => InstrType::SUM,
+            "PRODUCT" => InstrType::PRODUCT,
+            "SCALE" => InstrType::SCALE,
+            "OFFSET" => InstrType::OFFSET,
+            "CLIP" => InstrType::CLIP,
+            "TIMESHIFT" => InstrType::TIMESHIFT,
+            _ => return Err(format!("Unknown instruction type \"{}\"", s)),
+        };
+        Ok(instr_type)
+    }
+}
+
+/// Interpolation scheme used to resample a sampled waveform onto the channel clock grid.
+///
+/// - `Nearest`: value of the closest sample (zero-order hold around each sample point);
+/// - `Linear`: straight line between the two neighbouring samples;
+/// - `Cubic`: Catmull-Rom cubic through the four neighbouring samples.
+///   Passes through every sample point and has a continuous first derivative.
+///
+/// Outside of the sampled interval all schemes hold the first/last sample value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interp {
+    Nearest,
+    Linear,
+    Cubic,
+}
+impl Interp {
+    /// Evaluates the interpolant through `samples` at fractional sample index `x`.
+    ///
+    /// Sample `i` is located at `x = i`. Neighbours beyond the buffer edges are clamped to the edge samples.
+    pub fn interpolate(&self, samples: &[f64], x: f64) -> f64 {
+        let last = samples.len() - 1;
+        let x = x.max(0.0).min(last as f64);
+        let at = |idx: isize| samples[idx.max(0).min(last as isize) as usize];
+        match self {
+            Interp::Nearest => samples[x.round() as usize],
+            Interp::Linear => {
+                let i = x.floor() as isize;
+                let frac = x - i as f64;
+                at(i) + frac * (at(i + 1) - at(i))
+            }
+            Interp::Cubic => {
+                let i = x.floor() as isize;
+                let u = x - i as f64;
+                let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+                p1 + 0.5 * u * (p2 - p0
+                    + u * (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3
+                    + u * (3.0 * (p1 - p2) + p3 - p0)))
+            }
+        }
+    }
+}
+impl FromStr for Interp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "nearest" => Ok(Interp::Nearest),
+            "linear" => Ok(Interp::Linear),
+            "cubic" => Ok(Interp::Cubic),
+            _ => Err(format!(
+                "Unknown interpolation \"{}\". Supported values are \"nearest\", \"linear\" and \"cubic\"",
+                s
+            )),
+        }
+    }
+}
+impl fmt::Display for Interp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Interp::Nearest => "nearest",
+                Interp::Linear => "linear",
+                Interp::Cubic => "cubic",
+            }
+        )
+    }
+}
+
+/// Time reference of the carrier phase of periodic instructions (`SINE` and windowed pulses with a carrier).
+///
+/// - `Absolute`: `sin(2 pi f t + phase)` with the absolute experiment time `t`, so the phase at the instruction start
+///   depends on when it starts;
+/// - `Local`: time is counted from the instruction start, so the instruction starts with `phase`
+///   no matter where it is placed;
+/// - `Continuous`: the carrier continues the phase of the closest preceding instruction on the channel
+///   with the same carrier frequency, shifted by `phase`. Without such an instruction it behaves as `Local`.
+///
+/// `Local` and `Continuous` depend on where the instruction ends up on the channel, so they are resolved
+/// into an equivalent `Absolute` instruction by `BaseChannel::compile` (see [`Instruction::resolve_time_ref`]).
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeRef {
+    Absolute,
+    Local,
+    Continuous,
+}
+impl FromStr for TimeRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "absolute" => Ok(TimeRef::Absolute),
+            "local" => Ok(TimeRef::Local),
+            "continuous" => Ok(TimeRef::Continuous),
+            _ => Err(format!(
+                "Unknown time reference \"{}\". Supported values are \"absolute\", \"local\" and \"continuous\"",
+                s
+            )),
+        }
+    }
+}
+impl fmt::Display for TimeRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                TimeRef::Absolute => "absolute",
+                TimeRef::Local => "local",
+                TimeRef::Continuous => "continuous",
+            }
+        )
+    }
+}
+
+/// Profile of a smooth ramp, see [`Instruction::new_tanhramp`] and [`Instruction::new_smooth_ramp`].
+///
+/// Parsed from the shape names used on the python side: `"tanh"`, `"minjerk"`, `"cos"` and `"blackman"`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RampShape {
+    Tanh,
+    MinJerk,
+    Cos,
+    Blackman,
+}
+impl RampShape {
+    /// Instruction type implementing the shape
+    pub fn instr_type(&self) -> InstrType {
+        match self {
+            RampShape::Tanh => InstrType::TANHRAMP,
+            RampShape::MinJerk => InstrType::MINJERKRAMP,
+            RampShape::Cos => InstrType::COSRAMP,
+            RampShape::Blackman => InstrType::BLACKMANRAMP,
+        }
+    }
+
+    /// Builds the ramp of this shape from `start_val` at `start_time` to `end_val` at `end_time`.
+    ///
+    /// `steepness` is only accepted for [`RampShape::Tanh`], see [`Instruction::new_tanhramp`].
+    pub fn try_new_ramp(
+        &self,
+        start_val: f64,
+        end_val: f64,
+        start_time: f64,
+        end_time: f64,
+        steepness: Option<f64>,
+    ) -> Result<Instruction, InstrError> {
+        match self {
+            RampShape::Tanh => Instruction::try_new_tanhramp(start_val, end_val, start_time, end_time, steepness),
+            _ if steepness.is_some() => Err(InstrError::InvalidValue {
+                instr_type: self.instr_type().to_string(),
+                msg: format!(
+                    "Steepness can only be specified for the \"tanh\" ramp shape, got shape \"{}\"",
+                    self
+                ),
+            }),
+            _ => Instruction::try_new_smooth_ramp(self.instr_type(), start_val, end_val, start_time, end_time),
+        }
+    }
+}
+impl FromStr for RampShape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "tanh" => Ok(RampShape::Tanh),
+            "minjerk" => Ok(RampShape::MinJerk),
+            "cos" => Ok(RampShape::Cos),
+            "blackman" => Ok(RampShape::Blackman),
+            _ => Err(format!(
+                "Unknown smooth ramp shape \"{}\". Supported shapes are \"tanh\", \"minjerk\", \"cos\" and \"blackman\"",
+                s
+            )),
+        }
+    }
+}
+impl fmt::Display for RampShape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                RampShape::Tanh => "tanh",
+                RampShape::MinJerk => "minjerk",
+                RampShape::Cos => "cos",
+                RampShape::Blackman => "blackman",
+            }
+        )
+    }
+}
+
+/// Window of a windowed pulse, see [`Instruction::new_pulse`].
+///
+/// Parsed from the window names used on the python side: `"gauss"`, `"blackman"`, `"cos2"` and `"flattop"`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PulseWindow {
+    Gauss,
+    Blackman,
+    Cos2,
+    FlatTop,
+}
+impl PulseWindow {
+    /// Instruction type implementing the window
+    pub fn instr_type(&self) -> InstrType {
+        match self {
+            PulseWindow::Gauss => InstrType::GAUSSPULSE,
+            PulseWindow::Blackman => InstrType::BLACKMANPULSE,
+            PulseWindow::Cos2 => InstrType::COS2PULSE,
+            PulseWindow::FlatTop => InstrType::FLATTOPPULSE,
+        }
+    }
+}
+impl FromStr for PulseWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "gauss" => Ok(PulseWindow::Gauss),
+            "blackman" => Ok(PulseWindow::Blackman),
+            "cos2" => Ok(PulseWindow::Cos2),
+            "flattop" => Ok(PulseWindow::FlatTop),
+            _ => Err(format!(
+                "Unknown pulse window \"{}\". Supported windows are \"gauss\", \"blackman\", \"cos2\" and \"flattop\"",
+                s
+            )),
+        }
+    }
+}
+impl fmt::Display for PulseWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PulseWindow::Gauss => "gauss",
+                PulseWindow::Blackman => "blackman",
+                PulseWindow::Cos2 => "cos2",
+                PulseWindow::FlatTop => "flattop",
             }
         )
     }
 }
 
+/// Non-scalar instruction data which can't be stored in the `f64`-valued [`InstrArgs`].
+///
+/// [`Instruction`] holds it behind an `Arc`, so cloning an instruction
+/// (which `BaseChannel::compile` does for every compiled segment) never copies the buffers.
+#[derive(Clone, PartialEq)]
+pub enum InstrData {
+    /// Sample buffer of an `ARB` instruction together with the interpolation scheme
+    Samples { samples: Vec<f64>, interp: Interp },
+    /// Breakpoints of a `PWL` or `SPLINE` instruction. Times are relative to the instruction `start_time`.
+    ///
+    /// For `SPLINE`, `moments` holds the second derivatives of the natural spline at each breakpoint,
+    /// so the tridiagonal system is only solved once when the instruction is created. It is empty for `PWL`.
+    Breakpoints {
+        times: Vec<f64>,
+        values: Vec<f64>,
+        moments: Vec<f64>,
+    },
+    /// Polynomial coefficients of a `POLY` instruction, lowest order first
+    Coefficients(Vec<f64>),
+    /// Harmonics of a `FOURIER` instruction, `amplitudes[k]` and `phases[k]` belong to harmonic `k + 1`.
+    ///
+    /// `sin_coefs` and `cos_coefs` are precomputed from them so that harmonic `n` evaluates as
+    /// `sin_coefs[n-1] sin(n theta) + cos_coefs[n-1] cos(n theta)`.
+    Harmonics {
+        amplitudes: Vec<f64>,
+        phases: Vec<f64>,
+        sin_coefs: Vec<f64>,
+        cos_coefs: Vec<f64>,
+    },
+    /// Operands of a composite instruction (`SUM`, `PRODUCT`, `SCALE`, ...).
+    ///
+    /// Equality is structural, so two trees built the same way compare equal
+    /// and are merged by `BaseChannel::compile` just like plain instructions.
+    Children(Vec<Instruction>),
+    /// Compiled expression of an `EXPR` instruction
+    Expr(MathExpr),
+    /// Waveform of a `CUSTOM` instruction, compared with [`Waveform::dyn_eq`]
+    Custom(Arc<dyn Waveform>),
+}
+impl fmt::Display for InstrData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InstrData::Samples { samples, interp } => {
+                write!(f, "samples(len={}, interp={})", samples.len(), interp)
+            }
+            InstrData::Breakpoints { times, values, .. } => {
+                let points = times
+                    .iter()
+                    .zip(values.iter())
+                    .map(|(t, v)| format!("({}, {})", t, v))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "breakpoints([{}])", points)
+            }
+            InstrData::Coefficients(coefs) => write!(f, "coefficients({:?})", coefs),
+            InstrData::Harmonics { amplitudes, phases, .. } => {
+                write!(f, "harmonics(amplitudes={:?}, phases={:?})", amplitudes, phases)
+            }
+            InstrData::Children(children) => {
+                let children_string = children
+                    .iter()
+                    .map(|child| child.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "children([{}])", children_string)
+            }
+            InstrData::Expr(expr) => write!(f, "expr({})", expr),
+            InstrData::Custom(waveform) => write!(f, "{}", waveform),
+        }
+    }
+}
+
+/// Fraction of the ramp `[t_start, t_end]` passed by time `t`, clamped to `[0, 1]`.
+fn ramp_frac(t: f64, t_start: f64, t_end: f64) -> f64 {
+    ((t - t_start) / (t_end - t_start)).clamp(0.0, 1.0)
+}
+
+/// Smallest and largest of `vals`. NaNs are ignored.
+fn min_max(vals: impl IntoIterator<Item = f64>) -> (f64, f64) {
+    vals.into_iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), val| (min.min(val), max.max(val)))
+}
+
+/// Bounds of `x * y` for `x` and `y` within the bounds `a` and `b`.
+/// An infinite bound times 0 counts as 0.
+fn product_bounds(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    min_max([a.0 * b.0, a.0 * b.1, a.1 * b.0, a.1 * b.1].map(|val| if val.is_nan() { 0.0 } else { val }))
+}
+
+/// Checks that breakpoint times are strictly increasing and that there are at least two of them.
+fn check_breakpoints(times: &[f64], values: &[f64]) -> Result<(), String> {
+    if times.len() != values.len() || times.len() < 2 {
+        return Err(format!(
+            "requires at least 2 breakpoints with matching times and values, got {} times and {} values",
+            times.len(),
+            values.len()
+        ));
+    }
+    if !times.windows(2).all(|w| w[0] < w[1]) {
+        return Err(format!("requires strictly increasing breakpoint times, got {:?}", times));
+    }
+    Ok(())
+}
+
+/// Index `i` of the breakpoint interval `[times[i], times[i+1]]` containing `t`.
+/// Points outside of the breakpoint range are assigned to the first/last interval.
+fn breakpoint_interval(times: &[f64], t: f64) -> usize {
+    times
+        .partition_point(|&knot| knot <= t)
+        .max(1)
+        .min(times.len() - 1)
+        - 1
+}
+
+/// Pseudo-random 64-bit value for `index` in the stream `seed` (SplitMix64 finalizer).
+///
+/// Stateless, so any sample can be drawn on its own.
+fn noise_hash(seed: u64, index: i64) -> u64 {
+    let mut z = seed
+        .wrapping_add((index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Noise value number `index` of the stream `seed`: uniform in `[-1, 1)` or standard normal.
+fn noise_value(seed: u64, index: i64, gaussian: bool) -> f64 {
+    // Top 53 bits give a uniform float in [0, 1)
+    let unit = |hash: u64| (hash >> 11) as f64 / (1u64 << 53) as f64;
+    if gaussian {
+        // Box-Muller with the second uniform drawn from an independent stream
+        let u1 = 1.0 - unit(noise_hash(seed, index));
+        let u2 = unit(noise_hash(seed ^ 0xD1B5_4A32_D192_ED03, index));
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    } else {
+        2.0 * unit(noise_hash(seed, index)) - 1.0
+    }
+}
+
+/// Second derivatives of the natural cubic spline through the breakpoints (zero at both ends).
+///
+/// Solves the tridiagonal continuity system with the Thomas algorithm.
+fn natural_spline_moments(times: &[f64], values: &[f64]) -> Vec<f64> {
+    let n = times.len();
+    let mut moments = vec![0.0; n];
+    if n < 3 {
+        return moments;
+    }
+    // Forward sweep: `diag` and `rhs` hold the eliminated system for the inner knots
+    let mut diag = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    for i in 1..n - 1 {
+        let h_prev = times[i] - times[i - 1];
+        let h_next = times[i + 1] - times[i];
+        let slope_diff = (values[i + 1] - values[i]) / h_next - (values[i] - values[i - 1]) / h_prev;
+        diag[i] = 2.0 * (h_prev + h_next);
+        rhs[i] = 6.0 * slope_diff;
+        if i > 1 {
+            let factor = h_prev / diag[i - 1];
+            diag[i] -= factor * h_prev;
+            rhs[i] -= factor * rhs[i - 1];
+        }
+    }
+    // Back substitution
+    for i in (1..n - 1).rev() {
+        let h_next = times[i + 1] - times[i];
+        moments[i] = (rhs[i] - h_next * moments[i + 1]) / diag[i];
+    }
+    moments
+}
+
+/// `(sin_coefs, cos_coefs)` of the harmonics of a `FOURIER` instruction, see [`InstrData::Harmonics`]
+fn fourier_coefs(amplitudes: &[f64], phases: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    // sin(x + phase) = cos(phase) sin(x) + sin(phase) cos(x)
+    amplitudes.iter().zip(phases.iter()).map(|(amp, phase)| (amp * phase.cos(), amp * phase.sin())).unzip()
+}
+
+/// Typed scalar parameters of an instruction.
+///
+/// Each variant covers one family of instruction types and holds its parameters with defaults already applied,
+/// so evaluation never looks anything up by name. [`InstrParams::from_args`] parses an argument dictionary
+/// and [`Instruction::args`] gives the dictionary view back (used for python and `Display`).
+/// Non-scalar payload (sample buffers, breakpoints, children, ...) stays in [`InstrData`].
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InstrParams {
+    /// `CONST`
+    Const { value: f64 },
+    /// `SINE`
+    Sine {
+        freq: f64,
+        amplitude: f64,
+        offset: f64,
+        phase: f64,
+    },
+    /// `CHIRP`
+    Chirp {
+        f_start: f64,
+        f_end: f64,
+        start_time: f64,
+        end_time: f64,
+        exponential: bool,
+        amplitude: f64,
+        offset: f64,
+        phase: f64,
+    },
+    /// Ramps fully defined by their end points: `LINRAMP`, geometric `EXPRAMP`, `MINJERKRAMP`, `COSRAMP` and `BLACKMANRAMP`
+    Ramp {
+        start_val: f64,
+        end_val: f64,
+        start_time: f64,
+        end_time: f64,
+    },
+    /// `EXPRAMP` relaxing towards `target_val` with time constant `tau`
+    ExpRampTau {
+        start_val: f64,
+        target_val: f64,
+        tau: f64,
+        start_time: f64,
+    },
+    /// `LOGRAMP`
+    LogRamp {
+        start_val: f64,
+        end_val: f64,
+        start_time: f64,
+        end_time: f64,
+        tau: f64,
+    },
+    /// `POWRAMP`
+    PowRamp {
+        start_val: f64,
+        end_val: f64,
+        start_time: f64,
+        end_time: f64,
+        exponent: f64,
+    },
+    /// `TANHRAMP`
+    TanhRamp {
+        start_val: f64,
+        end_val: f64,
+        start_time: f64,
+        end_time: f64,
+        steepness: f64,
+    },
+    /// Windowed pulses. `freq` is `None` for a DC pulse.
+    /// `sigma` is only used by `GAUSSPULSE`, `rise_time` and `fall_time` only by `FLATTOPPULSE`.
+    Pulse {
+        start_time: f64,
+        end_time: f64,
+        amplitude: f64,
+        offset: f64,
+        freq: Option<f64>,
+        phase: f64,
+        sigma: f64,
+        rise_time: f64,
+        fall_time: f64,
+    },
+    /// `PULSETRAIN`. `phase` is a fraction of the period, `count` is `None` for an unlimited train.
+    PulseTrain {
+        start_time: f64,
+        period: f64,
+        duty: f64,
+        phase: f64,
+        count: Option<usize>,
+    },
+    /// `NOISE`. `samp_rate` is the rate of the channel the noise is drawn for, `bandwidth` is `None` for white noise.
+    Noise {
+        amplitude: f64,
+        offset: f64,
+        seed: u64,
+        gaussian: bool,
+        bandwidth: Option<f64>,
+        samp_rate: f64,
+    },
+    /// `ARB`, samples are held in [`InstrData::Samples`]
+    Sampled { start_time: f64, samp_rate: f64 },
+    /// `PWL` and `SPLINE`, breakpoints are held in [`InstrData::Breakpoints`]
+    Breakpoints { start_time: f64 },
+    /// `POLY`, coefficients are held in [`InstrData::Coefficients`]
+    Poly { start_time: f64 },
+    /// `FOURIER` fundamental frequency and DC offset, harmonics are held in [`InstrData::Harmonics`]
+    Fourier { freq: f64, offset: f64 },
+    /// User-named `EXPR` parameters and `CUSTOM` arguments. These are only kept for display and equality:
+    /// the compiled expression / waveform holds its own copy of the values.
+    Named(InstrArgs),
+    /// `SUM` and `PRODUCT`
+    Empty,
+    /// `SCALE`
+    Scale { factor: f64 },
+    /// `OFFSET`
+    Offset { offset: f64 },
+    /// `CLIP`, a missing bound is stored as an infinity (and serialized as a missing value)
+    Clip {
+        #[cfg_attr(feature = "serde", serde(with = "serialize::lower_bound"))]
+        min: f64,
+        #[cfg_attr(feature = "serde", serde(with = "serialize::upper_bound"))]
+        max: f64,
+    },
+    /// `TIMESHIFT`
+    TimeShift { dt: f64 },
+}
+impl InstrParams {
+    /// Parses an argument dictionary into the parameters of `instr_type`, applying defaults for optional keys.
+    ///
+    /// Panics if a required key is missing, see [`InstrParams::try_from_args`].
+    pub fn from_args(instr_type: &InstrType, args: InstrArgs) -> InstrParams {
+        InstrParams::try_from_args(instr_type, args).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`InstrParams::from_args`], but returns [`InstrError::MissingArg`] for a missing required key.
+    /// The expected keys are listed in the [`Instruction`] docs.
+    pub fn try_from_args(instr_type: &InstrType, args: InstrArgs) -> Result<InstrParams, InstrError> {
+        let req = |key: &str| -> Result<f64, InstrError> {
+            args.get(key).copied().ok_or_else(|| InstrError::MissingArg {
+                instr_type: instr_type.to_string(),
+                key: key.to_string(),
+            })
+        };
+        let opt = |key: &str, default: f64| -> f64 { *args.get(key).unwrap_or(&default) };
+        // Integer arguments (pulse count, noise seed) travel as f64, so only exactly representable values are accepted
+        let int = |key: &str, value: f64| -> Result<u64, InstrError> {
+            if value.fract() == 0.0 && (0.0..=MAX_INT_ARG as f64).contains(&value) {
+                Ok(value as u64)
+            } else {
+                Err(InstrError::InvalidValue {
+                    instr_type: instr_type.to_string(),
+                    msg: format!("{} must be an integer in [0, 2^53], got {}", key, value),
+                })
+            }
+        };
+        let params = match instr_type {
+            InstrType::CONST => InstrParams::Const { value: req("value")? },
+            InstrType::SINE => InstrParams::Sine {
+                freq: req("freq")?,
+                amplitude: opt("amplitude", 1.0),
+                offset: opt("offset", 0.0),
+                phase: opt("phase", 0.0),
+            },
+            InstrType::CHIRP => InstrParams::Chirp {
+                f_start: req("f_start")?,
+                f_end: req("f_end")?,
+                start_time: req("start_time")?,
+                end_time: req("end_time")?,
+                exponential: opt("exponential", 0.0) != 0.0,
+                amplitude: opt("amplitude", 1.0),
+                offset: opt("offset", 0.0),
+                phase: opt("phase", 0.0),
+            },
+            InstrType::EXPRAMP if args.contains_key("tau") => InstrParams::ExpRampTau {
+                start_val: req("start_val")?,
+                target_val: req("target_val")?,
+                tau: req("tau")?,
+                start_time: req("start_time")?,
+            },
+            InstrType::LINRAMP
+            | InstrType::EXPRAMP
+            | InstrType::MINJERKRAMP
+            | InstrType::COSRAMP
+            | InstrType::BLACKMANRAMP => InstrParams::Ramp {
+                start_val: req("start_val")?,
+                end_val: req("end_val")?,
+                start_time: req("start_time")?,
+                end_time: req("end_time")?,
+            },
+            InstrType::LOGRAMP => InstrParams::LogRamp {
+                start_val: req("start_val")?,
+                end_val: req("end_val")?,
+                start_time: req("start_time")?,
+                end_time: req("end_time")?,
+                tau: req("tau")?,
+            },
+            InstrType::POWRAMP => InstrParams::PowRamp {
+                start_val: req("start_val")?,
+                end_val: req("end_val")?,
+                start_time: req("start_time")?,
+                end_time: req("end_time")?,
+                exponent: req("exponent")?,
+            },
+            InstrType::TANHRAMP => InstrParams::TanhRamp {
+                start_val: req("start_val")?,
+                end_val: req("end_val")?,
+                start_time: req("start_time")?,
+                end_time: req("end_time")?,
+                steepness: opt("steepness", 4.0),
+            },
+            InstrType::GAUSSPULSE
+            | InstrType::BLACKMANPULSE
+            | InstrType::COS2PULSE
+            | InstrType::FLATTOPPULSE => {
+                let (start_time, end_time) = (req("start_time")?, req("end_time")?);
+                let dur = end_time - start_time;
+                InstrParams::Pulse {
+                    start_time,
+                    end_time,
+                    amplitude: opt("amplitude", 1.0),
+                    offset: opt("offset", 0.0),
+                    freq: args.get("freq").copied(),
+                    phase: opt("phase", 0.0),
+                    sigma: opt("sigma", dur / 6.0),
+                    rise_time: opt("rise_time", dur / 10.0),
+                    fall_time: opt("fall_time", dur / 10.0),
+                }
+            }
+            InstrType::PULSETRAIN => InstrParams::PulseTrain {
+                start_time: req("start_time")?,
+                period: req("period")?,
+                duty: opt("duty", 0.5),
+                phase: opt("phase", 0.0),
+                count: args.get("count").map(|count| int("count", *count)).transpose()?.map(|count| count as usize),
+            },
+            InstrType::NOISE => InstrParams::Noise {
+                amplitude: opt("amplitude", 1.0),
+                offset: opt("offset", 0.0),
+                seed: int("seed", req("seed")?)?,
+                gaussian: opt("gaussian", 0.0) != 0.0,
+                bandwidth: args.get("bandwidth").copied(),
+                samp_rate: req("samp_rate")?,
+            },
+            InstrType::ARB => InstrParams::Sampled {
+                start_time: req("start_time")?,
+                samp_rate: req("samp_rate")?,
+            },
+            InstrType::PWL | InstrType::SPLINE => InstrParams::Breakpoints {
+                start_time: req("start_time")?,
+            },
+            InstrType::POLY => InstrParams::Poly {
+                start_time: req("start_time")?,
+            },
+            InstrType::FOURIER => InstrParams::Fourier {
+                freq: req("freq")?,
+                offset: opt("offset", 0.0),
+            },
+            InstrType::EXPR | InstrType::CUSTOM => InstrParams::Named(args.clone()),
+            InstrType::SUM | InstrType::PRODUCT => InstrParams::Empty,
+            InstrType::SCALE => InstrParams::Scale { factor: req("factor")? },
+            InstrType::OFFSET => InstrParams::Offset { offset: req("offset")? },
+            InstrType::CLIP => InstrParams::Clip {
+                min: opt("min", f64::NEG_INFINITY),
+                max: opt("max", f64::INFINITY),
+            },
+            InstrType::TIMESHIFT => InstrParams::TimeShift { dt: req("dt")? },
+        };
+        Ok(params)
+    }
+
+    /// Whether these parameters belong to `instr_type`
+    fn fits(&self, instr_type: &InstrType) -> bool {
+        match self {
+            InstrParams::Const { .. } => matches!(instr_type, InstrType::CONST),
+            InstrParams::Sine { .. } => matches!(instr_type, InstrType::SINE),
+            InstrParams::Chirp { .. } => matches!(instr_type, InstrType::CHIRP),
+            InstrParams::Ramp { .. } => matches!(
+                instr_type,
+                InstrType::LINRAMP
+                    | InstrType::EXPRAMP
+                    | InstrType::MINJERKRAMP
+                    | InstrType::COSRAMP
+                    | InstrType::BLACKMANRAMP
+            ),
+            InstrParams::ExpRampTau { .. } => matches!(instr_type, InstrType::EXPRAMP),
+            InstrParams::LogRamp { .. } => matches!(instr_type, InstrType::LOGRAMP),
+            InstrParams::PowRamp { .. } => matches!(instr_type, InstrType::POWRAMP),
+            InstrParams::TanhRamp { .. } => matches!(instr_type, InstrType::TANHRAMP),
+            InstrParams::Pulse { .. } => matches!(
+                instr_type,
+                InstrType::GAUSSPULSE
+                    | InstrType::BLACKMANPULSE
+                    | InstrType::COS2PULSE
+                    | InstrType::FLATTOPPULSE
+            ),
+            InstrParams::PulseTrain { .. } => matches!(instr_type, InstrType::PULSETRAIN),
+            InstrParams::Noise { .. } => matches!(instr_type, InstrType::NOISE),
+            InstrParams::Sampled { .. } => matches!(instr_type, InstrType::ARB),
+            InstrParams::Breakpoints { .. } => matches!(instr_type, InstrType::PWL | InstrType::SPLINE),
+            InstrParams::Poly { .. } => matches!(instr_type, InstrType::POLY),
+            InstrParams::Fourier { .. } => matches!(instr_type, InstrType::FOURIER),
+            InstrParams::Named(_) => matches!(instr_type, InstrType::EXPR | InstrType::CUSTOM),
+            InstrParams::Empty => matches!(instr_type, InstrType::SUM | InstrType::PRODUCT),
+            InstrParams::Scale { .. } => matches!(instr_type, InstrType::SCALE),
+            InstrParams::Offset { .. } => matches!(instr_type, InstrType::OFFSET),
+            InstrParams::Clip { .. } => matches!(instr_type, InstrType::CLIP),
+            InstrParams::TimeShift { .. } => matches!(instr_type, InstrType::TIMESHIFT),
+        }
+    }
+}
+
 // / This function uses [`other_function`] to ...
 // /
 // / [`other_function`]: ./path/to/other/function
 
-// Instruction struct consists of instr_type (enumerated type) and argument dictionary
-/// Struct for a general instruction, consisting of type and arguments.
+// Instruction struct consists of instr_type (enumerated type) and typed parameters
+/// Struct for a general instruction, consisting of type and parameters.
 ///
-/// Different instruction types expects different fields in their argument dictionary.
-/// Behavior for minimally expected keys are defined in `Instruction::new`, behavior of
-/// default values are defined in `Instruction::eval_inplace`.
+/// Parameters are stored as typed [`InstrParams`]. Instructions can also be built from an argument dictionary
+/// with `Instruction::new`, which defines the minimally expected keys and the default values of optional ones.
+/// [`Instruction::args`] returns the same dictionary view of the parameters.
 ///
 /// ## Implemented instruction types and their expected fields:
 /// 1. `InstrType::CONST`:
@@ -73,17 +978,89 @@ impl fmt::Display for InstrType {
 /// 3. `InstrType::LINRAMP`: 
 ///     - `start_val`
 ///     - `end_val`
+/// 4. `InstrType::CHIRP`:
+///     - `f_start`, `f_end`: frequencies at `start_time` and `end_time`
+///     - `start_time`, `end_time`
+///     - `exponential`: Default is `0.0` (linear sweep), non-zero for exponential sweep
+///     - `amplitude`: Default is `1.0`
+///     - `offset`: Default is `0.0`
+///     - `phase`: Default is `0.0`, phase at `start_time`
+/// 5. `InstrType::EXPRAMP`, one of the two parametrizations:
+///     - `start_val`, `end_val`, `start_time`, `end_time`: geometric ramp hitting both values exactly
+///     - `start_val`, `target_val`, `tau`, `start_time`: exponential relaxation towards `target_val`
+///       with time constant `tau`
+/// 6. `InstrType::LOGRAMP`:
+///     - `start_val`, `end_val`, `start_time`, `end_time`
+///     - `tau`: time scale of the logarithm, smaller values give a steeper start
+/// 7. `InstrType::POWRAMP`:
+///     - `start_val`, `end_val`, `start_time`, `end_time`
+///     - `exponent`
+/// 8. `InstrType::TANHRAMP`, `InstrType::MINJERKRAMP`, `InstrType::COSRAMP`, `InstrType::BLACKMANRAMP`:
+///     - `start_val`, `end_val`, `start_time`, `end_time`
+///     - `steepness` (`TANHRAMP` only): Default is `4.0`
+/// 9. `InstrType::GAUSSPULSE`, `InstrType::BLACKMANPULSE`, `InstrType::COS2PULSE`, `InstrType::FLATTOPPULSE`:
+///     - `start_time`, `end_time`: pulse edges
+///     - `amplitude`: Default is `1.0`
+///     - `offset`: Default is `0.0`
+///     - `freq`: carrier frequency, if absent the envelope multiplies a DC level
+///     - `phase`: Default is `0.0`, carrier phase
+///     - `sigma` (`GAUSSPULSE` only): Default is 1/6 of the pulse duration
+///     - `rise_time`, `fall_time` (`FLATTOPPULSE` only): Default is 1/10 of the pulse duration
+/// 10. `InstrType::PULSETRAIN`:
+///     - `start_time`: time the periods are counted from
+///     - `period`
+///     - `duty`: Default is `0.5`
+///     - `phase`: Default is `0.0`, fraction of the period
+///     - `count`: number of pulses, unlimited if absent
+/// 11. `InstrType::NOISE`:
+///     - `amplitude`: Default is `1.0`
+///     - `offset`: Default is `0.0`
+///     - `seed`
+///     - `gaussian`: Default is `0.0` (uniform), nonzero for Gaussian
+///     - `bandwidth`: bandwidth limit, white noise if absent
+///     - `samp_rate`: sample rate of the channel, sample indices are `round(t * samp_rate)`
+/// 12. `InstrType::ARB`:
+///     - `start_time`: absolute time of the first sample
+///     - `samp_rate`: sample rate of the waveform buffer
+///     - `data`: [`InstrData::Samples`] holding the sample buffer and the interpolation scheme
+/// 13. `InstrType::PWL` and `InstrType::SPLINE`:
+///     - `start_time`: absolute time which breakpoint times are counted from
+///     - `data`: [`InstrData::Breakpoints`]
+/// 14. `InstrType::POLY`:
+///     - `start_time`: the polynomial variable is `t - start_time`
+///     - `data`: [`InstrData::Coefficients`]
+/// 15. `InstrType::FOURIER`:
+///     - `freq`: fundamental frequency
+///     - `offset`: Default is `0.0`
+///     - `data`: [`InstrData::Harmonics`]
+/// 16. `InstrType::EXPR`:
+///     - named expression parameters (any keys)
+///     - `data`: [`InstrData::Expr`] holding the expression compiled with these parameters
+/// 17. `InstrType::CUSTOM`:
+///     - arguments the waveform was built from (any keys, required ones are defined at registration)
+///     - `data`: [`InstrData::Custom`] holding the [`Waveform`]
+/// 18. Composite types, all hold their operands as [`InstrData::Children`]:
+///     - `SUM`, `PRODUCT`: one or more children, no args
+///     - `SCALE`: one child, `factor`
+///     - `OFFSET`: one child, `offset`
+///     - `CLIP`: one child, `min` and/or `max` (a missing bound is not applied)
+///     - `TIMESHIFT`: one child, `dt` - the child is evaluated at `t - dt`
 #[derive(Clone, PartialEq)]
 pub struct Instruction {
     pub instr_type: InstrType,
-    pub args: InstrArgs,
+    pub params: InstrParams,
+    pub data: Option<Arc<InstrData>>,
+    /// Carrier phase reference, always `Absolute` for non-periodic instructions. See [`TimeRef`].
+    pub time_ref: TimeRef,
+    /// Whether the start value is picked up from the channel output, see [`Instruction::with_from_current`]
+    pub from_current: bool,
 }
 impl Instruction {
-    /// Constructs an `Instruction` object.
+    /// Constructs an `Instruction` object from an argument dictionary.
     ///
-    /// This method serves as the foundational constructor upon which custom constructor
-    /// wrappers for new instructions should be built. For each instruction type,
-    /// it ensures that the `args` dictionary contains the required keys.
+    /// For each instruction type, it ensures that the `args` dictionary contains the required keys
+    /// and parses it into typed [`InstrParams`]. The convenience constructors (`new_const`, `new_sine`, ...)
+    /// skip the dictionary and build the parameters directly with [`Instruction::new_with_params`].
     ///
     /// Missing keys will cause a panic.
     ///
@@ -126,165 +1103,1961 @@ impl Instruction {
     /// let sine_instr = Instruction::new(InstrType::SINE, sine_args);
     /// ```
     pub fn new(instr_type: InstrType, args: InstrArgs) -> Self {
-        let panic_no_key = |keys: &[&str]| {
-            for &key in keys {
-                if !args.contains_key(key) {
-                    panic!("Expected instr type {} to contain key {}", instr_type, key)
-                }
-            }
-        };
-        match instr_type {
-            InstrType::CONST => panic_no_key(&["value"]),
-            InstrType::SINE => panic_no_key(&["freq"]),
-            InstrType::LINRAMP => panic_no_key(&["start_val", "end_val", "start_time", "end_time"]),
-        };
-        Instruction { instr_type, args }
+        Instruction::new_with_data(instr_type, args, None)
     }
 
-    /// Evaluates the instruction and populates the given array view with float-point values.
-    ///
-    /// This method takes a mutable array view (`t_arr`) and modifies its values in-place based on the instruction type and its arguments.
-    ///
-    /// - For `InstrType::CONST`, the array will be filled with the constant value specified by the `value` argument.
-    /// - For `InstrType::SINE`, a sinusoidal waveform is generated using the arguments `freq`, `amplitude`, `offset`, and `phase`. Default values are used if certain arguments are not provided.
+    /// Same as [`Instruction::new`], but returns an [`InstrError`] instead of panicking.
     ///
-    /// # Arguments
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let err = Instruction::try_new(InstrType::CONST, InstrArgs::new()).err().unwrap();
+    /// assert_eq!(err, InstrError::MissingArg { instr_type: "CONST".to_string(), key: "value".to_string() });
     ///
-    /// * `t_arr` - A mutable 1D array view that will be populated with the evaluated values.
+    /// let mut args = InstrArgs::new();
+    /// args.insert("value".to_string(), f64::NAN);
+    /// assert!(matches!(Instruction::try_new(InstrType::CONST, args), Err(InstrError::NonFinite { .. })));
+    /// ```
+    pub fn try_new(instr_type: InstrType, args: InstrArgs) -> Result<Self, InstrError> {
+        Instruction::try_new_with_data(instr_type, args, None)
+    }
+
+    /// Same as [`Instruction::new`] but additionally attaches non-scalar [`InstrData`].
     ///
-    /// # Examples
+    /// Instruction types which require data (e.g. `ARB`) panic if it is missing or of the wrong kind.
+    pub fn new_with_data(instr_type: InstrType, args: InstrArgs, data: Option<InstrData>) -> Self {
+        Instruction::try_new_with_data(instr_type, args, data).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_with_data`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_with_data(instr_type: InstrType, args: InstrArgs, data: Option<InstrData>) -> Result<Self, InstrError> {
+        let params = InstrParams::try_from_args(&instr_type, args)?;
+        Instruction::try_new_with_params(instr_type, params, data)
+    }
+
+    /// Constructs an instruction from typed parameters and optional non-scalar [`InstrData`].
     ///
-    /// Given an instruction set with a constant and a sine instruction, and an array representing time values from 0 to 1:
+    /// Panics if `params` belong to a different instruction type, if the data required by
+    /// `instr_type` (e.g. the sample buffer of `ARB`) is missing or of the wrong kind,
+    /// or if any argument is out of range or not finite. See [`Instruction::try_new_with_params`].
     ///
     /// ```
-    /// use ndarray::{Array2, Array1};
-    /// use nicompiler_backend::instruction::*;
-    ///
-    /// let t_row = ndarray::Array1::linspace(0.0, 1.0, 10);
-    /// let mut t_values = ndarray::stack(ndarray::Axis(0), &[t_row.view(), t_row.view()]).unwrap();
-    /// // Use wrappers to create sine and constant instructions same as the examples above
-    /// let const_instr = Instruction::new_const(1.0);
-    /// const_instr.eval_inplace(&mut t_values.row_mut(0));
-    ///
-    /// let sine_instr = Instruction::new_sine(10.0, None, None, Some(1.0));
-    /// sine_instr.eval_inplace(&mut t_values.row_mut(1));
-    /// assert!(t_values[[0, 0]] == 1. && t_values[[0, 1]] == 1.);
+    /// # use nicompiler_backend::instruction::*;
+    /// let instr = Instruction::new_with_params(InstrType::CONST, InstrParams::Const { value: 1.0 }, None);
+    /// assert!(instr == Instruction::new_const(1.0));
     /// ```
-    pub fn eval_inplace(&self, t_arr: &mut ndarray::ArrayViewMut1<f64>) {
-        // Tocheck: t_arr may not be 1-dimensional. 
-        match self.instr_type {
-            InstrType::CONST => {
-                let value = *self.args.get("value").unwrap();
-                t_arr.fill(value);
-            }
-            InstrType::SINE => {
-                let freq = *self.args.get("freq").unwrap();
-                // Default values can be set by default with unwrap_or
-                let amplitude = *self.args.get("amplitude").unwrap_or(&1.0);
-                let offset = *self.args.get("offset").unwrap_or(&0.0);
-                let phase = *self.args.get("phase").unwrap_or(&0.0);
+    pub fn new_with_params(instr_type: InstrType, params: InstrParams, data: Option<InstrData>) -> Self {
+        Instruction::try_new_with_params(instr_type, params, data).unwrap_or_else(|err| panic!("{}", err))
+    }
 
-                t_arr.map_inplace(|t| {
-                    *t = (2.0 * PI * freq * (*t) + phase).sin() * amplitude + offset
-                });
+    /// Same as [`Instruction::new_with_params`], but returns an [`InstrError`] instead of panicking.
+    ///
+    /// All the typed constructors (`new_sine`, `new_pulse`, ...) end up here, so this is the single place
+    /// where instructions are validated. NaN and infinite arguments are rejected
+    /// (except for the unbounded sides of `CLIP`), so they never reach the sample buffers.
+    pub fn try_new_with_params(
+        instr_type: InstrType,
+        params: InstrParams,
+        data: Option<InstrData>,
+    ) -> Result<Self, InstrError> {
+        let instr = Instruction {
+            instr_type,
+            params,
+            data: data.map(Arc::new),
+            time_ref: TimeRef::Absolute,
+            from_current: false,
+        };
+        instr.validate()?;
+        Ok(instr)
+    }
+
+    /// Checks parameters and data of a freshly built instruction, see [`Instruction::try_new_with_params`].
+    fn validate(&self) -> Result<(), InstrError> {
+        let instr_type = &self.instr_type;
+        let invalid = |msg: String| InstrError::InvalidValue {
+            instr_type: instr_type.to_string(),
+            msg,
+        };
+        let check = |ok: bool, msg: String| if ok { Ok(()) } else { Err(invalid(msg)) };
+        let check_finite = |name: &str, values: &[f64]| match values.iter().find(|value| !value.is_finite()) {
+            Some(&value) => Err(InstrError::NonFinite {
+                instr_type: instr_type.to_string(),
+                name: name.to_string(),
+                value,
+            }),
+            None => Ok(()),
+        };
+
+        check(
+            self.params.fits(instr_type),
+            format!("parameters {:?} don't belong to instr type {}", self.params, instr_type),
+        )?;
+
+        // Scalar arguments
+        match self.params {
+            // Infinite bounds stand for an unbounded side
+            InstrParams::Clip { min, max } => {
+                for (name, bound) in [("min", min), ("max", max)] {
+                    if bound.is_nan() {
+                        check_finite(name, &[bound])?;
+                    }
+                }
+            }
+            _ => {
+                for (name, value) in self.args() {
+                    check_finite(&name, &[value])?;
+                }
+            }
+        };
+        match self.params {
+            InstrParams::Ramp { start_val, end_val, .. } if *instr_type == InstrType::EXPRAMP => check(
+                start_val * end_val > 0.0,
+                format!("start_val and end_val must be non-zero and have the same sign, got {} and {}", start_val, end_val),
+            )?,
+            InstrParams::ExpRampTau { tau, .. } => check(tau > 0.0, format!("time constant must be positive, got {}", tau))?,
+            InstrParams::LogRamp { tau, .. } => check(tau > 0.0, format!("time scale must be positive, got {}", tau))?,
+            InstrParams::PowRamp { exponent, .. } => {
+                check(exponent > 0.0, format!("exponent must be positive, got {}", exponent))?
             }
-            InstrType::LINRAMP => {
-                let start_val = *self.args.get("start_val").unwrap();
-                let end_val = *self.args.get("end_val").unwrap();
-                let t_start = *self.args.get("start_time").unwrap();
-                let t_end = *self.args.get("end_time").unwrap();
-                // println!("{:?} \n {:?}", t_arr.shape(), t_arr);
+            InstrParams::TanhRamp { steepness, .. } => {
+                check(steepness > 0.0, format!("steepness must be positive, got {}", steepness))?
+            }
+            InstrParams::Chirp { f_start, f_end, exponential, .. } => check(
+                !exponential || (f_start > 0.0 && f_end > 0.0),
+                format!("exponential sweep requires positive frequencies, got f_start={} and f_end={}", f_start, f_end),
+            )?,
+            InstrParams::Pulse { start_time, end_time, sigma, rise_time, fall_time, .. } => match instr_type {
+                InstrType::GAUSSPULSE => check(sigma > 0.0, format!("sigma must be positive, got {}", sigma))?,
+                InstrType::FLATTOPPULSE => check(
+                    rise_time > 0.0 && fall_time > 0.0 && rise_time + fall_time <= end_time - start_time,
+                    format!(
+                        "rise_time ({}) and fall_time ({}) must be positive and fit into the pulse duration {}",
+                        rise_time,
+                        fall_time,
+                        end_time - start_time
+                    ),
+                )?,
+                _ => {}
+            },
+            InstrParams::PulseTrain { period, duty, phase, count, .. } => {
+                check(period > 0.0, format!("period must be positive, got {}", period))?;
+                check((0.0..=1.0).contains(&duty), format!("duty cycle must be in [0, 1], got {}", duty))?;
+                check((0.0..1.0).contains(&phase), format!("phase must be in [0, 1), got {}", phase))?;
+                check(count != Some(0), "count must be positive".to_string())?;
+                check(
+                    count.is_none_or(|count| count as u64 <= MAX_INT_ARG),
+                    format!("count must not exceed 2^53, got {}", count.unwrap_or(0)),
+                )?;
+            }
+            InstrParams::Noise { seed, bandwidth, samp_rate, .. } => {
+                check(seed <= MAX_INT_ARG, format!("seed must not exceed 2^53, got {}", seed))?;
+                check(samp_rate > 0.0, format!("samp_rate must be positive, got {}", samp_rate))?;
+                if let Some(bandwidth) = bandwidth {
+                    check(bandwidth > 0.0, format!("bandwidth must be positive, got {}", bandwidth))?;
+                }
+            }
+            InstrParams::Sampled { samp_rate, .. } => {
+                check(samp_rate > 0.0, format!("sample rate must be positive, got {}", samp_rate))?
+            }
+            InstrParams::Clip { min, max } => check(min <= max, format!("min {} must not exceed max {}", min, max))?,
+            _ => {}
+        };
 
-                t_arr.map_inplace(|t| {
-                    *t = (*t - t_start) * (end_val - start_val) / (t_end - t_start) + start_val;
-                });
+        // Non-scalar data
+        let data = self.data.as_deref();
+        match instr_type {
+            InstrType::ARB => match data {
+                Some(InstrData::Samples { samples, .. }) => {
+                    check(!samples.is_empty(), "requires a non-empty sample buffer".to_string())?;
+                    check_finite("samples", samples)?;
+                }
+                _ => return Err(invalid("expected sample data".to_string())),
+            },
+            InstrType::PWL | InstrType::SPLINE => match data {
+                Some(InstrData::Breakpoints { times, values, moments }) => {
+                    check_finite("breakpoint times", times)?;
+                    check_finite("breakpoint values", values)?;
+                    check_breakpoints(times, values).map_err(invalid)?;
+                    let num_moments = if *instr_type == InstrType::SPLINE { times.len() } else { 0 };
+                    check(
+                        moments.len() == num_moments,
+                        format!("expected {} spline moments, got {}", num_moments, moments.len()),
+                    )?;
+                }
+                _ => return Err(invalid("expected breakpoint data".to_string())),
+            },
+            InstrType::POLY => match data {
+                Some(InstrData::Coefficients(coefs)) => {
+                    check(!coefs.is_empty(), "requires at least one coefficient".to_string())?;
+                    check_finite("coefficients", coefs)?;
+                }
+                _ => return Err(invalid("expected polynomial coefficients".to_string())),
+            },
+            InstrType::FOURIER => match data {
+                Some(InstrData::Harmonics { amplitudes, phases, sin_coefs, cos_coefs }) => {
+                    check(
+                        !amplitudes.is_empty() && amplitudes.len() == phases.len(),
+                        format!(
+                            "requires at least one harmonic with matching amplitudes and phases, got {} amplitudes and {} phases",
+                            amplitudes.len(),
+                            phases.len()
+                        ),
+                    )?;
+                    check(
+                        sin_coefs.len() == amplitudes.len() && cos_coefs.len() == amplitudes.len(),
+                        format!(
+                            "expected {} sin and cos coefficients, got {} and {}",
+                            amplitudes.len(),
+                            sin_coefs.len(),
+                            cos_coefs.len()
+                        ),
+                    )?;
+                    check_finite("amplitudes", amplitudes)?;
+                    check_finite("phases", phases)?;
+                }
+                _ => return Err(invalid("expected harmonics".to_string())),
+            },
+            InstrType::EXPR => check(
+                matches!(data, Some(InstrData::Expr(_))),
+                "expected a compiled expression".to_string(),
+            )?,
+            InstrType::CUSTOM => check(matches!(data, Some(InstrData::Custom(_))), "expected a waveform".to_string())?,
+            InstrType::SUM
+            | InstrType::PRODUCT
+            | InstrType::SCALE
+            | InstrType::OFFSET
+            | InstrType::CLIP
+            | InstrType::TIMESHIFT => {
+                let num_children = match data {
+                    Some(InstrData::Children(children)) => children.len(),
+                    _ => return Err(invalid("expected child instructions".to_string())),
+                };
+                match instr_type {
+                    InstrType::SUM | InstrType::PRODUCT => {
+                        check(num_children >= 1, "requires at least one child instruction".to_string())?
+                    }
+                    _ => check(
+                        num_children == 1,
+                        format!("requires exactly one child instruction, got {}", num_children),
+                    )?,
+                };
             }
+            _ => {}
+        };
+        Ok(())
+    }
+
+    /// Sets the carrier phase reference of a periodic instruction.
+    ///
+    /// Panics if the instruction has no carrier (anything but `SINE` and windowed pulses with `freq`),
+    /// unless `time_ref` is `Absolute`.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// // Starts at phase 0 wherever it is placed
+    /// let sine = Instruction::new_sine(1e3, None, None, None).with_time_ref(TimeRef::Local);
+    /// let resolved = sine.resolve_time_ref(0.25e-3, None);
+    /// assert!(resolved.eval_point(0.25e-3).abs() < 1e-12);
+    /// ```
+    pub fn with_time_ref(mut self, time_ref: TimeRef) -> Self {
+        assert!(
+            time_ref == TimeRef::Absolute || self.carrier().is_some(),
+            "Time reference {} requires a periodic instruction, got {}",
+            time_ref,
+            self
+        );
+        self.time_ref = time_ref;
+        self
+    }
+
+    /// Carrier `(freq, phase)` of periodic instructions
+    fn carrier(&self) -> Option<(f64, f64)> {
+        match self.params {
+            InstrParams::Sine { freq, phase, .. } => Some((freq, phase)),
+            InstrParams::Pulse { freq: Some(freq), phase, .. } => Some((freq, phase)),
+            _ => None,
         }
     }
 
-    /// Evaluate function at a single time point
-    pub fn eval_point(&self, t: f64) -> f64 {
-        let mut t_arr = array![t];
-        self.eval_inplace(&mut t_arr.view_mut());
-        t_arr[0]
+    /// Copy of the instruction with the carrier phase replaced by `phase` (wrapped into `[0, 2 pi)`)
+    /// and an `Absolute` time reference
+    fn with_absolute_phase(&self, phase: f64) -> Instruction {
+        let mut instr = self.clone();
+        match &mut instr.params {
+            InstrParams::Sine { phase: carrier_phase, .. }
+            | InstrParams::Pulse { phase: carrier_phase, .. } => *carrier_phase = phase.rem_euclid(2.0 * PI),
+            _ => unreachable!("Instruction {} has no carrier", self),
+        }
+        instr.time_ref = TimeRef::Absolute;
+        instr
     }
 
-    /// Wrapper for conveniently creating new constant instructions.
-    /// Example usage equivalent to the constant example above:
+    /// Converts a `Local` or `Continuous` instruction into the equivalent `Absolute` one.
+    ///
+    /// `start_time` is where the instruction starts on the channel. For `Continuous`, `prev` is the closest preceding
+    /// instruction with the same carrier frequency (already resolved). `Absolute` instructions are returned unchanged.
+    pub fn resolve_time_ref(&self, start_time: f64, prev: Option<&Instruction>) -> Instruction {
+        let (freq, phase) = match (self.time_ref, self.carrier()) {
+            (TimeRef::Absolute, _) | (_, None) => return self.clone(),
+            (_, Some(carrier)) => carrier,
+        };
+        // Phase offset such that `2 pi freq t + phase` at `start_time` equals `phase`
+        let local_phase = phase - 2.0 * PI * freq * start_time;
+        match (self.time_ref, prev.and_then(|prev| prev.carrier())) {
+            (TimeRef::Continuous, Some((prev_freq, prev_phase))) if prev_freq == freq => {
+                self.with_absolute_phase(prev_phase + phase)
+            }
+            _ => self.with_absolute_phase(local_phase),
+        }
+    }
+
+    /// Makes the instruction start from whatever the channel outputs when it begins,
+    /// instead of its own start value. Works for ramps defined by their end points (`LINRAMP`, `EXPRAMP`
+    /// without `tau` and the smooth ramps) and for `SINE`:
+    /// - a ramp replaces `start_val` with the current value
+    /// - a sine replaces its offset, such that its first sample equals the current value
+    ///
+    /// The current value is the preceding instruction evaluated at the start time (which covers `keep_val`
+    /// padding) or the channel default. It is only known once all instructions are placed,
+    /// so `BaseChannel::compile` resolves it with [`Instruction::resolve_from_current`].
+    ///
+    /// Panics for other instruction types.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let ramp = Instruction::new_linramp(0.0, 5.0, 1.0, 2.0).with_from_current();
+    /// let resolved = ramp.resolve_from_current(1.0, 3.0);
+    /// assert_eq!(resolved.eval_point(1.5), 4.0);
+    /// ```
+    pub fn with_from_current(mut self) -> Self {
+        assert!(
+            matches!(self.params, InstrParams::Ramp { .. } | InstrParams::Sine { .. }),
+            "Only ramps defined by their end points and sines can start from the current value, got {}",
+            self
+        );
+        self.from_current = true;
+        self
+    }
+
+    /// Converts an instruction created with [`Instruction::with_from_current`] into the equivalent one
+    /// with a fixed start value. `start_time` is where it starts on the channel, `current` the channel value there.
+    /// Other instructions are returned unchanged.
+    ///
+    /// Call it after [`Instruction::resolve_time_ref`], since the offset of a sine depends on its phase.
+    /// Panics if the start value is out of range for the instruction type (e.g. the wrong sign for `EXPRAMP`).
+    pub fn resolve_from_current(&self, start_time: f64, current: f64) -> Instruction {
+        if !self.from_current {
+            return self.clone();
+        }
+        let mut params = self.params.clone();
+        match &mut params {
+            InstrParams::Ramp { start_val, .. } => *start_val = current,
+            InstrParams::Sine { freq, amplitude, offset, phase } => {
+                *offset = current - *amplitude * (2.0 * PI * *freq * start_time + *phase).sin()
+            }
+            _ => unreachable!("Instruction {} can't start from the current value", self),
+        }
+        let mut instr = Instruction::new_with_params(self.instr_type.clone(), params, None);
+        instr.time_ref = self.time_ref;
+        instr
+    }
+
+    /// Copy of the instruction delayed by `dt`: the value at `t + dt` equals the original value at `t`.
+    ///
+    /// Used to move an instruction to a new start time on the channel. Where possible the time arguments
+    /// are shifted directly (everything with a `start_time`, the phase of an `Absolute` sine), so the result keeps
+    /// its type and flags. Other instructions (`FOURIER`, `EXPR`, `CUSTOM` and composites) are wrapped into a
+    /// `TIMESHIFT`. Instructions which don't depend on their position (`CONST`, `Local` and `Continuous` sines)
+    /// are returned unchanged, and so is `NOISE`: it is drawn per sample index, so the moved instruction
+    /// outputs the samples of the same noise stream at its new position.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let ramp = Instruction::new_linramp(0.0, 1.0, 1.0, 2.0);
+    /// let moved = ramp.shifted(0.5);
+    /// assert!(moved.instr_type == InstrType::LINRAMP);
+    /// assert_eq!(moved.eval_point(2.0), ramp.eval_point(1.5));
+    /// ```
+    pub fn shifted(&self, dt: f64) -> Instruction {
+        let mut instr = self.clone();
+        let absolute = self.time_ref == TimeRef::Absolute;
+        match &mut instr.params {
+            InstrParams::Const { .. } | InstrParams::Noise { .. } => {}
+            InstrParams::Sine { freq, phase, .. } => {
+                if absolute {
+                    *phase = (*phase - 2.0 * PI * *freq * dt).rem_euclid(2.0 * PI);
+                }
+            }
+            InstrParams::Chirp { start_time, end_time, .. }
+            | InstrParams::Ramp { start_time, end_time, .. }
+            | InstrParams::LogRamp { start_time, end_time, .. }
+            | InstrParams::PowRamp { start_time, end_time, .. }
+            | InstrParams::TanhRamp { start_time, end_time, .. } => {
+                *start_time += dt;
+                *end_time += dt;
+            }
+            InstrParams::ExpRampTau { start_time, .. }
+            | InstrParams::PulseTrain { start_time, .. }
+            | InstrParams::Sampled { start_time, .. }
+            | InstrParams::Breakpoints { start_time }
+            | InstrParams::Poly { start_time } => *start_time += dt,
+            InstrParams::Pulse { start_time, end_time, freq, phase, .. } => {
+                *start_time += dt;
+                *end_time += dt;
+                if let (Some(freq), true) = (freq, absolute) {
+                    *phase = (*phase - 2.0 * PI * *freq * dt).rem_euclid(2.0 * PI);
+                }
+            }
+            InstrParams::TimeShift { dt: child_dt } => *child_dt += dt,
+            _ => return Instruction::new_time_shift(self.clone(), dt),
+        }
+        instr
+    }
+
+    /// Carrier frequency of a periodic instruction
+    pub fn carrier_freq(&self) -> Option<f64> {
+        self.carrier().map(|(freq, _)| freq)
+    }
+
+    /// Dictionary view of the instruction parameters, with the same keys `Instruction::new` accepts.
+    ///
+    /// Optional parameters are listed with their effective (possibly default) values,
+    /// except for the absent carrier `freq` of a DC pulse and unbounded `CLIP` sides, which are left out.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let args = Instruction::new_sine(10.0, None, None, Some(0.5)).args();
+    /// assert_eq!(args["freq"], 10.0);
+    /// assert_eq!(args["amplitude"], 1.0);
+    /// assert_eq!(args["offset"], 0.5);
+    /// ```
+    pub fn args(&self) -> InstrArgs {
+        let pairs: Vec<(&str, f64)> = match self.params {
+            InstrParams::Const { value } => vec![("value", value)],
+            InstrParams::Sine { freq, amplitude, offset, phase } => vec![
+                ("freq", freq),
+                ("amplitude", amplitude),
+                ("offset", offset),
+                ("phase", phase),
+            ],
+            InstrParams::Chirp { f_start, f_end, start_time, end_time, exponential, amplitude, offset, phase } => vec![
+                ("f_start", f_start),
+                ("f_end", f_end),
+                ("start_time", start_time),
+                ("end_time", end_time),
+                ("exponential", if exponential { 1.0 } else { 0.0 }),
+                ("amplitude", amplitude),
+                ("offset", offset),
+                ("phase", phase),
+            ],
+            InstrParams::Ramp { start_val, end_val, start_time, end_time } => vec![
+                ("start_val", start_val),
+                ("end_val", end_val),
+                ("start_time", start_time),
+                ("end_time", end_time),
+            ],
+            InstrParams::ExpRampTau { start_val, target_val, tau, start_time } => vec![
+                ("start_val", start_val),
+                ("target_val", target_val),
+                ("tau", tau),
+                ("start_time", start_time),
+            ],
+            InstrParams::LogRamp { start_val, end_val, start_time, end_time, tau } => vec![
+                ("start_val", start_val),
+                ("end_val", end_val),
+                ("start_time", start_time),
+                ("end_time", end_time),
+                ("tau", tau),
+            ],
+            InstrParams::PowRamp { start_val, end_val, start_time, end_time, exponent } => vec![
+                ("start_val", start_val),
+                ("end_val", end_val),
+                ("start_time", start_time),
+                ("end_time", end_time),
+                ("exponent", exponent),
+            ],
+            InstrParams::TanhRamp { start_val, end_val, start_time, end_time, steepness } => vec![
+                ("start_val", start_val),
+                ("end_val", end_val),
+                ("start_time", start_time),
+                ("end_time", end_time),
+                ("steepness", steepness),
+            ],
+            InstrParams::Pulse { start_time, end_time, amplitude, offset, freq, phase, sigma, rise_time, fall_time } => {
+                let mut pairs = vec![
+                    ("start_time", start_time),
+                    ("end_time", end_time),
+                    ("amplitude", amplitude),
+                    ("offset", offset),
+                ];
+                if let Some(freq) = freq {
+                    pairs.push(("freq", freq));
+                }
+                pairs.push(("phase", phase));
+                match self.instr_type {
+                    InstrType::GAUSSPULSE => pairs.push(("sigma", sigma)),
+                    InstrType::FLATTOPPULSE => {
+                        pairs.push(("rise_time", rise_time));
+                        pairs.push(("fall_time", fall_time));
+                    }
+                    _ => {}
+                }
+                pairs
+            }
+            InstrParams::PulseTrain { start_time, period, duty, phase, count } => {
+                let mut pairs = vec![
+                    ("start_time", start_time),
+                    ("period", period),
+                    ("duty", duty),
+                    ("phase", phase),
+                ];
+                if let Some(count) = count {
+                    pairs.push(("count", count as f64));
+                }
+                pairs
+            }
+            InstrParams::Noise { amplitude, offset, seed, gaussian, bandwidth, samp_rate } => {
+                let mut pairs = vec![
+                    ("amplitude", amplitude),
+                    ("offset", offset),
+                    ("seed", seed as f64),
+                    ("gaussian", if gaussian { 1.0 } else { 0.0 }),
+                ];
+                if let Some(bandwidth) = bandwidth {
+                    pairs.push(("bandwidth", bandwidth));
+                }
+                pairs.push(("samp_rate", samp_rate));
+                pairs
+            }
+            InstrParams::Sampled { start_time, samp_rate } => {
+                vec![("start_time", start_time), ("samp_rate", samp_rate)]
+            }
+            InstrParams::Breakpoints { start_time } => vec![("start_time", start_time)],
+            InstrParams::Poly { start_time } => vec![("start_time", start_time)],
+            InstrParams::Fourier { freq, offset } => vec![("freq", freq), ("offset", offset)],
+            InstrParams::Named(ref args) => return args.clone(),
+            InstrParams::Empty => vec![],
+            InstrParams::Scale { factor } => vec![("factor", factor)],
+            InstrParams::Offset { offset } => vec![("offset", offset)],
+            InstrParams::Clip { min, max } => [("min", min), ("max", max)]
+                .into_iter()
+                .filter(|(_, bound)| bound.is_finite())
+                .collect(),
+            InstrParams::TimeShift { dt } => vec![("dt", dt)],
+        };
+        pairs
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect()
+    }
+
+    /// Evaluates the instruction and populates the given array view with float-point values.
+    ///
+    /// This method takes a mutable array view (`t_arr`) and modifies its values in-place based on the instruction type and its parameters.
+    ///
+    /// - For `InstrType::CONST`, the array will be filled with the constant value specified by the `value` argument.
+    /// - For `InstrType::SINE`, a sinusoidal waveform is generated from the `freq`, `amplitude`, `offset`, and `phase` parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `t_arr` - A mutable 1D array view that will be populated with the evaluated values.
+    ///
+    /// # Examples
+    ///
+    /// Given an instruction set with a constant and a sine instruction, and an array representing time values from 0 to 1:
+    ///
+    /// ```
+    /// use ndarray::{Array2, Array1};
+    /// use nicompiler_backend::instruction::*;
+    ///
+    /// let t_row = ndarray::Array1::linspace(0.0, 1.0, 10);
+    /// let mut t_values = ndarray::stack(ndarray::Axis(0), &[t_row.view(), t_row.view()]).unwrap();
+    /// // Use wrappers to create sine and constant instructions same as the examples above
+    /// let const_instr = Instruction::new_const(1.0);
+    /// const_instr.eval_inplace(&mut t_values.row_mut(0));
+    ///
+    /// let sine_instr = Instruction::new_sine(10.0, None, None, Some(1.0));
+    /// sine_instr.eval_inplace(&mut t_values.row_mut(1));
+    /// assert!(t_values[[0, 0]] == 1. && t_values[[0, 1]] == 1.);
+    /// ```
+    pub fn eval_inplace(&self, t_arr: &mut ndarray::ArrayViewMut1<f64>) {
+        // Tocheck: t_arr may not be 1-dimensional. 
+        match self.params {
+            InstrParams::Const { value } => {
+                t_arr.fill(value);
+            }
+            InstrParams::Sine { freq, amplitude, offset, phase } => {
+                t_arr.map_inplace(|t| {
+                    *t = (2.0 * PI * freq * (*t) + phase).sin() * amplitude + offset
+                });
+            }
+            InstrParams::Chirp { f_start, f_end, start_time: t_start, end_time: t_end, exponential, amplitude, offset, phase } => {
+                let dur = t_end - t_start;
+
+                // The phase is the analytic integral of the instantaneous frequency since `start_time`,
+                // so any sub-interval evaluates to exactly the same samples as the full instruction
+                if exponential && f_start != f_end {
+                    let log_ratio = (f_end / f_start).ln();
+                    t_arr.map_inplace(|t| {
+                        let cycles = f_start * dur / log_ratio * ((log_ratio * (*t - t_start) / dur).exp() - 1.0);
+                        *t = (2.0 * PI * cycles + phase).sin() * amplitude + offset
+                    });
+                } else {
+                    let sweep_rate = (f_end - f_start) / dur;
+                    t_arr.map_inplace(|t| {
+                        let tau = *t - t_start;
+                        let cycles = f_start * tau + 0.5 * sweep_rate * tau * tau;
+                        *t = (2.0 * PI * cycles + phase).sin() * amplitude + offset
+                    });
+                }
+            }
+            InstrParams::Ramp { start_val, end_val, start_time: t_start, end_time: t_end } => match self.instr_type {
+                InstrType::LINRAMP => {
+                    t_arr.map_inplace(|t| {
+                        *t = (*t - t_start) * (end_val - start_val) / (t_end - t_start) + start_val;
+                    });
+                }
+                InstrType::EXPRAMP => {
+                    let ratio = end_val / start_val;
+                    t_arr.map_inplace(|t| {
+                        *t = start_val * ratio.powf((*t - t_start) / (t_end - t_start));
+                    });
+                }
+                // Smooth ramps: value goes as `start_val + (end_val - start_val) * shape(s)`,
+                // where `s` is the ramp fraction and `shape` goes from 0 to 1
+                InstrType::MINJERKRAMP => {
+                    t_arr.map_inplace(|t| {
+                        let s = ramp_frac(*t, t_start, t_end);
+                        let shape = s * s * s * (10.0 + s * (-15.0 + 6.0 * s));
+                        *t = start_val + (end_val - start_val) * shape;
+                    });
+                }
+                InstrType::COSRAMP => {
+                    t_arr.map_inplace(|t| {
+                        let s = ramp_frac(*t, t_start, t_end);
+                        let shape = 0.5 * (1.0 - (PI * s).cos());
+                        *t = start_val + (end_val - start_val) * shape;
+                    });
+                }
+                InstrType::BLACKMANRAMP => {
+                    t_arr.map_inplace(|t| {
+                        let s = ramp_frac(*t, t_start, t_end);
+                        // Integral of the Blackman window `0.42 - 0.5 cos(2 pi s) + 0.08 cos(4 pi s)`, normalized to 1
+                        let shape = s - (2.0 * PI * s).sin() / (1.68 * PI)
+                            + 0.08 * (4.0 * PI * s).sin() / (1.68 * PI);
+                        *t = start_val + (end_val - start_val) * shape;
+                    });
+                }
+                _ => unreachable!("Ramp parameters for instr type {}", self.instr_type),
+            },
+            InstrParams::ExpRampTau { start_val, target_val, tau, start_time: t_start } => {
+                t_arr.map_inplace(|t| {
+                    *t = target_val + (start_val - target_val) * (-(*t - t_start) / tau).exp();
+                });
+            }
+            InstrParams::LogRamp { start_val, end_val, start_time: t_start, end_time: t_end, tau } => {
+                let norm = ((t_end - t_start) / tau).ln_1p();
+
+                t_arr.map_inplace(|t| {
+                    *t = start_val + (end_val - start_val) * ((*t - t_start) / tau).ln_1p() / norm;
+                });
+            }
+            InstrParams::PowRamp { start_val, end_val, start_time: t_start, end_time: t_end, exponent } => {
+                t_arr.map_inplace(|t| {
+                    // Clamp at 0 so that samples before `start_time` don't produce NaN for fractional exponents
+                    let frac = ((*t - t_start) / (t_end - t_start)).max(0.0);
+                    *t = start_val + (end_val - start_val) * frac.powf(exponent);
+                });
+            }
+            InstrParams::TanhRamp { start_val, end_val, start_time: t_start, end_time: t_end, steepness } => {
+                let norm = steepness.tanh();
+
+                t_arr.map_inplace(|t| {
+                    let s = ramp_frac(*t, t_start, t_end);
+                    let shape = 0.5 * (1.0 + (steepness * (2.0 * s - 1.0)).tanh() / norm);
+                    *t = start_val + (end_val - start_val) * shape;
+                });
+            }
+            InstrParams::Pulse { start_time: t_start, end_time: t_end, amplitude, offset, freq, phase, sigma, rise_time, fall_time } => {
+                let dur = t_end - t_start;
+
+                t_arr.map_inplace(|t| {
+                    let tau = *t - t_start;
+                    // Outside of the pulse the envelope is 0, so `keep_val` padding (evaluated at `end_time`)
+                    // returns to the offset. The Blackman, cos^2 and flat-top windows vanish there anyway,
+                    // while for the Gaussian this cuts off the truncated tail.
+                    let envelope = if tau < 0.0 || tau >= dur {
+                        0.0
+                    } else {
+                        let s = tau / dur;
+                        match self.instr_type {
+                            InstrType::GAUSSPULSE => {
+                                let x = (tau - 0.5 * dur) / sigma;
+                                (-0.5 * x * x).exp()
+                            }
+                            InstrType::BLACKMANPULSE => {
+                                0.42 - 0.5 * (2.0 * PI * s).cos() + 0.08 * (4.0 * PI * s).cos()
+                            }
+                            InstrType::COS2PULSE => (PI * s).sin().powi(2),
+                            _ => {
+                                if tau < rise_time {
+                                    (0.5 * PI * tau / rise_time).sin().powi(2)
+                                } else if dur - tau < fall_time {
+                                    (0.5 * PI * (dur - tau) / fall_time).sin().powi(2)
+                                } else {
+                                    1.0
+                                }
+                            }
+                        }
+                    };
+                    let carrier = match freq {
+                        // Same phase convention as `SINE`, so carriers stay coherent with sine instructions
+                        Some(freq) => (2.0 * PI * freq * (*t) + phase).sin(),
+                        None => 1.0,
+                    };
+                    *t = amplitude * envelope * carrier + offset;
+                });
+            }
+            InstrParams::PulseTrain { start_time: t_start, period, duty, phase, count } => {
+                t_arr.map_inplace(|t| {
+                    // Position in units of periods. Sample times land exactly on edges all the time,
+                    // so nudge by a tiny fraction of a period to keep rounding from moving an edge by one sample.
+                    let cycles = (*t - t_start) / period + phase + 1e-9;
+                    let cycle = cycles.floor();
+                    let in_train = cycle >= 0.0 && count.is_none_or(|count| cycle < count as f64);
+                    *t = if in_train && cycles - cycle < duty { 1.0 } else { 0.0 };
+                });
+            }
+            InstrParams::Noise { amplitude, offset, seed, gaussian, bandwidth, samp_rate } => {
+                t_arr.map_inplace(|t| {
+                    // Everything is derived from the integer sample index, so the value of a sample doesn't depend
+                    // on which chunk (or preview) it is computed in
+                    let samp_idx = (*t * samp_rate).round() as i64;
+                    let val = match bandwidth {
+                        None => noise_value(seed, samp_idx, gaussian),
+                        Some(bandwidth) => {
+                            // Independent nodes at the Nyquist rate of `bandwidth`, Catmull-Rom interpolated in between
+                            let x = samp_idx as f64 * 2.0 * bandwidth / samp_rate;
+                            let node = x.floor() as i64;
+                            let nodes: [f64; 4] =
+                                std::array::from_fn(|k| noise_value(seed, node - 1 + k as i64, gaussian));
+                            Interp::Cubic.interpolate(&nodes, 1.0 + x - node as f64)
+                        }
+                    };
+                    *t = amplitude * val + offset;
+                });
+            }
+            InstrParams::Sampled { start_time: t_start, samp_rate } => {
+                let (samples, interp) = match self.data.as_deref() {
+                    Some(InstrData::Samples { samples, interp }) => (samples, interp),
+                    _ => unreachable!("ARB instruction without sample data"),
+                };
+
+                t_arr.map_inplace(|t| {
+                    *t = interp.interpolate(samples, (*t - t_start) * samp_rate);
+                });
+            }
+            InstrParams::Breakpoints { start_time: t_start } => {
+                let (times, values, moments) = match self.data.as_deref() {
+                    Some(InstrData::Breakpoints { times, values, moments }) => (times, values, moments),
+                    _ => unreachable!("{} instruction without breakpoint data", self.instr_type),
+                };
+                let (first, last) = (times[0], times[times.len() - 1]);
+
+                if self.instr_type == InstrType::SPLINE {
+                    t_arr.map_inplace(|t| {
+                        let x = (*t - t_start).max(first).min(last);
+                        let i = breakpoint_interval(times, x);
+                        let h = times[i + 1] - times[i];
+                        let a = (times[i + 1] - x) / h;
+                        let b = (x - times[i]) / h;
+                        *t = a * values[i]
+                            + b * values[i + 1]
+                            + ((a * a * a - a) * moments[i] + (b * b * b - b) * moments[i + 1]) * h * h / 6.0;
+                    });
+                } else {
+                    t_arr.map_inplace(|t| {
+                        // Hold the edge values outside of the breakpoint range
+                        let x = (*t - t_start).max(first).min(last);
+                        let i = breakpoint_interval(times, x);
+                        let frac = (x - times[i]) / (times[i + 1] - times[i]);
+                        *t = values[i] + frac * (values[i + 1] - values[i]);
+                    });
+                }
+            }
+            InstrParams::Poly { start_time: t_start } => {
+                let coefs = match self.data.as_deref() {
+                    Some(InstrData::Coefficients(coefs)) => coefs,
+                    _ => unreachable!("POLY instruction without coefficients"),
+                };
+
+                // Horner's rule, highest order first
+                t_arr.map_inplace(|t| {
+                    let tau = *t - t_start;
+                    *t = coefs.iter().rev().fold(0.0, |acc, &coef| acc * tau + coef);
+                });
+            }
+            InstrParams::Fourier { freq, offset } => {
+                let (sin_coefs, cos_coefs) = match self.data.as_deref() {
+                    Some(InstrData::Harmonics { sin_coefs, cos_coefs, .. }) => (sin_coefs, cos_coefs),
+                    _ => unreachable!("FOURIER instruction without harmonics"),
+                };
+
+                t_arr.map_inplace(|t| {
+                    // Only the fundamental calls sin/cos, higher harmonics follow from the angle-addition recurrence
+                    // sin((n+1) theta) = sin(n theta) cos(theta) + cos(n theta) sin(theta), same for cos
+                    let (sin_1, cos_1) = (2.0 * PI * freq * (*t)).sin_cos();
+                    let (mut sin_n, mut cos_n) = (sin_1, cos_1);
+                    let mut val = offset;
+                    for (&sin_coef, &cos_coef) in sin_coefs.iter().zip(cos_coefs.iter()) {
+                        val += sin_coef * sin_n + cos_coef * cos_n;
+                        (sin_n, cos_n) = (sin_n * cos_1 + cos_n * sin_1, cos_n * cos_1 - sin_n * sin_1);
+                    }
+                    *t = val;
+                });
+            }
+            InstrParams::Named(_) => match self.data.as_deref() {
+                Some(InstrData::Expr(expr)) => expr.eval_inplace(t_arr),
+                Some(InstrData::Custom(waveform)) => waveform.eval_inplace(t_arr),
+                _ => unreachable!("{} instruction without expression or waveform", self.instr_type),
+            },
+            // Composite instructions evaluate their children over the whole array at once.
+            // `SUM` and `PRODUCT` keep a copy of the time array and reuse one scratch buffer for all children.
+            InstrParams::Empty => {
+                let children = self.children();
+                let times = t_arr.to_owned();
+                children[0].eval_inplace(t_arr);
+                if children.len() > 1 {
+                    let mut scratch = times.clone();
+                    for child in &children[1..] {
+                        scratch.assign(&times);
+                        child.eval_inplace(&mut scratch.view_mut());
+                        if self.instr_type == InstrType::SUM {
+                            *t_arr += &scratch;
+                        } else {
+                            *t_arr *= &scratch;
+                        }
+                    }
+                }
+            }
+            InstrParams::Scale { factor } => {
+                self.children()[0].eval_inplace(t_arr);
+                *t_arr *= factor;
+            }
+            InstrParams::Offset { offset } => {
+                self.children()[0].eval_inplace(t_arr);
+                *t_arr += offset;
+            }
+            InstrParams::Clip { min, max } => {
+                self.children()[0].eval_inplace(t_arr);
+                t_arr.map_inplace(|val| *val = val.max(min).min(max));
+            }
+            InstrParams::TimeShift { dt } => {
+                *t_arr -= dt;
+                self.children()[0].eval_inplace(t_arr);
+            }
+        }
+    }
+
+    /// Child instructions of a composite instruction
+    fn children(&self) -> &[Instruction] {
+        match self.data.as_deref() {
+            Some(InstrData::Children(children)) => children,
+            _ => unreachable!("Composite instruction {} without children", self.instr_type),
+        }
+    }
+
+    /// Evaluate function at a single time point
+    pub fn eval_point(&self, t: f64) -> f64 {
+        let mut t_arr = array![t];
+        self.eval_inplace(&mut t_arr.view_mut());
+        t_arr[0]
+    }
+
+    /// Minimum and maximum value over the time interval `[t_start, t_end]`, computed without sampling.
+    ///
+    /// The bounds are exact for `CONST`, `LINRAMP` and `Absolute` `SINE`, and for all other ramps,
+    /// `PWL` and `ARB` without cubic interpolation. Everything else gets bounds that contain every value
+    /// the instruction can take in the interval, but may be wider:
+    /// - pulses, chirps and `FOURIER` use the offset plus or minus the total amplitude,
+    ///   and `PULSETRAIN` returns `(0, 1)`
+    /// - `SPLINE`, cubic `ARB` and band-limited `NOISE` account for the largest possible interpolation overshoot,
+    ///   `POLY` is bounded by interval arithmetic
+    /// - Gaussian `NOISE`, `EXPR` and `CUSTOM` instructions are unbounded
+    /// - composite instructions combine the bounds of their children
+    ///
+    /// Instructions starting from the current channel value are unbounded until they are resolved,
+    /// see [`Instruction::with_from_current`].
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let sine = Instruction::new_sine(1.0, Some(2.0), None, Some(1.0));
+    /// // Crest at t = 0.25, but no trough before t = 0.75
+    /// let (min, max) = sine.bounds(0.0, 0.5);
+    /// assert!((min - 1.0).abs() < 1e-12 && (max - 3.0).abs() < 1e-12);
+    ///
+    /// let ramp = Instruction::new_linramp(0.0, 10.0, 0.0, 1.0);
+    /// assert_eq!(Instruction::new_clip(ramp, None, Some(4.0)).bounds(0.2, 0.8), (2.0, 4.0));
+    /// ```
+    pub fn bounds(&self, t_start: f64, t_end: f64) -> (f64, f64) {
+        assert!(t_start <= t_end, "Expected t_start <= t_end, got {} and {}", t_start, t_end);
+        if self.from_current {
+            return (f64::NEG_INFINITY, f64::INFINITY);
+        }
+        let endpoints = || min_max([self.eval_point(t_start), self.eval_point(t_end)]);
+
+        match self.params {
+            InstrParams::Const { value } => (value, value),
+            // Every ramp is monotonic, so the extremes sit at the ends of the interval
+            InstrParams::Ramp { .. }
+            | InstrParams::ExpRampTau { .. }
+            | InstrParams::LogRamp { .. }
+            | InstrParams::PowRamp { .. }
+            | InstrParams::TanhRamp { .. } => endpoints(),
+            InstrParams::Sine { freq, amplitude, offset, phase } => {
+                let full = (offset - amplitude.abs(), offset + amplitude.abs());
+                // The phase of an unresolved `Local` or `Continuous` sine depends on where it is placed
+                if self.time_ref != TimeRef::Absolute || (freq * (t_end - t_start)).abs() >= 1.0 {
+                    return full;
+                }
+                // Crests of `sin` sit at phase pi/2 + 2 pi k, troughs at 3 pi/2 + 2 pi k
+                let (theta_0, theta_1) = min_max([2.0 * PI * freq * t_start + phase, 2.0 * PI * freq * t_end + phase]);
+                let contains = |extremum: f64| {
+                    let k = ((theta_0 - extremum) / (2.0 * PI)).ceil();
+                    extremum + 2.0 * PI * k <= theta_1
+                };
+                let (mut sin_min, mut sin_max) = min_max([theta_0.sin(), theta_1.sin()]);
+                if contains(0.5 * PI) {
+                    sin_max = 1.0;
+                }
+                if contains(1.5 * PI) {
+                    sin_min = -1.0;
+                }
+                min_max([offset + amplitude * sin_min, offset + amplitude * sin_max])
+            }
+            InstrParams::Chirp { amplitude, offset, .. } => (offset - amplitude.abs(), offset + amplitude.abs()),
+            // The envelope stays within [0, 1]
+            InstrParams::Pulse { amplitude, offset, freq: None, .. } => min_max([offset, offset + amplitude]),
+            InstrParams::Pulse { amplitude, offset, .. } => (offset - amplitude.abs(), offset + amplitude.abs()),
+            InstrParams::PulseTrain { .. } => (0.0, 1.0),
+            InstrParams::Noise { amplitude, offset, gaussian, bandwidth, .. } => {
+                if gaussian {
+                    return (f64::NEG_INFINITY, f64::INFINITY);
+                }
+                // Catmull-Rom interpolation overshoots the node range by at most 1/8 of it on either side
+                let spread = if bandwidth.is_some() { 1.25 } else { 1.0 } * amplitude.abs();
+                (offset - spread, offset + spread)
+            }
+            InstrParams::Sampled { start_time, samp_rate } => {
+                let (samples, interp) = match self.data.as_deref() {
+                    Some(InstrData::Samples { samples, interp }) => (samples, interp),
+                    _ => unreachable!("ARB instruction without sample data"),
+                };
+                let last = samples.len() - 1;
+                let index = |t: f64| ((t - start_time) * samp_rate).max(0.0).min(last as f64);
+                let (x_0, x_1) = (index(t_start), index(t_end));
+                match interp {
+                    Interp::Nearest => min_max(samples[x_0.round() as usize..=x_1.round() as usize].iter().copied()),
+                    Interp::Linear => {
+                        let (min, max) = min_max(samples[x_0.ceil() as usize..=x_1.floor() as usize].iter().copied());
+                        let (end_min, end_max) = endpoints();
+                        (min.min(end_min), max.max(end_max))
+                    }
+                    Interp::Cubic => {
+                        // Every value is interpolated from the samples neighbouring the interval
+                        let first = (x_0.floor() as usize).saturating_sub(1);
+                        let end = (x_1.floor() as usize + 2).min(last);
+                        let (min, max) = min_max(samples[first..=end].iter().copied());
+                        (min - 0.125 * (max - min), max + 0.125 * (max - min))
+                    }
+                }
+            }
+            InstrParams::Breakpoints { start_time } => {
+                let (times, values, moments) = match self.data.as_deref() {
+                    Some(InstrData::Breakpoints { times, values, moments }) => (times, values, moments),
+                    _ => unreachable!("{} instruction without breakpoint data", self.instr_type),
+                };
+                let (x_0, x_1) = (t_start - start_time, t_end - start_time);
+                let (end_min, end_max) = endpoints();
+                let (min, max) = if self.instr_type == InstrType::SPLINE {
+                    // Each spline segment lies within the convex hull of its Bezier control points
+                    let (first, last) = (breakpoint_interval(times, x_0), breakpoint_interval(times, x_1));
+                    min_max((first..=last).flat_map(|i| {
+                        let h = times[i + 1] - times[i];
+                        let slope = (values[i + 1] - values[i]) / h;
+                        let d_0 = slope - h * (2.0 * moments[i] + moments[i + 1]) / 6.0;
+                        let d_1 = slope + h * (moments[i] + 2.0 * moments[i + 1]) / 6.0;
+                        [values[i], values[i] + h * d_0 / 3.0, values[i + 1] - h * d_1 / 3.0, values[i + 1]]
+                    }))
+                } else {
+                    min_max(times.iter().zip(values.iter())
+                        .filter(|(&time, _)| x_0 < time && time < x_1)
+                        .map(|(_, &value)| value))
+                };
+                (min.min(end_min), max.max(end_max))
+            }
+            InstrParams::Poly { start_time } => {
+                let coefs = match self.data.as_deref() {
+                    Some(InstrData::Coefficients(coefs)) => coefs,
+                    _ => unreachable!("POLY instruction without coefficients"),
+                };
+                // Horner's rule in interval arithmetic
+                let tau = (t_start - start_time, t_end - start_time);
+                coefs.iter().rev().fold((0.0, 0.0), |acc, &coef| {
+                    let (min, max) = product_bounds(acc, tau);
+                    (min + coef, max + coef)
+                })
+            }
+            InstrParams::Fourier { offset, .. } => {
+                let total = match self.data.as_deref() {
+                    Some(InstrData::Harmonics { amplitudes, .. }) => amplitudes.iter().map(|amp| amp.abs()).sum::<f64>(),
+                    _ => unreachable!("FOURIER instruction without harmonics"),
+                };
+                (offset - total, offset + total)
+            }
+            InstrParams::Named(_) => (f64::NEG_INFINITY, f64::INFINITY),
+            InstrParams::Empty => {
+                let mut child_bounds = self.children().iter().map(|child| child.bounds(t_start, t_end));
+                let first = child_bounds.next().unwrap();
+                child_bounds.fold(first, |acc, bounds| {
+                    if self.instr_type == InstrType::SUM {
+                        (acc.0 + bounds.0, acc.1 + bounds.1)
+                    } else {
+                        product_bounds(acc, bounds)
+                    }
+                })
+            }
+            InstrParams::Scale { factor } => product_bounds(self.children()[0].bounds(t_start, t_end), (factor, factor)),
+            InstrParams::Offset { offset } => {
+                let (min, max) = self.children()[0].bounds(t_start, t_end);
+                (min + offset, max + offset)
+            }
+            InstrParams::Clip { min, max } => {
+                let (child_min, child_max) = self.children()[0].bounds(t_start, t_end);
+                (child_min.max(min).min(max), child_max.max(min).min(max))
+            }
+            InstrParams::TimeShift { dt } => self.children()[0].bounds(t_start - dt, t_end - dt),
+        }
+    }
+
+    /// Upper bound of the slope magnitude `|dv/dt|` over `[t_start, t_end]`, computed without sampling.
+    ///
+    /// Exact for `CONST`, `LINRAMP`, `SINE` and `PWL`. The other ramps, `CHIRP`, `FOURIER` and composite instructions
+    /// get a bound that may be larger than the actual slope. Returns `None` where there is no simple analytic bound
+    /// (pulses, noise, sampled waveforms, `POLY`, `EXPR`, ...), so callers have to look at the samples instead.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let ramp = Instruction::new_linramp(0.0, 10.0, 0.0, 2.0);
+    /// assert_eq!(ramp.slope_bound(0.0, 2.0), Some(5.0));
+    /// let sine = Instruction::new_sine(10.0, Some(0.5), None, None);
+    /// assert_eq!(sine.slope_bound(0.0, 1.0), Some(10.0 * std::f64::consts::PI));
+    /// ```
+    pub fn slope_bound(&self, t_start: f64, t_end: f64) -> Option<f64> {
+        if self.from_current {
+            return None;
+        }
+        let slope = match self.params {
+            InstrParams::Const { .. } => 0.0,
+            InstrParams::Sine { freq, amplitude, .. } => 2.0 * PI * (freq * amplitude).abs(),
+            InstrParams::Chirp { f_start, f_end, amplitude, .. } => 2.0 * PI * f_start.abs().max(f_end.abs()) * amplitude.abs(),
+            InstrParams::Ramp { start_val, end_val, start_time, end_time } => {
+                let avg_slope = ((end_val - start_val) / (end_time - start_time)).abs();
+                // Peak of the shape derivative relative to a straight line
+                let peak = match self.instr_type {
+                    InstrType::LINRAMP => 1.0,
+                    InstrType::EXPRAMP => {
+                        (end_val / start_val).ln().abs() * start_val.abs().max(end_val.abs()) / (end_val - start_val).abs()
+                    }
+                    InstrType::MINJERKRAMP => 1.875,
+                    InstrType::COSRAMP => 0.5 * PI,
+                    InstrType::BLACKMANRAMP => 1.0 / 0.42,
+                    _ => unreachable!("Ramp parameters for instr type {}", self.instr_type),
+                };
+                if start_val == end_val { 0.0 } else { avg_slope * peak }
+            }
+            InstrParams::ExpRampTau { start_val, target_val, tau, .. } => (start_val - target_val).abs() / tau,
+            // Steepest at `start_time`
+            InstrParams::LogRamp { start_val, end_val, start_time, end_time, tau } => {
+                (end_val - start_val).abs() / (tau * ((end_time - start_time) / tau).ln_1p())
+            }
+            // Steepest at `end_time`, fractional exponents have an infinite slope at `start_time`
+            InstrParams::PowRamp { start_val, end_val, start_time, end_time, exponent } if exponent >= 1.0 => {
+                ((end_val - start_val) / (end_time - start_time)).abs() * exponent
+            }
+            InstrParams::TanhRamp { start_val, end_val, start_time, end_time, steepness } => {
+                ((end_val - start_val) / (end_time - start_time)).abs() * steepness / steepness.tanh()
+            }
+            InstrParams::Breakpoints { .. } if self.instr_type == InstrType::PWL => {
+                let (times, values) = match self.data.as_deref() {
+                    Some(InstrData::Breakpoints { times, values, .. }) => (times, values),
+                    _ => unreachable!("PWL instruction without breakpoint data"),
+                };
+                times.windows(2).zip(values.windows(2))
+                    .map(|(t, v)| ((v[1] - v[0]) / (t[1] - t[0])).abs())
+                    .fold(0.0, f64::max)
+            }
+            InstrParams::Fourier { freq, .. } => match self.data.as_deref() {
+                Some(InstrData::Harmonics { amplitudes, .. }) => amplitudes.iter().enumerate()
+                    .map(|(k, amp)| 2.0 * PI * (k + 1) as f64 * (freq * amp).abs())
+                    .sum(),
+                _ => unreachable!("FOURIER instruction without harmonics"),
+            },
+            InstrParams::Empty => {
+                let children = self.children();
+                let slopes = children.iter().map(|child| child.slope_bound(t_start, t_end)).collect::<Option<Vec<f64>>>()?;
+                if self.instr_type == InstrType::SUM {
+                    slopes.iter().sum()
+                } else {
+                    // Product rule, with each of the other factors bounded by its largest magnitude
+                    let magnitudes: Vec<f64> = children.iter()
+                        .map(|child| {
+                            let (min, max) = child.bounds(t_start, t_end);
+                            min.abs().max(max.abs())
+                        })
+                        .collect();
+                    (0..children.len())
+                        .map(|i| {
+                            let others: f64 = magnitudes.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, m)| m).product();
+                            if slopes[i] == 0.0 { 0.0 } else { slopes[i] * others }
+                        })
+                        .sum()
+                }
+            }
+            InstrParams::Scale { factor } => self.children()[0].slope_bound(t_start, t_end)? * factor.abs(),
+            InstrParams::Offset { .. } | InstrParams::Clip { .. } => self.children()[0].slope_bound(t_start, t_end)?,
+            InstrParams::TimeShift { dt } => self.children()[0].slope_bound(t_start - dt, t_end - dt)?,
+            _ => return None,
+        };
+        Some(slope)
+    }
+
+    /// Wrapper for conveniently creating new constant instructions.
+    /// Example usage equivalent to the constant example above:
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let const_instr = Instruction::new_const(1.0);
+    /// ```
+    pub fn new_const(value: f64) -> Instruction {
+        Instruction::try_new_const(value).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_const`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_const(value: f64) -> Result<Instruction, InstrError> {
+        Instruction::try_new_with_params(InstrType::CONST, InstrParams::Const { value }, None)
+    }
+
+    /// Wrapper for conveniently creating new linear ramp instructions. 
+    /// `start_val` will be the value on the first tick, and `end_val` value on the last tick. 
+    pub fn new_linramp(start_val: f64, end_val:f64, start_time: f64, end_time: f64) -> Instruction {
+        Instruction::try_new_linramp(start_val, end_val, start_time, end_time).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_linramp`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_linramp(start_val: f64, end_val:f64, start_time: f64, end_time: f64) -> Result<Instruction, InstrError> {
+        let params = InstrParams::Ramp { start_val, end_val, start_time, end_time };
+        Instruction::try_new_with_params(InstrType::LINRAMP, params, None)
+    }
+
+    /// Wrapper for creating hyperbolic tangent ramps.
+    ///
+    /// The shape is `tanh(steepness * (2s - 1))` rescaled to exactly hit `start_val` at `start_time`
+    /// and `end_val` at `end_time` (`s` is the ramp fraction). Larger `steepness` concentrates the change
+    /// around the ramp center and flattens the ends. If `None`, the default `4.0` is used.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let instr = Instruction::new_tanhramp(0.0, 1.0, 0.0, 1.0, None);
+    /// assert!((instr.eval_point(0.5) - 0.5).abs() < 1e-12);
+    /// ```
+    pub fn new_tanhramp(
+        start_val: f64,
+        end_val: f64,
+        start_time: f64,
+        end_time: f64,
+        steepness: Option<f64>,
+    ) -> Instruction {
+        Instruction::try_new_tanhramp(start_val, end_val, start_time, end_time, steepness).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_tanhramp`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_tanhramp(
+        start_val: f64,
+        end_val: f64,
+        start_time: f64,
+        end_time: f64,
+        steepness: Option<f64>,
+    ) -> Result<Instruction, InstrError> {
+        let steepness = steepness.unwrap_or(4.0);
+        let params = InstrParams::TanhRamp { start_val, end_val, start_time, end_time, steepness };
+        Instruction::try_new_with_params(InstrType::TANHRAMP, params, None)
+    }
+
+    /// Wrapper for creating smooth ramps with zero first and second derivatives at both ends.
+    ///
+    /// - `MINJERKRAMP`: minimum-jerk profile `10s^3 - 15s^4 + 6s^5`;
+    /// - `COSRAMP`: raised cosine `(1 - cos(pi s)) / 2` (zero first derivative only);
+    /// - `BLACKMANRAMP`: normalized integral of the Blackman window.
+    ///
+    /// `s` is the fraction of the ramp passed. Any other `instr_type` panics.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let instr = Instruction::new_smooth_ramp(InstrType::MINJERKRAMP, 1.0, 3.0, 0.0, 1.0);
+    /// assert_eq!(instr.eval_point(0.5), 2.0);
+    /// ```
+    pub fn new_smooth_ramp(
+        instr_type: InstrType,
+        start_val: f64,
+        end_val: f64,
+        start_time: f64,
+        end_time: f64,
+    ) -> Instruction {
+        Instruction::try_new_smooth_ramp(instr_type, start_val, end_val, start_time, end_time)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_smooth_ramp`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_smooth_ramp(
+        instr_type: InstrType,
+        start_val: f64,
+        end_val: f64,
+        start_time: f64,
+        end_time: f64,
+    ) -> Result<Instruction, InstrError> {
+        if !matches!(
+            instr_type,
+            InstrType::MINJERKRAMP | InstrType::COSRAMP | InstrType::BLACKMANRAMP
+        ) {
+            return Err(InstrError::InvalidValue {
+                instr_type: instr_type.to_string(),
+                msg: format!("Instr type {} is not a smooth ramp", instr_type),
+            });
+        }
+        let params = InstrParams::Ramp { start_val, end_val, start_time, end_time };
+        Instruction::try_new_with_params(instr_type, params, None)
+    }
+
+    /// Wrapper for creating exponential ramps with fixed start and end values.
+    ///
+    /// The value changes geometrically, `start_val * (end_val / start_val)^((t - start_time) / (end_time - start_time))`,
+    /// so `start_val` and `end_val` must be non-zero and have the same sign.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let instr = Instruction::new_expramp(1.0, 100.0, 0.0, 2.0);
+    /// assert!((instr.eval_point(1.0) - 10.0).abs() < 1e-12);
+    /// ```
+    pub fn new_expramp(start_val: f64, end_val: f64, start_time: f64, end_time: f64) -> Instruction {
+        Instruction::try_new_expramp(start_val, end_val, start_time, end_time).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_expramp`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_expramp(start_val: f64, end_val: f64, start_time: f64, end_time: f64) -> Result<Instruction, InstrError> {
+        let params = InstrParams::Ramp { start_val, end_val, start_time, end_time };
+        Instruction::try_new_with_params(InstrType::EXPRAMP, params, None)
+    }
+
+    /// Wrapper for creating exponential ramps with a fixed time constant.
+    ///
+    /// The value relaxes from `start_val` towards `target_val` as `exp(-(t - start_time) / tau)`.
+    /// `target_val` is only reached asymptotically, the value at the instruction end depends on its duration.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let instr = Instruction::new_expramp_tau(1.0, 0.0, 0.5, 0.0);
+    /// assert!((instr.eval_point(0.5) - (-1.0_f64).exp()).abs() < 1e-12);
+    /// ```
+    pub fn new_expramp_tau(start_val: f64, target_val: f64, tau: f64, start_time: f64) -> Instruction {
+        Instruction::try_new_expramp_tau(start_val, target_val, tau, start_time).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_expramp_tau`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_expramp_tau(start_val: f64, target_val: f64, tau: f64, start_time: f64) -> Result<Instruction, InstrError> {
+        let params = InstrParams::ExpRampTau { start_val, target_val, tau, start_time };
+        Instruction::try_new_with_params(InstrType::EXPRAMP, params, None)
+    }
+
+    /// Wrapper for creating logarithmic ramps.
+    ///
+    /// The value follows `ln(1 + (t - start_time) / tau)`, rescaled to go from `start_val` at `start_time`
+    /// to `end_val` at `end_time`. Smaller `tau` gives a steeper start and a flatter end.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let instr = Instruction::new_logramp(0.0, 1.0, 0.0, 1.0, 0.1);
+    /// assert!((instr.eval_point(1.0) - 1.0).abs() < 1e-12);
+    /// assert!(instr.eval_point(0.5) > 0.5);
+    /// ```
+    pub fn new_logramp(start_val: f64, end_val: f64, start_time: f64, end_time: f64, tau: f64) -> Instruction {
+        Instruction::try_new_logramp(start_val, end_val, start_time, end_time, tau).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_logramp`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_logramp(start_val: f64, end_val: f64, start_time: f64, end_time: f64, tau: f64) -> Result<Instruction, InstrError> {
+        let params = InstrParams::LogRamp { start_val, end_val, start_time, end_time, tau };
+        Instruction::try_new_with_params(InstrType::LOGRAMP, params, None)
+    }
+
+    /// Wrapper for creating power-law ramps `start_val + (end_val - start_val) * s^exponent`,
+    /// where `s` goes from 0 at `start_time` to 1 at `end_time`.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let instr = Instruction::new_powramp(0.0, 4.0, 0.0, 2.0, 2.0);
+    /// assert_eq!(instr.eval_point(1.0), 1.0);
+    /// ```
+    pub fn new_powramp(start_val: f64, end_val: f64, start_time: f64, end_time: f64, exponent: f64) -> Instruction {
+        Instruction::try_new_powramp(start_val, end_val, start_time, end_time, exponent).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_powramp`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_powramp(start_val: f64, end_val: f64, start_time: f64, end_time: f64, exponent: f64) -> Result<Instruction, InstrError> {
+        let params = InstrParams::PowRamp { start_val, end_val, start_time, end_time, exponent };
+        Instruction::try_new_with_params(InstrType::POWRAMP, params, None)
+    }
+
+    /// Constructs a new sine instruction with provided parameters.
+    ///
+    /// Allows for convenient creation of sine instructions by specifying the frequency and optionally, amplitude, phase, and DC offset. Unspecified parameters take their default values.
+    ///
+    /// # Arguments
+    ///
+    /// - `freq`: The frequency of the sine wave.
+    /// - `amplitude`: Optional amplitude of the sine wave. If `None`, defaults to `1.0`.
+    /// - `phase`: Optional phase offset of the sine wave in radians. If `None`, defaults to `0.0`.
+    /// - `dc_offset`: Optional DC offset for the sine wave. If `None`, defaults to `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// Constructing a sine instruction with a specified frequency, and DC offset. Amplitude and phase will use any default values defined elsewhere:
+    ///
+    /// ---
+    /// # use nicompiler_backend::instruction::*;
+    ///
+    /// let sine_instr = Instruction::new_sine(10.0, None, None, Some(1.0));
+    /// ---
+    ///
+    pub fn new_sine(
+        freq: f64,
+        amplitude: Option<f64>,
+        phase: Option<f64>,
+        dc_offset: Option<f64>,
+    ) -> Instruction {
+        Instruction::try_new_sine(freq, amplitude, phase, dc_offset).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_sine`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_sine(
+        freq: f64,
+        amplitude: Option<f64>,
+        phase: Option<f64>,
+        dc_offset: Option<f64>,
+    ) -> Result<Instruction, InstrError> {
+        let params = InstrParams::Sine {
+            freq,
+            amplitude: amplitude.unwrap_or(1.0),
+            offset: dc_offset.unwrap_or(0.0),
+            phase: phase.unwrap_or(0.0),
+        };
+        Instruction::try_new_with_params(InstrType::SINE, params, None)
+    }
+
+    /// Constructs a new frequency chirp instruction.
+    ///
+    /// The instantaneous frequency is swept from `f_start` at `start_time` to `f_end` at `end_time`,
+    /// either linearly or exponentially (constant relative sweep rate). The phase is computed analytically
+    /// as the integral of the frequency, so the waveform is continuous regardless of how it is chunked.
+    ///
+    /// # Arguments
+    ///
+    /// - `f_start`, `f_end`: Start and end frequencies. For an exponential sweep, both must be positive.
+    /// - `start_time`, `end_time`: Absolute times of the sweep start and end.
+    /// - `exponential`: Whether to sweep exponentially instead of linearly.
+    /// - `amplitude`, `phase`, `dc_offset`: Same as for [`Instruction::new_sine`], with `phase` being the phase at `start_time`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// // 1 kHz -> 3 kHz linear sweep over 1 ms: the phase advances by 2 cycles
+    /// let chirp = Instruction::new_chirp(1e3, 3e3, 0.0, 1e-3, false, None, Some(0.5), None);
+    /// assert!((chirp.eval_point(1e-3) - 0.5_f64.sin()).abs() < 1e-9);
+    /// ```
+    pub fn new_chirp(
+        f_start: f64,
+        f_end: f64,
+        start_time: f64,
+        end_time: f64,
+        exponential: bool,
+        amplitude: Option<f64>,
+        phase: Option<f64>,
+        dc_offset: Option<f64>,
+    ) -> Instruction {
+        Instruction::try_new_chirp(f_start, f_end, start_time, end_time, exponential, amplitude, phase, dc_offset).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_chirp`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_chirp(
+        f_start: f64,
+        f_end: f64,
+        start_time: f64,
+        end_time: f64,
+        exponential: bool,
+        amplitude: Option<f64>,
+        phase: Option<f64>,
+        dc_offset: Option<f64>,
+    ) -> Result<Instruction, InstrError> {
+        let params = InstrParams::Chirp {
+            f_start,
+            f_end,
+            start_time,
+            end_time,
+            exponential,
+            amplitude: amplitude.unwrap_or(1.0),
+            offset: dc_offset.unwrap_or(0.0),
+            phase: phase.unwrap_or(0.0),
+        };
+        Instruction::try_new_with_params(InstrType::CHIRP, params, None)
+    }
+
+    /// Constructs a new windowed pulse instruction.
+    ///
+    /// The pulse is `amplitude * window(t) * carrier(t) + dc_offset`, where the carrier is
+    /// `sin(2 pi freq t + phase)` if `freq` is specified and `1` (a DC level) otherwise.
+    /// Supported windows (`instr_type`):
+    /// - `GAUSSPULSE`: Gaussian centered on the pulse with width `sigma` (default: 1/6 of the duration,
+    ///   i.e. truncated at 3 sigma). The truncated tails are cut off at the pulse edges.
+    /// - `BLACKMANPULSE`: Blackman window, vanishing at both edges.
+    /// - `COS2PULSE`: `cos^2` (Hann) window, vanishing at both edges.
+    /// - `FLATTOPPULSE`: flat top with `cos^2` rise and fall over `rise_time` and `fall_time`
+    ///   (default: 1/10 of the duration each).
+    ///
+    /// As for [`Instruction::new_sine`], unspecified optional arguments take their default values.
+    /// Window-specific arguments passed for another window panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// // 10 us Blackman-shaped DC pulse of height 2
+    /// let pulse = Instruction::new_pulse(InstrType::BLACKMANPULSE, 0.0, 10e-6, Some(2.0), None, None, None, None, None, None);
+    /// assert!((pulse.eval_point(5e-6) - 2.0).abs() < 1e-12);
+    /// assert_eq!(pulse.eval_point(10e-6), 0.0);
+    /// ```
+    pub fn new_pulse(
+        instr_type: InstrType,
+        start_time: f64,
+        end_time: f64,
+        amplitude: Option<f64>,
+        freq: Option<f64>,
+        phase: Option<f64>,
+        dc_offset: Option<f64>,
+        sigma: Option<f64>,
+        rise_time: Option<f64>,
+        fall_time: Option<f64>,
+    ) -> Instruction {
+        Instruction::try_new_pulse(
+            instr_type, start_time, end_time, amplitude, freq, phase, dc_offset, sigma, rise_time, fall_time,
+        )
+        .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_pulse`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_pulse(
+        instr_type: InstrType,
+        start_time: f64,
+        end_time: f64,
+        amplitude: Option<f64>,
+        freq: Option<f64>,
+        phase: Option<f64>,
+        dc_offset: Option<f64>,
+        sigma: Option<f64>,
+        rise_time: Option<f64>,
+        fall_time: Option<f64>,
+    ) -> Result<Instruction, InstrError> {
+        let invalid = |msg: String| {
+            Err(InstrError::InvalidValue {
+                instr_type: instr_type.to_string(),
+                msg,
+            })
+        };
+        if !matches!(
+            instr_type,
+            InstrType::GAUSSPULSE | InstrType::BLACKMANPULSE | InstrType::COS2PULSE | InstrType::FLATTOPPULSE
+        ) {
+            return invalid(format!("Instr type {} is not a windowed pulse", instr_type));
+        }
+        if sigma.is_some() && instr_type != InstrType::GAUSSPULSE {
+            return invalid(format!("sigma can only be specified for GAUSSPULSE, got {}", instr_type));
+        }
+        if (rise_time.is_some() || fall_time.is_some()) && instr_type != InstrType::FLATTOPPULSE {
+            return invalid(format!(
+                "rise_time and fall_time can only be specified for FLATTOPPULSE, got {}",
+                instr_type
+            ));
+        }
+        let dur = end_time - start_time;
+        let sigma = sigma.unwrap_or(dur / 6.0);
+        let (rise_time, fall_time) = (rise_time.unwrap_or(dur / 10.0), fall_time.unwrap_or(dur / 10.0));
+
+        let params = InstrParams::Pulse {
+            start_time,
+            end_time,
+            amplitude: amplitude.unwrap_or(1.0),
+            offset: dc_offset.unwrap_or(0.0),
+            freq,
+            phase: phase.unwrap_or(0.0),
+            sigma,
+            rise_time,
+            fall_time,
+        };
+        Instruction::try_new_with_params(instr_type, params, None)
+    }
+
+    /// Constructs a new periodic square-wave instruction switching between 1 and 0, meant for digital lines.
+    ///
+    /// Each period starts with the high part lasting `duty * period`. `phase` (a fraction of the period, in `[0, 1)`)
+    /// advances the train, such that the first rising edge is at `start_time - phase * period`.
+    /// If `count` is given, the output stays low after `count` periods.
+    ///
+    /// # Arguments
+    ///
+    /// - `start_time`: Absolute time the train is counted from.
+    /// - `period`: Period (must be positive).
+    /// - `duty`: Optional duty cycle in `[0, 1]`, default is `0.5`.
+    /// - `phase`: Optional phase as a fraction of the period, default is `0.0`.
+    /// - `count`: Optional number of pulses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// // Three 1 ms pulses every 4 ms, starting at t = 1 s
+    /// let train = Instruction::new_pulse_train(1.0, 4e-3, Some(0.25), None, Some(3));
+    /// assert_eq!(train.eval_point(1.0), 1.0);
+    /// assert_eq!(train.eval_point(1.002), 0.0);
+    /// assert_eq!(train.eval_point(1.008), 1.0);
+    /// assert_eq!(train.eval_point(1.012), 0.0);
+    /// ```
+    pub fn new_pulse_train(
+        start_time: f64,
+        period: f64,
+        duty: Option<f64>,
+        phase: Option<f64>,
+        count: Option<usize>,
+    ) -> Instruction {
+        Instruction::try_new_pulse_train(start_time, period, duty, phase, count).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_pulse_train`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_pulse_train(
+        start_time: f64,
+        period: f64,
+        duty: Option<f64>,
+        phase: Option<f64>,
+        count: Option<usize>,
+    ) -> Result<Instruction, InstrError> {
+        let (duty, phase) = (duty.unwrap_or(0.5), phase.unwrap_or(0.0));
+        let params = InstrParams::PulseTrain {
+            start_time,
+            period,
+            duty,
+            phase,
+            count,
+        };
+        Instruction::try_new_with_params(InstrType::PULSETRAIN, params, None)
+    }
+
+    /// Constructs a new seeded noise instruction `offset + amplitude * noise`.
+    ///
+    /// `noise` is uniform in `[-1, 1)`, or standard normal if `gaussian` is set. Values are a pure function
+    /// of `seed` and the sample index `round(t * samp_rate)`, so a sample gets the same value however the
+    /// sequence is split into chunks for streaming, and the same seed always reproduces the same waveform.
+    ///
+    /// Without `bandwidth`, every sample is independent (white noise up to the Nyquist frequency).
+    /// With `bandwidth`, independent values are drawn at rate `2 * bandwidth` and interpolated with a
+    /// Catmull-Rom cubic (see [`Interp::Cubic`]), which suppresses content above `bandwidth`.
+    ///
+    /// # Arguments
+    ///
+    /// - `samp_rate`: Sample rate of the channel the noise is played on.
+    /// - `amplitude`: Half-width of the uniform distribution, or standard deviation of the Gaussian one.
+    /// - `seed`: Seed of the noise stream, at most [`MAX_INT_ARG`].
+    /// - `gaussian`: Draw from a normal instead of a uniform distribution.
+    /// - `bandwidth`: Optional bandwidth limit (must be positive).
+    /// - `dc_offset`: Optional DC offset, default is `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let noise = Instruction::new_noise(1e6, 0.1, 42, false, None, Some(1.0));
+    /// let val = noise.eval_point(3e-6);
+    /// assert!(val >= 0.9 && val < 1.1);
+    /// // Same seed and sample - same value
+    /// assert_eq!(Instruction::new_noise(1e6, 0.1, 42, false, None, Some(1.0)).eval_point(3e-6), val);
+    /// ```
+    pub fn new_noise(
+        samp_rate: f64,
+        amplitude: f64,
+        seed: u64,
+        gaussian: bool,
+        bandwidth: Option<f64>,
+        dc_offset: Option<f64>,
+    ) -> Instruction {
+        Instruction::try_new_noise(samp_rate, amplitude, seed, gaussian, bandwidth, dc_offset).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_noise`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_noise(
+        samp_rate: f64,
+        amplitude: f64,
+        seed: u64,
+        gaussian: bool,
+        bandwidth: Option<f64>,
+        dc_offset: Option<f64>,
+    ) -> Result<Instruction, InstrError> {
+        let params = InstrParams::Noise {
+            amplitude,
+            offset: dc_offset.unwrap_or(0.0),
+            seed,
+            gaussian,
+            bandwidth,
+            samp_rate,
+        };
+        Instruction::try_new_with_params(InstrType::NOISE, params, None)
+    }
+
+    /// Constructs a new math-expression instruction.
+    ///
+    /// `expr` is a function of the absolute time `t` and the named `params`, see [`crate::expression`]
+    /// for the supported syntax. The expression is parsed and compiled here, so syntax errors and undefined
+    /// variables are returned as `Err` right away rather than surfacing at compile time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let mut params = InstrArgs::new();
+    /// params.insert("A".to_string(), 2.0);
+    /// params.insert("tau".to_string(), 0.5);
+    /// let instr = Instruction::new_expr("A * exp(-t / tau)", params.clone()).unwrap();
+    /// assert_eq!(instr.eval_point(0.0), 2.0);
+    ///
+    /// assert!(Instruction::new_expr("A * exp(-t / tau) + c", params).is_err());
+    /// ```
+    pub fn new_expr(expr: &str, params: InstrArgs) -> Result<Instruction, String> {
+        let compiled = MathExpr::parse(expr, &params)?;
+        Instruction::try_new_with_params(InstrType::EXPR, InstrParams::Named(params), Some(InstrData::Expr(compiled)))
+            .map_err(|err| err.to_string())
+    }
+
+    /// Constructs an instruction of a registered [`Waveform`] kind, see [`crate::waveform::register_waveform`].
+    ///
+    /// Panics if `kind` is not registered or `args` miss a required key.
+    /// Use [`crate::waveform::build_waveform`] with [`Instruction::from_waveform`] to handle these errors instead.
+    pub fn new_custom(kind: &str, args: InstrArgs) -> Instruction {
+        let waveform = build_waveform(kind, &args).unwrap_or_else(|msg| panic!("{}", msg));
+        Instruction::from_waveform(waveform, args)
+    }
+
+    /// Wraps a [`Waveform`] object into a `CUSTOM` instruction.
+    ///
+    /// `args` are only kept for display and equality, the waveform must already hold its parameters.
+    pub fn from_waveform(waveform: Box<dyn Waveform>, args: InstrArgs) -> Instruction {
+        Instruction::new_with_params(
+            InstrType::CUSTOM,
+            InstrParams::Named(args),
+            Some(InstrData::Custom(Arc::from(waveform))),
+        )
+    }
+
+    /// Constructs the sum of several instructions, e.g. a sine riding on a ramp:
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let instr = Instruction::new_sum(vec![
+    ///     Instruction::new_linramp(0.0, 1.0, 0.0, 1.0),
+    ///     Instruction::new_sine(100.0, Some(0.1), None, None),
+    /// ]);
+    /// assert!((instr.eval_point(0.5) - 0.5).abs() < 1e-12);
+    /// ```
+    pub fn new_sum(children: Vec<Instruction>) -> Instruction {
+        Instruction::new_with_params(InstrType::SUM, InstrParams::Empty, Some(InstrData::Children(children)))
+    }
+
+    /// Constructs the product of several instructions, e.g. a carrier modulated by an envelope.
+    pub fn new_product(children: Vec<Instruction>) -> Instruction {
+        Instruction::new_with_params(InstrType::PRODUCT, InstrParams::Empty, Some(InstrData::Children(children)))
+    }
+
+    /// Constructs an instruction evaluating to `factor` times the `child` value.
+    pub fn new_scale(child: Instruction, factor: f64) -> Instruction {
+        let params = InstrParams::Scale { factor };
+        Instruction::new_with_params(InstrType::SCALE, params, Some(InstrData::Children(vec![child])))
+    }
+
+    /// Constructs an instruction evaluating to the `child` value plus `offset`.
+    pub fn new_offset(child: Instruction, offset: f64) -> Instruction {
+        let params = InstrParams::Offset { offset };
+        Instruction::new_with_params(InstrType::OFFSET, params, Some(InstrData::Children(vec![child])))
+    }
+
+    /// Constructs an instruction clipping the `child` value to `[min, max]`.
+    /// If a bound is `None`, the value is not limited from that side.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let clipped = Instruction::new_clip(Instruction::new_sine(1.0, None, None, None), None, Some(0.5));
+    /// assert_eq!(clipped.eval_point(0.25), 0.5);
+    /// assert_eq!(clipped.eval_point(0.75), -1.0);
+    /// ```
+    pub fn new_clip(child: Instruction, min: Option<f64>, max: Option<f64>) -> Instruction {
+        let params = InstrParams::Clip {
+            min: min.unwrap_or(f64::NEG_INFINITY),
+            max: max.unwrap_or(f64::INFINITY),
+        };
+        Instruction::new_with_params(InstrType::CLIP, params, Some(InstrData::Children(vec![child])))
+    }
+
+    /// Constructs an instruction delaying the `child` by `dt`: the value at time `t` is the child value at `t - dt`.
+    ///
+    /// This is useful to place instructions with absolute time arguments (e.g. `LINRAMP`) at a different time.
+    pub fn new_time_shift(child: Instruction, dt: f64) -> Instruction {
+        let params = InstrParams::TimeShift { dt };
+        Instruction::new_with_params(InstrType::TIMESHIFT, params, Some(InstrData::Children(vec![child])))
+    }
+
+    /// Builds an instruction tree from a python dictionary, which is how composite instructions are reached from python.
+    ///
+    /// The dictionary has the keys
+    /// - `"type"`: instruction type name, e.g. `"SUM"` or `"linramp"` (case-insensitive);
+    /// - `"args"`: optional dictionary of arguments, the same as for [`Instruction::new`];
+    /// - `"children"`: list of child dictionaries, required by the composite types.
+    ///
+    /// Time arguments (e.g. the `LINRAMP` start and end times) are absolute. Types carrying other data
+    /// (`ARB`, `PWL`, ...) can't be described this way. Returns a `ValueError` for an invalid tree.
+    pub fn from_py_spec(spec: &PyAny) -> PyResult<Instruction> {
+        let spec: &PyDict = spec.downcast()?;
+        let instr_type: String = spec
+            .get_item("type")
+            .ok_or_else(|| PyValueError::new_err(format!("Instruction spec {} has no \"type\" key", spec)))?
+            .extract()?;
+        let instr_type: InstrType = instr_type.to_uppercase().parse().map_err(PyValueError::new_err)?;
+        let args = match spec.get_item("args") {
+            Some(args) => args
+                .downcast::<PyDict>()?
+                .iter()
+                .map(|(name, value)| Ok((name.extract::<String>()?, value.extract::<f64>()?)))
+                .collect::<PyResult<InstrArgs>>()?,
+            None => InstrArgs::new(),
+        };
+        let data = match spec.get_item("children") {
+            Some(children) => Some(InstrData::Children(
+                children
+                    .extract::<Vec<&PyAny>>()?
+                    .into_iter()
+                    .map(Instruction::from_py_spec)
+                    .collect::<PyResult<Vec<Instruction>>>()?,
+            )),
+            None => None,
+        };
+        Ok(Instruction::try_new_with_data(instr_type, args, data)?)
+    }
+
+    /// Python dictionary describing the instruction tree, in the layout [`Instruction::from_py_spec`] accepts.
+    ///
+    /// Composite instructions list their operands under `"children"`, recursively. Other data
+    /// (`ARB` samples, `PWL` breakpoints, ...) is not included.
+    pub fn to_py_spec<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let spec = PyDict::new(py);
+        spec.set_item("type", self.instr_type.to_string())?;
+        spec.set_item("args", self.args().into_iter().collect::<Vec<_>>().into_py_dict(py))?;
+        if let Some(InstrData::Children(children)) = self.data.as_deref() {
+            let children = children.iter().map(|child| child.to_py_spec(py)).collect::<PyResult<Vec<_>>>()?;
+            spec.set_item("children", children)?;
+        }
+        Ok(spec)
+    }
+
+    /// Constructs a new arbitrary waveform instruction from a sample buffer.
+    ///
+    /// Sample `i` is played at time `start_time + i / samp_rate`. The waveform is resampled onto
+    /// the channel clock grid with the chosen [`Interp`] scheme, so the buffer sample rate doesn't have
+    /// to match the channel one. The natural duration of the waveform is `samples.len() / samp_rate`.
+    ///
+    /// # Arguments
+    ///
+    /// - `samples`: The waveform samples. Must not be empty.
+    /// - `samp_rate`: Sample rate of the buffer (must be positive).
+    /// - `start_time`: Absolute time of the first sample.
+    /// - `interp`: Interpolation scheme.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let arb_instr = Instruction::new_arb(vec![0.0, 1.0, 0.0], 1e3, 0.0, Interp::Linear);
+    /// assert_eq!(arb_instr.eval_point(0.5e-3), 0.5);
+    /// ```
+    pub fn new_arb(samples: Vec<f64>, samp_rate: f64, start_time: f64, interp: Interp) -> Instruction {
+        Instruction::try_new_arb(samples, samp_rate, start_time, interp).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_arb`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_arb(samples: Vec<f64>, samp_rate: f64, start_time: f64, interp: Interp) -> Result<Instruction, InstrError> {
+        Instruction::try_new_with_params(
+            InstrType::ARB,
+            InstrParams::Sampled { start_time, samp_rate },
+            Some(InstrData::Samples { samples, interp }),
+        )
+    }
+
+    /// Constructs a new piecewise-linear instruction through `(time, value)` breakpoints.
+    ///
+    /// Breakpoint times are relative to `start_time` and must be strictly increasing.
+    /// Before the first and after the last breakpoint the edge values are held.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let pwl_instr = Instruction::new_pwl(vec![(0.0, 0.0), (1.0, 2.0), (3.0, -2.0)], 10.0);
+    /// assert_eq!(pwl_instr.eval_point(10.5), 1.0);
+    /// assert_eq!(pwl_instr.eval_point(12.0), 0.0);
+    /// ```
+    pub fn new_pwl(breakpoints: Vec<(f64, f64)>, start_time: f64) -> Instruction {
+        Instruction::try_new_pwl(breakpoints, start_time).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_pwl`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_pwl(breakpoints: Vec<(f64, f64)>, start_time: f64) -> Result<Instruction, InstrError> {
+        let (times, values): (Vec<f64>, Vec<f64>) = breakpoints.into_iter().unzip();
+        Instruction::try_new_with_params(
+            InstrType::PWL,
+            InstrParams::Breakpoints { start_time },
+            Some(InstrData::Breakpoints {
+                times,
+                values,
+                moments: Vec::new(),
+            }),
+        )
+    }
+
+    /// Constructs a new natural cubic spline instruction through `(time, value)` breakpoints.
+    ///
+    /// Same conventions as [`Instruction::new_pwl`]. The spline passes through every breakpoint,
+    /// has continuous first and second derivatives, and zero curvature at both ends.
+    ///
+    /// # Examples
+    ///
     /// ```
     /// # use nicompiler_backend::instruction::*;
-    /// let const_instr = Instruction::new_const(1.0);
+    /// let spline_instr = Instruction::new_spline(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)], 0.0);
+    /// assert_eq!(spline_instr.eval_point(1.0), 1.0);
     /// ```
-    pub fn new_const(value: f64) -> Instruction {
-        let mut args = IndexMap::new();
-        args.insert(String::from("value"), value);
-        Instruction::new(InstrType::CONST, args)
+    pub fn new_spline(breakpoints: Vec<(f64, f64)>, start_time: f64) -> Instruction {
+        Instruction::try_new_spline(breakpoints, start_time).unwrap_or_else(|err| panic!("{}", err))
     }
 
-    /// Wrapper for conveniently creating new linear ramp instructions. 
-    /// `start_val` will be the value on the first tick, and `end_val` value on the last tick. 
-    pub fn new_linramp(start_val: f64, end_val:f64, start_time: f64, end_time: f64) -> Instruction {
-        let mut args = IndexMap::new();
-        args.insert(String::from("start_val"), start_val);
-        args.insert(String::from("end_val"), end_val);
-        args.insert(String::from("start_time"), start_time);
-        args.insert(String::from("end_time"),end_time);
-        Instruction::new(InstrType::LINRAMP, args)
+    /// Same as [`Instruction::new_spline`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_spline(breakpoints: Vec<(f64, f64)>, start_time: f64) -> Result<Instruction, InstrError> {
+        let (times, values): (Vec<f64>, Vec<f64>) = breakpoints.into_iter().unzip();
+        let moments = natural_spline_moments(&times, &values);
+        Instruction::try_new_with_params(
+            InstrType::SPLINE,
+            InstrParams::Breakpoints { start_time },
+            Some(InstrData::Breakpoints {
+                times,
+                values,
+                moments,
+            }),
+        )
     }
 
-    /// Constructs a new sine instruction with provided parameters.
-    ///
-    /// Allows for convenient creation of sine instructions by specifying the frequency and optionally, amplitude, phase, and DC offset. Unspecified parameters will not be included in the instruction's argument dictionary, allowing for default values to be used elsewhere if necessary.
+    /// Constructs a new polynomial instruction `sum_k coefs[k] * (t - start_time)^k`.
     ///
-    /// # Arguments
-    ///
-    /// - `freq`: The frequency of the sine wave.
-    /// - `amplitude`: Optional amplitude of the sine wave. If `None`, it will not be set in the instruction.
-    /// - `phase`: Optional phase offset of the sine wave in radians. If `None`, it will not be set in the instruction.
-    /// - `dc_offset`: Optional DC offset for the sine wave. If `None`, it will not be set in the instruction.
+    /// Coefficients are listed lowest order first and apply to the instruction-local time `t - start_time`,
+    /// so a fitted calibration curve can be placed anywhere. Evaluated with Horner's rule.
     ///
     /// # Examples
     ///
-    /// Constructing a sine instruction with a specified frequency, and DC offset. Amplitude and phase will use any default values defined elsewhere:
-    ///
-    /// ---
+    /// ```
     /// # use nicompiler_backend::instruction::*;
+    /// // 1 + 2 tau + 3 tau^2, starting at t = 1
+    /// let poly_instr = Instruction::new_poly(vec![1.0, 2.0, 3.0], 1.0);
+    /// assert_eq!(poly_instr.eval_point(1.0), 1.0);
+    /// assert_eq!(poly_instr.eval_point(2.0), 6.0);
+    /// ```
+    pub fn new_poly(coefs: Vec<f64>, start_time: f64) -> Instruction {
+        Instruction::try_new_poly(coefs, start_time).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_poly`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_poly(coefs: Vec<f64>, start_time: f64) -> Result<Instruction, InstrError> {
+        Instruction::try_new_with_params(
+            InstrType::POLY,
+            InstrParams::Poly { start_time },
+            Some(InstrData::Coefficients(coefs)),
+        )
+    }
+
+    /// Constructs a new Fourier-series instruction
+    /// `dc_offset + sum_n amplitudes[n-1] * sin(2 pi n freq t + phases[n-1])`, with `n` starting from 1 (the fundamental).
     ///
-    /// let sine_instr = Instruction::new_sine(10.0, None, None, Some(1.0));
-    /// ---
+    /// Same phase convention as [`Instruction::new_sine`] (absolute time). If `phases` is `None`, all phases are 0.
+    /// Only the fundamental calls `sin`/`cos` per sample, the harmonics follow from a recurrence.
     ///
-    pub fn new_sine(
-        freq: f64,
-        amplitude: Option<f64>,
-        phase: Option<f64>,
-        dc_offset: Option<f64>,
-    ) -> Instruction {
-        let mut instr_args = IndexMap::new();
-        instr_args.insert(String::from("freq"), freq);
-        // For each optional argument, if specified, insert into dictionary
-        [
-            ("amplitude", amplitude),
-            ("phase", phase),
-            ("offset", dc_offset),
-        ]
-        .iter()
-        .for_each(|(key, opt_value)| {
-            if let Some(value) = *opt_value {
-                instr_args.insert(key.to_string(), value);
-            }
-        });
-        Instruction::new(InstrType::SINE, instr_args)
+    /// # Examples
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// // Fundamental plus a third harmonic with a quarter of the amplitude
+    /// let fourier_instr = Instruction::new_fourier(10.0, vec![1.0, 0.0, 0.25], None, Some(0.5));
+    /// assert!((fourier_instr.eval_point(0.025) - (0.5 + 1.0 - 0.25)).abs() < 1e-12);
+    /// ```
+    pub fn new_fourier(freq: f64, amplitudes: Vec<f64>, phases: Option<Vec<f64>>, dc_offset: Option<f64>) -> Instruction {
+        Instruction::try_new_fourier(freq, amplitudes, phases, dc_offset).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Instruction::new_fourier`], but returns an [`InstrError`] instead of panicking.
+    pub fn try_new_fourier(freq: f64, amplitudes: Vec<f64>, phases: Option<Vec<f64>>, dc_offset: Option<f64>) -> Result<Instruction, InstrError> {
+        let phases = phases.unwrap_or_else(|| vec![0.0; amplitudes.len()]);
+        let (sin_coefs, cos_coefs) = fourier_coefs(&amplitudes, &phases);
+        Instruction::try_new_with_params(
+            InstrType::FOURIER,
+            InstrParams::Fourier {
+                freq,
+                offset: dc_offset.unwrap_or(0.0),
+            },
+            Some(InstrData::Harmonics {
+                amplitudes,
+                phases,
+                sin_coefs,
+                cos_coefs,
+            }),
+        )
     }
 }
 impl fmt::Display for Instruction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let args_string = self
-            .args
+            .args()
             .iter()
             .map(|(k, v)| format!("{}: {}", k, v))
             .collect::<Vec<String>>()
             .join(", ");
-        write!(f, "[{}, {{{}}}]", self.instr_type, args_string)
+        let mut flags_string = match self.time_ref {
+            TimeRef::Absolute => String::new(),
+            time_ref => format!(", time_ref={}", time_ref),
+        };
+        if self.from_current {
+            flags_string.push_str(", from_current");
+        }
+        match &self.data {
+            Some(data) => write!(f, "[{}, {{{}}}, {}{}]", self.instr_type, args_string, data, flags_string),
+            None => write!(f, "[{}, {{{}}}{}]", self.instr_type, args_string, flags_string),
+        }
     }
 }
 
@@ -313,6 +3086,11 @@ impl fmt::Display for Instruction {
 /// # Ordering
 /// `InstrBook` implements ordering based on `start_pos` to facilitate sorting.
 ///
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "serialize::InstrBookRecord")
+)]
 pub struct InstrBook {
     pub start_pos: usize,
     pub end_spec: Option<(usize, bool)>,
@@ -351,22 +3129,30 @@ impl InstrBook {
     /// ```
     ///
     /// The panic message will be:
-    /// `Instruction { /* ... */ } end_pos 5 should be strictly greater than start_pos 5`.
+    /// `Instruction must satisfy `start_pos + 1 <= end_pos`, got start_pos = 5 and end_pos = 5`.
     pub fn new(start_pos: usize, end_spec: Option<(usize, bool)>, func: Instruction) -> Self {
-        if let Some((end_pos, _keep_val)) = &end_spec {
+        InstrBook::try_new(start_pos, end_spec, func).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`InstrBook::new`], but returns [`InstrError::ZeroLengthInterval`] instead of panicking.
+    ///
+    /// ```
+    /// # use nicompiler_backend::instruction::*;
+    /// let book = InstrBook::try_new(5, Some((5, true)), Instruction::new_const(1.0));
+    /// assert!(matches!(book, Err(InstrError::ZeroLengthInterval { start_pos: 5, end_pos: 5 })));
+    /// ```
+    pub fn try_new(start_pos: usize, end_spec: Option<(usize, bool)>, func: Instruction) -> Result<Self, InstrError> {
+        if let Some((end_pos, _keep_val)) = end_spec {
             // Sanity check - the smallest permissible instruction length is 1 tick
-            assert!(
-                start_pos + 1 <= *end_pos,
-                "Instruction must satisfy `start_pos + 1 <= end_pos` \n\
-                 However, provided instruction has start_pos = {} and end_pos = {}",
-                start_pos, end_pos
-            )
+            if start_pos + 1 > end_pos {
+                return Err(InstrError::ZeroLengthInterval { start_pos, end_pos });
+            }
         }
-        InstrBook {
+        Ok(InstrBook {
             start_pos,
             end_spec,
             instr: func,
-        }
+        })
     }
     /// Returns the value of the `end_pos` field
     pub fn end_pos(&self) -> Option<usize> {
@@ -430,3 +3216,927 @@ impl fmt::Display for InstrBook {
     }
 }
 impl Eq for InstrBook {}
+
+/// Serde support, enabled by the `serde` cargo feature.
+///
+/// [`Instruction`] goes through `InstrRecord` since two kinds of payload can't be stored as they are:
+/// an `EXPR` is stored as its source and a `CUSTOM` waveform as its kind. Both are rebuilt from the instruction
+/// arguments when deserializing, so custom waveforms must be registered by then (python functions can't be restored).
+/// Deserialized instructions go through the same validation as [`Instruction::try_new_with_params`],
+/// and instruction books through [`InstrBook::try_new`].
+#[cfg(feature = "serde")]
+mod serialize {
+    use std::borrow::Cow;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    /// Instruction data without the derived spline moments and Fourier coefficients,
+    /// which are rebuilt from the breakpoints and harmonics on deserialization
+    #[derive(Serialize, Deserialize)]
+    enum DataRecord<'a> {
+        Samples { samples: Cow<'a, [f64]>, interp: Interp },
+        Breakpoints { times: Cow<'a, [f64]>, values: Cow<'a, [f64]> },
+        Coefficients(Cow<'a, [f64]>),
+        Harmonics { amplitudes: Cow<'a, [f64]>, phases: Cow<'a, [f64]> },
+        Children(Cow<'a, [Instruction]>),
+        Expr(Cow<'a, str>),
+        Custom(Cow<'a, str>),
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Instruction")]
+    struct InstrRecord<'a> {
+        instr_type: InstrType,
+        params: Cow<'a, InstrParams>,
+        data: Option<DataRecord<'a>>,
+        time_ref: TimeRef,
+        #[serde(default)]
+        from_current: bool,
+    }
+
+    impl Serialize for Instruction {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let data = self.data.as_deref().map(|data| match data {
+                InstrData::Samples { samples, interp } => DataRecord::Samples { samples: Cow::from(samples), interp: *interp },
+                InstrData::Breakpoints { times, values, .. } => {
+                    DataRecord::Breakpoints { times: Cow::from(times), values: Cow::from(values) }
+                }
+                InstrData::Coefficients(coefs) => DataRecord::Coefficients(Cow::from(coefs)),
+                InstrData::Harmonics { amplitudes, phases, .. } => {
+                    DataRecord::Harmonics { amplitudes: Cow::from(amplitudes), phases: Cow::from(phases) }
+                }
+                InstrData::Children(children) => DataRecord::Children(Cow::from(children)),
+                InstrData::Expr(expr) => DataRecord::Expr(Cow::from(expr.source())),
+                InstrData::Custom(waveform) => DataRecord::Custom(Cow::from(waveform.kind())),
+            });
+            InstrRecord {
+                instr_type: self.instr_type.clone(),
+                params: Cow::Borrowed(&self.params),
+                data,
+                time_ref: self.time_ref,
+                from_current: self.from_current,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Instruction {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let record = InstrRecord::deserialize(deserializer)?;
+            let params = record.params.into_owned();
+            let named_args = || match &params {
+                InstrParams::Named(args) => Ok(args),
+                _ => Err(D::Error::custom(format!("{} instruction requires named arguments", record.instr_type))),
+            };
+            let data = match record.data {
+                None => None,
+                Some(DataRecord::Samples { samples, interp }) => Some(InstrData::Samples { samples: samples.into_owned(), interp }),
+                Some(DataRecord::Breakpoints { times, values }) => {
+                    // The spline system is only solved for breakpoints which pass the checks of `try_new_with_params`
+                    let moments = if record.instr_type == InstrType::SPLINE && check_breakpoints(&times, &values).is_ok() {
+                        natural_spline_moments(&times, &values)
+                    } else {
+                        Vec::new()
+                    };
+                    Some(InstrData::Breakpoints { times: times.into_owned(), values: values.into_owned(), moments })
+                }
+                Some(DataRecord::Coefficients(coefs)) => Some(InstrData::Coefficients(coefs.into_owned())),
+                Some(DataRecord::Harmonics { amplitudes, phases }) => {
+                    let (sin_coefs, cos_coefs) = fourier_coefs(&amplitudes, &phases);
+                    Some(InstrData::Harmonics {
+                        amplitudes: amplitudes.into_owned(),
+                        phases: phases.into_owned(),
+                        sin_coefs,
+                        cos_coefs,
+                    })
+                }
+                Some(DataRecord::Children(children)) => Some(InstrData::Children(children.into_owned())),
+                Some(DataRecord::Expr(source)) => {
+                    Some(InstrData::Expr(MathExpr::parse(&source, named_args()?).map_err(D::Error::custom)?))
+                }
+                Some(DataRecord::Custom(kind)) => {
+                    let waveform = build_waveform(&kind, named_args()?).map_err(D::Error::custom)?;
+                    Some(InstrData::Custom(Arc::from(waveform)))
+                }
+            };
+            let mut instr = Instruction::try_new_with_params(record.instr_type, params, data).map_err(D::Error::custom)?;
+            if record.time_ref != TimeRef::Absolute && instr.carrier().is_none() {
+                return Err(D::Error::custom(format!("Time reference {} requires a periodic instruction, got {}", record.time_ref, instr)));
+            }
+            instr.time_ref = record.time_ref;
+            if record.from_current {
+                if !matches!(instr.params, InstrParams::Ramp { .. } | InstrParams::Sine { .. }) {
+                    return Err(D::Error::custom(format!("Instruction {} can't start from the current value", instr)));
+                }
+                instr.from_current = true;
+            }
+            Ok(instr)
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "InstrBook")]
+    pub struct InstrBookRecord {
+        start_pos: usize,
+        end_spec: Option<(usize, bool)>,
+        instr: Instruction,
+    }
+
+    impl TryFrom<InstrBookRecord> for InstrBook {
+        type Error = InstrError;
+
+        fn try_from(record: InstrBookRecord) -> Result<Self, Self::Error> {
+            InstrBook::try_new(record.start_pos, record.end_spec, record.instr)
+        }
+    }
+
+    /// Lower `CLIP` bound, `-inf` is stored as a missing value
+    pub mod lower_bound {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S: Serializer>(bound: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+            Some(*bound).filter(|bound| bound.is_finite()).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+            Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::NEG_INFINITY))
+        }
+    }
+
+    /// Upper `CLIP` bound, `inf` is stored as a missing value
+    pub mod upper_bound {
+        use serde::{Deserialize, Deserializer};
+
+        pub use super::lower_bound::serialize;
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+            Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::INFINITY))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod params {
+        use crate::instruction::*;
+
+        #[test]
+        fn args_round_trip() {
+            let instrs = vec![
+                Instruction::new_const(1.5),
+                Instruction::new_sine(10.0, None, Some(0.3), Some(-1.0)),
+                Instruction::new_chirp(1.0, 2.0, 0.0, 1.0, true, Some(0.5), None, None),
+                Instruction::new_linramp(0.0, 1.0, 2.0, 3.0),
+                Instruction::new_expramp_tau(1.0, 0.0, 0.5, 0.0),
+                Instruction::new_tanhramp(0.0, 1.0, 0.0, 1.0, Some(2.0)),
+                Instruction::new_pulse(InstrType::GAUSSPULSE, 0.0, 1.0, None, Some(5.0), None, None, None, None, None),
+                Instruction::new_pulse(InstrType::FLATTOPPULSE, 0.0, 1.0, None, None, None, None, None, Some(0.2), None),
+            ];
+            for instr in instrs {
+                let rebuilt = Instruction::new(instr.instr_type.clone(), instr.args());
+                assert!(rebuilt == instr, "{} was rebuilt as {}", instr, rebuilt);
+            }
+
+            // Only the finite CLIP bound is listed
+            let clipped = Instruction::new_clip(Instruction::new_const(1.0), None, Some(0.5));
+            assert_eq!(clipped.args().keys().collect::<Vec<_>>(), vec!["max"]);
+            // A DC pulse has no carrier frequency
+            let dc_pulse = Instruction::new_pulse(InstrType::COS2PULSE, 0.0, 1.0, None, None, None, None, None, None, None);
+            assert!(!dc_pulse.args().contains_key("freq"));
+        }
+
+        #[test]
+        fn defaults() {
+            let mut args = InstrArgs::new();
+            args.insert("freq".to_string(), 10.0);
+            let instr = Instruction::new(InstrType::SINE, args);
+            assert!(instr == Instruction::new_sine(10.0, Some(1.0), Some(0.0), Some(0.0)));
+            assert_eq!(
+                instr.to_string(),
+                "[SINE, {freq: 10, amplitude: 1, offset: 0, phase: 0}]"
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "don't belong to instr type SINE")]
+        fn mismatched_params() {
+            Instruction::new_with_params(InstrType::SINE, InstrParams::Const { value: 1.0 }, None);
+        }
+    }
+
+    mod errors {
+        use crate::instruction::*;
+
+        #[test]
+        fn try_new() {
+            let mut args = InstrArgs::new();
+            args.insert("value".to_string(), 1.0);
+            assert!(Instruction::try_new(InstrType::CONST, args).is_ok());
+
+            let err = Instruction::try_new(InstrType::LINRAMP, InstrArgs::new()).err().unwrap();
+            assert_eq!(
+                err,
+                InstrError::MissingArg { instr_type: "LINRAMP".to_string(), key: "start_val".to_string() }
+            );
+
+            let params = InstrParams::TanhRamp { start_val: 0.0, end_val: 1.0, start_time: 0.0, end_time: 1.0, steepness: -1.0 };
+            let err = Instruction::try_new_with_params(InstrType::TANHRAMP, params, None).err().unwrap();
+            assert!(matches!(err, InstrError::InvalidValue { .. }));
+            assert_eq!(err.to_string(), "Invalid TANHRAMP instruction: steepness must be positive, got -1");
+
+            assert!(matches!(
+                InstrBook::try_new(3, Some((2, false)), Instruction::new_const(0.0)),
+                Err(InstrError::ZeroLengthInterval { start_pos: 3, end_pos: 2 })
+            ));
+            assert!(InstrBook::try_new(3, None, Instruction::new_const(0.0)).is_ok());
+        }
+
+        #[test]
+        fn non_finite() {
+            let params = InstrParams::Sine { freq: f64::INFINITY, amplitude: 1.0, offset: 0.0, phase: 0.0 };
+            assert_eq!(
+                Instruction::try_new_with_params(InstrType::SINE, params, None).err().unwrap(),
+                InstrError::NonFinite { instr_type: "SINE".to_string(), name: "freq".to_string(), value: f64::INFINITY }
+            );
+
+            let samples = InstrData::Samples { samples: vec![0.0, f64::NAN], interp: Interp::Linear };
+            let params = InstrParams::Sampled { start_time: 0.0, samp_rate: 1.0 };
+            let err = Instruction::try_new_with_params(InstrType::ARB, params, Some(samples)).err().unwrap();
+            assert!(matches!(err, InstrError::NonFinite { ref name, .. } if name == "samples"));
+
+            // Infinite CLIP bounds only mean "unbounded", NaN is still rejected
+            let child = InstrData::Children(vec![Instruction::new_const(0.0)]);
+            let params = InstrParams::Clip { min: f64::NEG_INFINITY, max: f64::NAN };
+            assert!(Instruction::try_new_with_params(InstrType::CLIP, params, Some(child)).is_err());
+        }
+
+        #[test]
+        #[should_panic(expected = "Argument value of instr type CONST must be finite")]
+        fn nan_panics() {
+            Instruction::new_const(f64::NAN);
+        }
+    }
+
+    mod chirp {
+        use crate::instruction::*;
+
+        /// Evaluating on sub-intervals (as the streamer does chunk-by-chunk) gives the same samples
+        #[test]
+        fn chunked_eval() {
+            for exponential in [false, true] {
+                let instr = Instruction::new_chirp(1e3, 5e3, 1e-3, 3e-3, exponential, Some(2.0), Some(0.3), Some(-1.0));
+                let full = ndarray::Array1::linspace(1e-3, 3e-3, 2001);
+                let mut whole = full.clone();
+                instr.eval_inplace(&mut whole.view_mut());
+                let mut chunked = full.clone();
+                for mut chunk in chunked.axis_chunks_iter_mut(ndarray::Axis(0), 23) {
+                    instr.eval_inplace(&mut chunk);
+                }
+                assert_eq!(whole, chunked);
+            }
+        }
+
+        #[test]
+        fn instantaneous_frequency() {
+            let phase_at = |instr: &Instruction, t: f64| {
+                // Recover phase derivative numerically from two nearby points of a unit-amplitude chirp
+                let dt = 1e-9;
+                let (y0, y1) = (instr.eval_point(t), instr.eval_point(t + dt));
+                (y1.asin() - y0.asin()) / dt / (2.0 * std::f64::consts::PI)
+            };
+            // Start at phase 0, where asin is well-behaved
+            let lin = Instruction::new_chirp(1e3, 3e3, 0.0, 1e-3, false, None, None, None);
+            assert!((phase_at(&lin, 0.0) - 1e3).abs() < 1.0);
+            let exp = Instruction::new_chirp(1e3, 4e3, 0.0, 1e-3, true, None, None, None);
+            assert!((phase_at(&exp, 0.0) - 1e3).abs() < 1.0);
+            // Exponential sweep accumulates `f_start * T * (k - 1) / ln(k)` cycles over the full duration
+            let cycles = 1e3 * 1e-3 * 3.0 / 4.0_f64.ln();
+            let expected = (2.0 * std::f64::consts::PI * cycles).sin();
+            assert!((exp.eval_point(1e-3) - expected).abs() < 1e-9);
+        }
+    }
+
+    mod pulses {
+        use crate::instruction::*;
+
+        #[test]
+        fn window_names() {
+            for window in [PulseWindow::Gauss, PulseWindow::Blackman, PulseWindow::Cos2, PulseWindow::FlatTop] {
+                assert_eq!(window.to_string().parse::<PulseWindow>(), Ok(window));
+            }
+            assert!("hann".parse::<PulseWindow>().unwrap_err().contains("Unknown pulse window \"hann\""));
+        }
+
+        #[test]
+        fn windows() {
+            let pulse = |instr_type: InstrType| {
+                Instruction::new_pulse(instr_type, 1.0, 3.0, Some(2.0), None, None, Some(0.5), None, None, None)
+            };
+            for instr_type in [
+                InstrType::GAUSSPULSE,
+                InstrType::BLACKMANPULSE,
+                InstrType::COS2PULSE,
+                InstrType::FLATTOPPULSE,
+            ] {
+                let instr = pulse(instr_type);
+                // Peak value in the pulse center
+                assert!((instr.eval_point(2.0) - 2.5).abs() < 1e-12, "{}", instr);
+                // Returns to the offset at the pulse end, which is where `keep_val` padding is evaluated
+                assert_eq!(instr.eval_point(3.0), 0.5, "{}", instr);
+                assert_eq!(instr.eval_point(0.5), 0.5, "{}", instr);
+            }
+            // Gaussian: default truncation at 3 sigma
+            let gauss = pulse(InstrType::GAUSSPULSE);
+            assert!((gauss.eval_point(1.0) - (0.5 + 2.0 * (-4.5_f64).exp())).abs() < 1e-12);
+            // Flat top: rise and fall last 1/10 of the duration by default
+            let flat_top = pulse(InstrType::FLATTOPPULSE);
+            assert_eq!(flat_top.eval_point(1.2), 2.5);
+            assert!((flat_top.eval_point(1.1) - 1.5).abs() < 1e-12);
+        }
+
+        #[test]
+        fn carrier() {
+            let pulse = Instruction::new_pulse(
+                InstrType::FLATTOPPULSE, 0.0, 1.0, Some(2.0), Some(10.0), Some(0.3), None, None, Some(0.2), Some(0.2),
+            );
+            let sine = Instruction::new_sine(10.0, Some(2.0), Some(0.3), None);
+            assert_eq!(pulse.eval_point(0.5), sine.eval_point(0.5));
+            assert!(pulse.eval_point(0.1).abs() < sine.eval_point(0.1).abs());
+        }
+
+        #[test]
+        #[should_panic(expected = "sigma can only be specified for GAUSSPULSE")]
+        fn wrong_window_arg() {
+            Instruction::new_pulse(InstrType::COS2PULSE, 0.0, 1.0, None, None, None, None, Some(0.1), None, None);
+        }
+    }
+
+    mod ramps {
+        use crate::instruction::*;
+
+        #[test]
+        fn end_values() {
+            let ramps = [
+                Instruction::new_linramp(2.0, 8.0, 1.0, 3.0),
+                Instruction::new_expramp(2.0, 8.0, 1.0, 3.0),
+                Instruction::new_logramp(2.0, 8.0, 1.0, 3.0, 0.1),
+                Instruction::new_powramp(2.0, 8.0, 1.0, 3.0, 3.0),
+            ];
+            for instr in ramps.iter() {
+                assert!((instr.eval_point(1.0) - 2.0).abs() < 1e-12, "{}", instr);
+                assert!((instr.eval_point(3.0) - 8.0).abs() < 1e-12, "{}", instr);
+            }
+            // Geometric midpoint
+            assert!((ramps[1].eval_point(2.0) - 4.0).abs() < 1e-12);
+            // Cubic midpoint
+            assert!((ramps[3].eval_point(2.0) - 2.75).abs() < 1e-12);
+        }
+
+        #[test]
+        fn smooth_ramps() {
+            let ramps = [
+                Instruction::new_tanhramp(2.0, 8.0, 1.0, 3.0, Some(3.0)),
+                Instruction::new_smooth_ramp(InstrType::MINJERKRAMP, 2.0, 8.0, 1.0, 3.0),
+                Instruction::new_smooth_ramp(InstrType::COSRAMP, 2.0, 8.0, 1.0, 3.0),
+                Instruction::new_smooth_ramp(InstrType::BLACKMANRAMP, 2.0, 8.0, 1.0, 3.0),
+            ];
+            let dt = 1e-6;
+            for instr in ramps.iter() {
+                assert!((instr.eval_point(1.0) - 2.0).abs() < 1e-12, "{}", instr);
+                assert!((instr.eval_point(3.0) - 8.0).abs() < 1e-12, "{}", instr);
+                // Point-symmetric around the ramp center
+                assert!((instr.eval_point(2.0) - 5.0).abs() < 1e-12, "{}", instr);
+                // Values are held outside of the ramp interval
+                assert!((instr.eval_point(0.0) - 2.0).abs() < 1e-12, "{}", instr);
+                assert!((instr.eval_point(4.0) - 8.0).abs() < 1e-12, "{}", instr);
+            }
+            // Zero slope at the ends (tanh only has a bounded one)
+            for instr in ramps[1..].iter() {
+                assert!(((instr.eval_point(1.0 + dt) - 2.0) / dt).abs() < 1e-3, "{}", instr);
+                assert!(((8.0 - instr.eval_point(3.0 - dt)) / dt).abs() < 1e-3, "{}", instr);
+            }
+        }
+
+        #[test]
+        fn shape_names() {
+            for shape in [RampShape::Tanh, RampShape::MinJerk, RampShape::Cos, RampShape::Blackman] {
+                assert_eq!(shape.to_string().parse::<RampShape>(), Ok(shape));
+            }
+            assert!("linear".parse::<RampShape>().unwrap_err().contains("Unknown smooth ramp shape \"linear\""));
+        }
+
+        #[test]
+        fn expramp_tau() {
+            let instr = Instruction::new_expramp_tau(5.0, 1.0, 2.0, 1.0);
+            assert_eq!(instr.eval_point(1.0), 5.0);
+            assert!((instr.eval_point(3.0) - (1.0 + 4.0 * (-1.0_f64).exp())).abs() < 1e-12);
+            assert!((instr.eval_point(100.0) - 1.0).abs() < 1e-9);
+        }
+
+        #[test]
+        #[should_panic(expected = "same sign")]
+        fn expramp_sign_change() {
+            Instruction::new_expramp(-1.0, 1.0, 0.0, 1.0);
+        }
+    }
+
+    mod arb {
+        use crate::instruction::*;
+
+        #[test]
+        fn interpolation() {
+            let samples = vec![0.0, 1.0, 4.0, 9.0];
+            let nearest = Instruction::new_arb(samples.clone(), 1.0, 10.0, Interp::Nearest);
+            let linear = Instruction::new_arb(samples.clone(), 1.0, 10.0, Interp::Linear);
+            let cubic = Instruction::new_arb(samples.clone(), 1.0, 10.0, Interp::Cubic);
+
+            // All schemes pass through the sample points
+            for (i, val) in samples.iter().enumerate() {
+                let t = 10.0 + i as f64;
+                assert_eq!(nearest.eval_point(t), *val);
+                assert_eq!(linear.eval_point(t), *val);
+                assert_eq!(cubic.eval_point(t), *val);
+            }
+            assert_eq!(nearest.eval_point(11.4), 1.0);
+            assert_eq!(nearest.eval_point(11.6), 4.0);
+            assert_eq!(linear.eval_point(11.5), 2.5);
+            // Catmull-Rom reproduces the quadratic exactly away from the edges
+            assert!((cubic.eval_point(11.5) - 2.25).abs() < 1e-12);
+
+            // Values outside of the sampled interval are held
+            assert_eq!(linear.eval_point(9.0), 0.0);
+            assert_eq!(cubic.eval_point(20.0), 9.0);
+        }
+
+        #[test]
+        fn shared_buffer() {
+            let instr = Instruction::new_arb(vec![1.0; 1000], 1e3, 0.0, Interp::Linear);
+            let copy = instr.clone();
+            assert!(std::sync::Arc::ptr_eq(
+                instr.data.as_ref().unwrap(),
+                copy.data.as_ref().unwrap()
+            ));
+            assert!(instr == copy);
+            assert!(instr != Instruction::new_arb(vec![1.0; 1000], 1e3, 0.0, Interp::Cubic));
+        }
+
+        #[test]
+        #[should_panic(expected = "non-empty sample buffer")]
+        fn empty_buffer() {
+            Instruction::new_arb(vec![], 1e3, 0.0, Interp::Linear);
+        }
+    }
+
+    mod composite {
+        use crate::instruction::*;
+
+        #[test]
+        fn eval() {
+            let ramp = Instruction::new_linramp(0.0, 2.0, 0.0, 2.0);
+            let sine = Instruction::new_sine(0.25, None, None, None);
+
+            let sum = Instruction::new_sum(vec![ramp.clone(), sine.clone(), Instruction::new_const(1.0)]);
+            assert!((sum.eval_point(1.0) - 3.0).abs() < 1e-12);
+            let product = Instruction::new_product(vec![ramp.clone(), sine.clone()]);
+            assert!((product.eval_point(1.0) - 1.0).abs() < 1e-12);
+
+            let scaled = Instruction::new_scale(ramp.clone(), -3.0);
+            assert_eq!(scaled.eval_point(1.0), -3.0);
+            let offset = Instruction::new_offset(ramp.clone(), 0.5);
+            assert_eq!(offset.eval_point(1.0), 1.5);
+            let clipped = Instruction::new_clip(scaled.clone(), Some(-2.0), None);
+            assert_eq!(clipped.eval_point(0.5), -1.5);
+            assert_eq!(clipped.eval_point(1.0), -2.0);
+            let shifted = Instruction::new_time_shift(ramp.clone(), 0.5);
+            assert_eq!(shifted.eval_point(1.5), 1.0);
+
+            // Nested trees evaluate the same on an array as point-by-point
+            let tree = Instruction::new_time_shift(Instruction::new_sum(vec![clipped, product]), 0.1);
+            let mut t_arr = ndarray::Array1::linspace(0.0, 2.0, 101);
+            let expected = t_arr.mapv(|t| tree.eval_point(t));
+            tree.eval_inplace(&mut t_arr.view_mut());
+            assert_eq!(t_arr, expected);
+        }
+
+        #[test]
+        fn from_py_spec() {
+            use pyo3::prelude::*;
+
+            pyo3::prepare_freethreaded_python();
+            Python::with_gil(|py| {
+                // Sine riding on a ramp
+                let spec = py
+                    .eval(
+                        "{'type': 'sum', 'children': [\
+                            {'type': 'SINE', 'args': {'freq': 10.0, 'amplitude': 0.1}},\
+                            {'type': 'LINRAMP', 'args': {'start_val': 0.0, 'end_val': 1.0, 'start_time': 0.0, 'end_time': 1.0}}]}",
+                        None,
+                        None,
+                    )
+                    .unwrap();
+                let instr = Instruction::from_py_spec(spec).unwrap();
+                assert!(instr == Instruction::new_sum(vec![
+                    Instruction::new_sine(10.0, Some(0.1), None, None),
+                    Instruction::new_linramp(0.0, 1.0, 0.0, 1.0),
+                ]));
+
+                // Nested trees survive a round trip through their python description
+                let tree = Instruction::new_time_shift(Instruction::new_clip(instr, None, Some(0.5)), 0.1);
+                let spec = tree.to_py_spec(py).unwrap();
+                assert!(Instruction::from_py_spec(spec).unwrap() == tree);
+                let children: Vec<&PyDict> = spec.get_item("children").unwrap().extract().unwrap();
+                assert_eq!(children[0].get_item("type").unwrap().extract::<String>().unwrap(), "CLIP");
+
+                for bad_spec in [
+                    "{'type': 'SCALE', 'args': {'factor': 2.0}}",
+                    "{'type': 'SQUARE'}",
+                    "{'args': {'value': 1.0}}",
+                    "{'type': 'CONST', 'args': {'value': 'one'}}",
+                ] {
+                    let err = Instruction::from_py_spec(py.eval(bad_spec, None, None).unwrap()).err().unwrap();
+                    assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py)
+                        || err.is_instance_of::<pyo3::exceptions::PyTypeError>(py), "{bad_spec}: {err}");
+                }
+            });
+        }
+
+        #[test]
+        fn equality() {
+            let build = |amplitude: f64| {
+                Instruction::new_sum(vec![
+                    Instruction::new_linramp(0.0, 1.0, 0.0, 1.0),
+                    Instruction::new_scale(Instruction::new_sine(10.0, None, None, None), amplitude),
+                ])
+            };
+            assert!(build(1.0) == build(1.0));
+            assert!(build(1.0) != build(2.0));
+            assert!(build(1.0) != Instruction::new_product(build(1.0).children().to_vec()));
+        }
+
+        #[test]
+        fn shifted() {
+            let dt = 0.3;
+            let instrs = [
+                Instruction::new_sine(1.7, Some(2.0), Some(0.4), Some(0.1)),
+                Instruction::new_pulse(InstrType::GAUSSPULSE, 1.0, 2.0, None, Some(5.0), None, None, None, None, None),
+                Instruction::new_poly(vec![1.0, 2.0, 3.0], 1.0),
+                Instruction::new_spline(vec![(0.0, 0.0), (0.5, 1.0), (1.0, -1.0)], 1.0),
+                Instruction::new_pulse_train(1.0, 0.2, Some(0.3), Some(0.1), None),
+                Instruction::new_fourier(2.0, vec![1.0, 0.5], Some(vec![0.2, 0.0]), None),
+                Instruction::new_time_shift(Instruction::new_linramp(0.0, 1.0, 1.0, 2.0), 0.2),
+            ];
+            for instr in instrs {
+                let moved = instr.shifted(dt);
+                // Time arguments are shifted in place where possible, `TIMESHIFT`s are merged
+                assert!(moved.instr_type == instr.instr_type || instr.instr_type == InstrType::FOURIER);
+                for t in [1.0, 1.25, 1.5, 1.9] {
+                    assert!((moved.eval_point(t + dt) - instr.eval_point(t)).abs() < 1e-9, "{instr} at t = {t}");
+                }
+            }
+            // Carriers placed on compilation don't depend on the position
+            let local = Instruction::new_sine(1.7, None, None, None).with_time_ref(TimeRef::Local);
+            assert!(local.shifted(dt) == local);
+        }
+
+        #[test]
+        #[should_panic(expected = "requires exactly one child instruction")]
+        fn wrong_num_children() {
+            let mut args = InstrArgs::new();
+            args.insert("factor".to_string(), 2.0);
+            Instruction::new_with_data(InstrType::SCALE, args, Some(InstrData::Children(vec![])));
+        }
+    }
+
+    mod breakpoints {
+        use crate::instruction::*;
+
+        #[test]
+        fn pwl() {
+            let instr = Instruction::new_pwl(vec![(0.0, 1.0), (2.0, 3.0), (3.0, 0.0)], 1.0);
+            assert_eq!(instr.eval_point(0.0), 1.0);
+            assert_eq!(instr.eval_point(2.0), 2.0);
+            assert_eq!(instr.eval_point(3.0), 3.0);
+            assert_eq!(instr.eval_point(3.5), 1.5);
+            assert_eq!(instr.eval_point(10.0), 0.0);
+        }
+
+        #[test]
+        fn natural_spline() {
+            let points = vec![(0.0, 0.0), (1.0, 1.0), (3.0, -1.0), (4.0, 2.0)];
+            let instr = Instruction::new_spline(points.clone(), 0.0);
+            for (t, val) in points.iter() {
+                assert!((instr.eval_point(*t) - val).abs() < 1e-12);
+            }
+            // Zero curvature at both ends
+            let curvature = |t: f64| {
+                let dt = 1e-4;
+                (instr.eval_point(t + dt) - 2.0 * instr.eval_point(t) + instr.eval_point(t - dt)) / (dt * dt)
+            };
+            assert!(curvature(1e-3).abs() < 1e-2);
+            assert!(curvature(4.0 - 1e-3).abs() < 1e-2);
+            // Continuous slope at an inner knot
+            let slope = |t0: f64, t1: f64| (instr.eval_point(t1) - instr.eval_point(t0)) / (t1 - t0);
+            assert!((slope(0.999, 1.0) - slope(1.0, 1.001)).abs() < 1e-2);
+
+            // Two breakpoints reduce to a straight line
+            let line = Instruction::new_spline(vec![(0.0, 0.0), (2.0, 4.0)], 0.0);
+            assert_eq!(line.eval_point(0.5), 1.0);
+        }
+
+        #[test]
+        #[should_panic(expected = "strictly increasing")]
+        fn unordered_times() {
+            Instruction::new_pwl(vec![(0.0, 0.0), (2.0, 1.0), (1.0, 0.0)], 0.0);
+        }
+    }
+
+    mod pulse_train {
+        use crate::instruction::*;
+
+        #[test]
+        fn edges() {
+            // 1 kHz clock, 5-tick period with 2 ticks high, 3 pulses
+            let train = Instruction::new_pulse_train(0.1, 5e-3, Some(0.4), None, Some(3));
+            let mut t_arr = ndarray::Array1::from_shape_fn(20, |i| 0.1 + i as f64 * 1e-3);
+            train.eval_inplace(&mut t_arr.view_mut());
+            let expected: Vec<f64> = (0..20)
+                .map(|k| if k < 15 && k % 5 < 2 { 1.0 } else { 0.0 })
+                .collect();
+            assert_eq!(t_arr.to_vec(), expected);
+
+            // Half a period of phase puts the train mid-way into its first (low) half
+            let shifted = Instruction::new_pulse_train(0.0, 1.0, Some(0.5), Some(0.5), None);
+            assert_eq!(shifted.eval_point(0.0), 0.0);
+            assert_eq!(shifted.eval_point(0.5), 1.0);
+        }
+
+        #[test]
+        #[should_panic(expected = "duty cycle must be in [0, 1]")]
+        fn bad_duty() {
+            Instruction::new_pulse_train(0.0, 1.0, Some(1.5), None, None);
+        }
+    }
+
+    mod noise {
+        use crate::instruction::*;
+
+        #[test]
+        fn chunking_independent() {
+            for bandwidth in [None, Some(3e3)] {
+                let noise = Instruction::new_noise(1e4, 0.5, 7, true, bandwidth, Some(0.1));
+                let times = ndarray::Array1::from_shape_fn(1000, |i| 0.2 + i as f64 / 1e4);
+                let mut whole = times.clone();
+                noise.eval_inplace(&mut whole.view_mut());
+                let mut chunked = times.clone();
+                for mut chunk in chunked.axis_chunks_iter_mut(ndarray::Axis(0), 37) {
+                    noise.eval_inplace(&mut chunk);
+                }
+                assert_eq!(whole, chunked);
+                // Times off the tick grid by rounding errors map to the same sample
+                assert_eq!(noise.eval_point(0.2 + 5.0 / 1e4 + 1e-12), whole[5]);
+            }
+            // A different seed gives a different stream
+            let other = Instruction::new_noise(1e4, 0.5, 8, true, None, Some(0.1));
+            assert_ne!(other.eval_point(0.2), Instruction::new_noise(1e4, 0.5, 7, true, None, Some(0.1)).eval_point(0.2));
+        }
+
+        #[test]
+        fn integer_args() {
+            let noise = Instruction::new_noise(1e4, 0.5, MAX_INT_ARG, false, None, None);
+            let rebuilt = Instruction::try_new(InstrType::NOISE, noise.args()).unwrap();
+            assert!(rebuilt == noise);
+            let params = InstrParams::Noise {
+                amplitude: 0.5,
+                offset: 0.0,
+                seed: MAX_INT_ARG + 1,
+                gaussian: false,
+                bandwidth: None,
+                samp_rate: 1e4,
+            };
+            assert!(Instruction::try_new_with_params(InstrType::NOISE, params, None).is_err());
+
+            let train = Instruction::new_pulse_train(0.0, 1.0, None, None, Some(3));
+            assert!(Instruction::try_new(InstrType::PULSETRAIN, train.args()).unwrap() == train);
+
+            for (instr_type, key, value) in [
+                (InstrType::NOISE, "seed", 1.5),
+                (InstrType::NOISE, "seed", -1.0),
+                (InstrType::NOISE, "seed", f64::NAN),
+                (InstrType::NOISE, "seed", 2f64.powi(60)),
+                (InstrType::PULSETRAIN, "count", 2.5),
+                (InstrType::PULSETRAIN, "count", -3.0),
+            ] {
+                let mut args = match instr_type {
+                    InstrType::NOISE => noise.args(),
+                    _ => train.args(),
+                };
+                args.insert(key.to_string(), value);
+                match Instruction::try_new(instr_type, args) {
+                    Err(InstrError::InvalidValue { msg, .. }) => assert!(msg.contains(key), "{}", msg),
+                    _ => panic!("{} = {} was accepted", key, value),
+                }
+            }
+        }
+
+        #[test]
+        fn statistics() {
+            let mut samples = ndarray::Array1::from_shape_fn(100_000, |i| i as f64 / 1e6);
+            Instruction::new_noise(1e6, 2.0, 1, false, None, None).eval_inplace(&mut samples.view_mut());
+            assert!(samples.iter().all(|val| (-2.0..2.0).contains(val)));
+            assert!(samples.mean().unwrap().abs() < 0.05);
+
+            let mut samples = ndarray::Array1::from_shape_fn(100_000, |i| i as f64 / 1e6);
+            Instruction::new_noise(1e6, 2.0, 1, true, None, Some(1.0)).eval_inplace(&mut samples.view_mut());
+            assert!((samples.mean().unwrap() - 1.0).abs() < 0.05);
+            assert!((samples.std(0.0) - 2.0).abs() < 0.05);
+        }
+    }
+
+    mod poly_fourier {
+        use crate::instruction::*;
+
+        #[test]
+        fn poly_horner() {
+            let coefs = vec![0.5, -1.0, 2.0, 0.25];
+            let instr = Instruction::new_poly(coefs.clone(), 1.0);
+            for t in [1.0, 1.3, 2.0, 4.5] {
+                let tau: f64 = t - 1.0;
+                let direct: f64 = coefs.iter().enumerate().map(|(k, c)| c * tau.powi(k as i32)).sum();
+                assert!((instr.eval_point(t) - direct).abs() < 1e-12);
+            }
+        }
+
+        #[test]
+        fn fourier_recurrence() {
+            let (freq, amps, phases) = (3.0, vec![1.0, 0.5, 0.0, -0.2, 0.1], vec![0.1, 1.0, 0.0, -2.0, 3.0]);
+            let instr = Instruction::new_fourier(freq, amps.clone(), Some(phases.clone()), Some(0.3));
+            let mut t_arr = ndarray::Array1::linspace(0.0, 10.0, 10001);
+            let direct = t_arr.mapv(|t| {
+                0.3 + amps.iter().zip(phases.iter()).enumerate()
+                    .map(|(k, (amp, phase))| amp * (2.0 * PI * (k + 1) as f64 * freq * t + phase).sin())
+                    .sum::<f64>()
+            });
+            instr.eval_inplace(&mut t_arr.view_mut());
+            assert!(t_arr.iter().zip(direct.iter()).all(|(a, b)| (a - b).abs() < 1e-9));
+        }
+
+        #[test]
+        #[should_panic(expected = "matching amplitudes and phases")]
+        fn fourier_phase_count() {
+            Instruction::new_fourier(1.0, vec![1.0, 0.5], Some(vec![0.0]), None);
+        }
+    }
+
+    mod bounds {
+        use crate::instruction::*;
+
+        /// Sampled minimum and maximum over `[t_start, t_end]`
+        fn sampled(instr: &Instruction, t_start: f64, t_end: f64) -> (f64, f64) {
+            let mut t_arr = ndarray::Array1::linspace(t_start, t_end, 100_001);
+            instr.eval_inplace(&mut t_arr.view_mut());
+            min_max(t_arr.iter().copied())
+        }
+
+        fn assert_exact(instr: &Instruction, t_start: f64, t_end: f64) {
+            let (min, max) = instr.bounds(t_start, t_end);
+            let (samp_min, samp_max) = sampled(instr, t_start, t_end);
+            assert!((min - samp_min).abs() < 1e-6 && (max - samp_max).abs() < 1e-6,
+                    "{}: bounds ({}, {}) vs sampled ({}, {})", instr, min, max, samp_min, samp_max);
+        }
+
+        fn assert_contains(instr: &Instruction, t_start: f64, t_end: f64) {
+            let (min, max) = instr.bounds(t_start, t_end);
+            let (samp_min, samp_max) = sampled(instr, t_start, t_end);
+            assert!(min <= samp_min + 1e-12 && samp_max <= max + 1e-12,
+                    "{}: bounds ({}, {}) miss sampled ({}, {})", instr, min, max, samp_min, samp_max);
+        }
+
+        #[test]
+        fn exact() {
+            let sine = Instruction::new_sine(3.0, Some(-1.5), Some(0.7), Some(0.2));
+            for (t_start, t_end) in [(0.0, 0.01), (0.02, 0.1), (0.05, 0.25), (0.3, 0.31), (0.0, 2.0)] {
+                assert_exact(&sine, t_start, t_end);
+            }
+            assert_exact(&Instruction::new_linramp(1.0, -2.0, 0.0, 1.0), 0.1, 0.6);
+            assert_exact(&Instruction::new_smooth_ramp(InstrType::BLACKMANRAMP, 1.0, 3.0, 0.0, 1.0), -0.5, 0.6);
+            assert_exact(&Instruction::new_expramp_tau(1.0, 0.0, 0.1, 0.0), 0.0, 1.0);
+            assert_exact(&Instruction::new_pwl(vec![(0.0, 0.0), (0.5, 2.0), (1.0, -1.0)], 0.0), 0.25, 0.75);
+            assert_exact(&Instruction::new_arb(vec![0.0, 3.0, -1.0, 2.0], 10.0, 0.0, Interp::Linear), 0.05, 0.25);
+        }
+
+        #[test]
+        fn conservative() {
+            let breakpoints = vec![(0.0, 0.0), (0.3, 2.0), (0.4, -1.0), (1.0, 0.5)];
+            assert_contains(&Instruction::new_spline(breakpoints, 0.0), 0.1, 0.9);
+            assert_contains(&Instruction::new_arb(vec![0.0, 3.0, -1.0, 2.0], 10.0, 0.0, Interp::Cubic), 0.0, 0.3);
+            assert_contains(&Instruction::new_poly(vec![0.5, -3.0, 1.0, 2.0], 0.0), -1.0, 1.0);
+            assert_contains(&Instruction::new_fourier(2.0, vec![1.0, -0.5, 0.25], None, Some(0.1)), 0.0, 1.0);
+            assert_contains(&Instruction::new_noise(1e4, 0.5, 3, false, Some(500.0), None), 0.0, 0.1);
+            assert_eq!(Instruction::new_noise(1e4, 0.5, 3, true, None, None).bounds(0.0, 1.0).1, f64::INFINITY);
+        }
+
+        #[test]
+        fn slope() {
+            let instrs = vec![
+                Instruction::new_linramp(1.0, -2.0, 0.0, 1.0),
+                Instruction::new_sine(3.0, Some(-1.5), Some(0.7), Some(0.2)),
+                Instruction::new_smooth_ramp(InstrType::MINJERKRAMP, 1.0, 3.0, 0.0, 1.0),
+                Instruction::new_smooth_ramp(InstrType::BLACKMANRAMP, 1.0, 3.0, 0.0, 1.0),
+                Instruction::new_tanhramp(1.0, 3.0, 0.0, 1.0, Some(3.0)),
+                Instruction::new_expramp(0.1, 10.0, 0.0, 1.0),
+                Instruction::new_logramp(0.0, 1.0, 0.0, 1.0, 0.1),
+                Instruction::new_pwl(vec![(0.0, 0.0), (0.2, 2.0), (1.0, -1.0)], 0.0),
+                Instruction::new_product(vec![
+                    Instruction::new_sine(2.0, None, None, None),
+                    Instruction::new_linramp(0.0, 2.0, 0.0, 1.0),
+                ]),
+            ];
+            for instr in &instrs {
+                let bound = instr.slope_bound(0.0, 1.0).unwrap();
+                let mut t_arr = ndarray::Array1::linspace(0.0, 1.0, 100_001);
+                instr.eval_inplace(&mut t_arr.view_mut());
+                let sampled = t_arr.windows(2).into_iter().map(|w| ((w[1] - w[0]) * 1e5).abs()).fold(0.0, f64::max);
+                assert!(sampled <= bound * (1.0 + 1e-6), "{}: slope bound {} below sampled {}", instr, bound, sampled);
+            }
+            assert!(Instruction::new_noise(1e4, 0.5, 3, false, None, None).slope_bound(0.0, 1.0).is_none());
+        }
+
+        #[test]
+        fn composite() {
+            let sine = Instruction::new_sine(1.0, None, None, None);
+            let ramp = Instruction::new_linramp(-1.0, 2.0, 0.0, 1.0);
+            assert_eq!(Instruction::new_sum(vec![sine.clone(), ramp.clone()]).bounds(0.0, 1.0), (-2.0, 3.0));
+            assert_eq!(Instruction::new_product(vec![sine.clone(), ramp.clone()]).bounds(0.0, 1.0), (-2.0, 2.0));
+            assert_eq!(Instruction::new_scale(ramp.clone(), -2.0).bounds(0.0, 1.0), (-4.0, 2.0));
+            assert_eq!(Instruction::new_clip(ramp.clone(), Some(0.0), None).bounds(0.0, 0.2), (0.0, 0.0));
+            assert_exact(&Instruction::new_time_shift(Instruction::new_offset(ramp, 1.0), 0.5), 0.6, 1.2);
+            // Time reference not resolved yet, the crest may be anywhere
+            let local = sine.with_time_ref(TimeRef::Local);
+            assert_eq!(local.bounds(0.0, 0.1), (-1.0, 1.0));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use crate::instruction::*;
+
+        fn round_trip(instr: &Instruction) -> Instruction {
+            serde_json::from_str(&serde_json::to_string(instr).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn round_trip_json() {
+            let mut params = InstrArgs::new();
+            params.insert("A".to_string(), 2.0);
+            let instrs = vec![
+                Instruction::new_sine(10.0, Some(0.5), None, None).with_time_ref(TimeRef::Continuous),
+                Instruction::new_clip(Instruction::new_spline(vec![(0.0, 0.0), (0.5, 1.0), (1.0, -1.0)], 0.2), None, Some(0.5)),
+                Instruction::new_sum(vec![
+                    Instruction::new_expr("A * t", params).unwrap(),
+                    Instruction::new_noise(1e6, 0.1, MAX_INT_ARG, true, Some(1e3), None),
+                ]),
+                Instruction::new_fourier(5.0, vec![1.0, 0.5], Some(vec![0.0, 1.0]), None),
+            ];
+            for instr in &instrs {
+                assert!(round_trip(instr) == *instr, "{} did not survive a JSON round trip", instr);
+            }
+        }
+
+        #[test]
+        fn invalid() {
+            let json = serde_json::to_string(&Instruction::new_expramp_tau(1.0, 0.0, 0.1, 0.0)).unwrap();
+            let err = serde_json::from_str::<Instruction>(&json.replace("0.1", "-0.1")).err().unwrap();
+            assert!(err.to_string().contains("time constant must be positive"));
+
+            // Breakpoints are checked before the spline moments are rebuilt from them
+            let json = serde_json::to_string(&Instruction::new_spline(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)], 0.0)).unwrap();
+            let err = serde_json::from_str::<Instruction>(&json.replace("[0.0,1.0,0.0]", "[0.0,1.0]")).err().unwrap();
+            assert!(err.to_string().contains("matching times and values"), "{}", err);
+            let err = serde_json::from_str::<Instruction>(&json.replace("[0.0,1.0,0.0]", "[0.0,1.0,0.0,0.0]")).err().unwrap();
+            assert!(err.to_string().contains("matching times and values"), "{}", err);
+
+            // Derived data of mismatched length is rejected when built directly
+            let data = InstrData::Breakpoints { times: vec![0.0, 1.0, 2.0], values: vec![0.0, 1.0, 0.0], moments: vec![0.0; 4] };
+            let err = Instruction::try_new_with_params(InstrType::SPLINE, InstrParams::Breakpoints { start_time: 0.0 }, Some(data));
+            assert!(err.err().unwrap().to_string().contains("expected 3 spline moments, got 4"));
+        }
+
+        #[test]
+        fn derived_data() {
+            // Spline moments and Fourier coefficients are not stored, stale values in an archive are ignored
+            let spline = Instruction::new_spline(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 0.0), (3.0, 1.0)], 0.0);
+            let json = serde_json::to_string(&spline).unwrap();
+            assert!(!json.contains("moments"));
+            let stale = json.replace("\"values\"", "\"moments\":[0.0,1.0,1.0,0.0,1.0],\"values\"");
+            let restored = serde_json::from_str::<Instruction>(&stale).unwrap();
+            assert!(restored == spline);
+            assert!((restored.eval_point(1.5) - 0.5).abs() < 1e-12);
+
+            let fourier = Instruction::new_fourier(5.0, vec![1.0, 0.5], Some(vec![0.0, 1.0]), None);
+            assert!(!serde_json::to_string(&fourier).unwrap().contains("coefs"));
+        }
+    }
+}
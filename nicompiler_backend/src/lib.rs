@@ -54,8 +54,8 @@
 //! exp.add_ao_channel("PXI1Slot4", 0, 0.);
 //!
 //! exp.add_do_device("PXI1Slot6", 1e7);
-//! exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
-//! exp.add_do_channel("PXI1Slot6", 0, 4, 0.);
+//! exp.add_do_channel("PXI1Slot6", 0, 0, false);
+//! exp.add_do_channel("PXI1Slot6", 0, 4, false);
 //!
 //! // Define synchronization behavior:
 //! exp.device_cfg_trig("PXI1Slot3", "PXI1_Trig0", true);
@@ -69,7 +69,7 @@
 //!
 //! // PXI1Slot3/ao0 starts with a 1s-long 7Hz sine wave with offset 1
 //! // and unit amplitude, zero phase. Does not keep its value.
-//! exp.sine("PXI1Slot3", "ao0", 0., 1., false, 7., None, None, Some(1.));
+//! exp.sine("PXI1Slot3", "ao0", 0., 1., false, 7., None, None, Some(1.), None);
 //! // Ends with a half-second long 1V constant signal which returns to zero
 //! exp.constant("PXI1Slot3", "ao0", 9., 0.5, 1., false);
 //!
@@ -169,7 +169,8 @@
 //!
 //! Ideal for those wanting to understand how instructions are managed or need to design a new [`TaskType`] as well as `TaskType`-specific customized channel behavior.
 //!
-//! - **Overview**: A [`Channel`] signifies a specific physical channel on an NI device. It administers a series of non-overlapping [`InstrBook`] which, after compilation, can be sampled to render floating-point signals.
+//! - **Overview**: An [`AoChannel`] or [`DoChannel`] signifies a specific physical channel on an NI device. It administers a series of non-overlapping [`InstrBook`] which, after compilation, can be sampled to render floating-point signals.
+//! - **Physical units**: AO channels can carry a [`Calibration`] (see the [`calibration`] module), so that instructions are written in e.g. MHz or mW and converted to volts when sampled.
 //!
 //! ### [`instruction`] Module: Deep Dive into Instructions
 //!
@@ -182,19 +183,25 @@
 use pyo3::prelude::*;
 // use pyo3::wrap_pyfunction;
 
+pub mod calibration;
 pub mod channel;
 pub mod device;
 pub mod experiment;
+pub mod expression;
 pub mod instruction;
 pub mod utils;
+pub mod waveform;
 
 // ToDo: restrict public API access to the following functions:
 //  - mutable field accessors
+pub use calibration::*;
 pub use channel::*;
 pub use device::*;
 pub use experiment::*;
+pub use expression::*;
 pub use instruction::*;
 pub use utils::*;
+pub use waveform::*;
 
 #[pymodule]
 fn nicompiler_backend(_py: Python, m: &PyModule) -> PyResult<()> {
@@ -1,9 +1,3 @@
-mod instruction;
-mod channel;
-mod device;
-mod experiment;
-mod utils;
-
 fn main() {
     println!("Hello!");
 }
\ No newline at end of file
@@ -0,0 +1,470 @@
+//! Open extension point for instruction kinds defined outside of this crate.
+//!
+//! Built-in instruction types are variants of the closed [`InstrType`] enum. Crates depending on
+//! `nicompiler_backend` (including `niexpctrl_backend`) can add their own kinds by implementing the
+//! [`Waveform`] trait and, optionally, registering a factory with [`register_waveform`] so that
+//! instructions can be created by name from an argument dictionary (which is how they are reached from python).
+//!
+//! Custom waveforms are stored in [`Instruction`]s of type `InstrType::CUSTOM`, so `BaseChannel::compile`
+//! (including merging of equal adjacent segments) and `BaseChannel::fill_signal_nsamps` treat them like built-ins.
+//!
+//! # Example
+//!
+//! ```
+//! use nicompiler_backend::*;
+//! use std::any::Any;
+//! use std::fmt;
+//! use std::hash::Hasher;
+//!
+//! /// Square wave toggling between `low` and `high` with the given period
+//! #[derive(PartialEq)]
+//! struct Square { period: f64, low: f64, high: f64 }
+//!
+//! impl fmt::Display for Square {
+//!     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!         write!(f, "square(period={}, low={}, high={})", self.period, self.low, self.high)
+//!     }
+//! }
+//! impl Waveform for Square {
+//!     fn kind(&self) -> &str { "square" }
+//!     fn required_args() -> &'static [&'static str] { &["period"] }
+//!     fn eval_inplace(&self, t_arr: &mut ndarray::ArrayViewMut1<f64>) {
+//!         t_arr.map_inplace(|t| {
+//!             *t = if (*t / self.period).fract() < 0.5 { self.high } else { self.low }
+//!         });
+//!     }
+//!     fn as_any(&self) -> &dyn Any { self }
+//!     fn dyn_eq(&self, other: &dyn Waveform) -> bool { waveform_eq(self, other) }
+//!     fn dyn_hash(&self, state: &mut dyn Hasher) {
+//!         for val in [self.period, self.low, self.high] {
+//!             state.write_u64(val.to_bits());
+//!         }
+//!     }
+//! }
+//!
+//! register_waveform("square", |args| Square {
+//!     period: args["period"],
+//!     low: *args.get("low").unwrap_or(&0.0),
+//!     high: *args.get("high").unwrap_or(&1.0),
+//! });
+//!
+//! let mut args = InstrArgs::new();
+//! args.insert("period".to_string(), 1.0);
+//! let instr = Instruction::new_custom("square", args);
+//! assert_eq!(instr.eval_point(0.25), 1.0);
+//! assert_eq!(instr.eval_point(0.75), 0.0);
+//! ```
+//!
+//! Python callables are wrapped by the built-in [`PyFuncWaveform`], which is not registered since
+//! it can't be built from an argument dictionary. If the callable fails, the python exception is kept and
+//! retrieved with [`take_pyfunc_error`], so the python-facing methods can re-raise it.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock, RwLock};
+
+use numpy::PyReadonlyArray1;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::AsPyPointer;
+
+use crate::instruction::*;
+
+/// Behavior of an instruction kind defined outside of the built-in [`InstrType`] variants.
+///
+/// A `Waveform` object is one concrete waveform, i.e. it holds its own parameters.
+/// It is shared between threads (the streamer evaluates instructions in worker threads), hence `Send + Sync`.
+pub trait Waveform: fmt::Display + Send + Sync + 'static {
+    /// Name of the instruction kind, the same as the registry key if the kind is registered
+    fn kind(&self) -> &str;
+
+    /// Arguments which must be present in the argument dictionary passed to the registered factory.
+    /// [`build_waveform`] checks them before calling the factory, so the factory may index them directly.
+    fn required_args() -> &'static [&'static str]
+    where
+        Self: Sized;
+
+    /// Replaces the time values in `t_arr` with the waveform values, same as [`Instruction::eval_inplace`]
+    fn eval_inplace(&self, t_arr: &mut ndarray::ArrayViewMut1<f64>);
+
+    /// Evaluates the waveform at a single time point
+    fn eval_point(&self, t: f64) -> f64 {
+        let mut t_arr = ndarray::array![t];
+        self.eval_inplace(&mut t_arr.view_mut());
+        t_arr[0]
+    }
+
+    /// Upcast used for comparing waveforms of the same concrete type, see [`waveform_eq`]
+    fn as_any(&self) -> &dyn Any;
+
+    /// Equality with another waveform. `BaseChannel::compile` merges adjacent segments of equal instructions,
+    /// so this should only be true if both waveforms produce identical values.
+    fn dyn_eq(&self, other: &dyn Waveform) -> bool;
+
+    /// Feeds the waveform parameters into `state`, consistent with [`Waveform::dyn_eq`]
+    fn dyn_hash(&self, state: &mut dyn Hasher);
+}
+
+impl PartialEq for dyn Waveform {
+    fn eq(&self, other: &Self) -> bool {
+        self.dyn_eq(other)
+    }
+}
+
+impl Hash for dyn Waveform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind().hash(state);
+        self.dyn_hash(state);
+    }
+}
+
+/// Implementation of [`Waveform::dyn_eq`] for waveform types which implement `PartialEq`:
+/// waveforms are equal if they have the same concrete type and compare equal.
+pub fn waveform_eq<W: Waveform + PartialEq>(this: &W, other: &dyn Waveform) -> bool {
+    other
+        .as_any()
+        .downcast_ref::<W>()
+        .is_some_and(|other| this == other)
+}
+
+/// Builds a waveform from an argument dictionary
+pub type WaveformFactory<W> = fn(&InstrArgs) -> W;
+
+/// Type-erased [`WaveformFactory`] as stored in the registry
+type BoxedFactory = Box<dyn Fn(&InstrArgs) -> Box<dyn Waveform> + Send + Sync>;
+
+/// Registry entry of a waveform kind
+struct WaveformKind {
+    required_args: &'static [&'static str],
+    factory: BoxedFactory,
+}
+
+fn registry() -> &'static RwLock<HashMap<String, WaveformKind>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, WaveformKind>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Registers a waveform kind under `name`, so that [`Instruction::new_custom`] can create it.
+///
+/// The [`Waveform::required_args`] of `W` are checked to be present before `factory` is called, the same way
+/// [`Instruction::new`] checks built-in instruction types. Registering an existing name replaces the previous entry.
+pub fn register_waveform<W: Waveform>(name: &str, factory: WaveformFactory<W>) {
+    registry().write().unwrap().insert(
+        name.to_string(),
+        WaveformKind {
+            required_args: W::required_args(),
+            factory: Box::new(move |args| Box::new(factory(args))),
+        },
+    );
+}
+
+/// Names of all registered waveform kinds, sorted alphabetically
+pub fn registered_waveforms() -> Vec<String> {
+    let mut names: Vec<String> = registry().read().unwrap().keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Required arguments of a registered waveform kind, or `None` if `name` is not registered
+pub fn waveform_required_args(name: &str) -> Option<Vec<String>> {
+    registry()
+        .read()
+        .unwrap()
+        .get(name)
+        .map(|kind| kind.required_args.iter().map(|arg| arg.to_string()).collect())
+}
+
+/// Creates a waveform of the registered kind `name` from `args`.
+///
+/// Returns an error if the kind is not registered or a required argument is missing.
+pub fn build_waveform(name: &str, args: &InstrArgs) -> Result<Box<dyn Waveform>, String> {
+    let registry = registry().read().unwrap();
+    let kind = registry.get(name).ok_or_else(|| {
+        let mut known: Vec<&String> = registry.keys().collect();
+        known.sort();
+        format!("Waveform kind \"{}\" is not registered. Registered kinds: {:?}", name, known)
+    })?;
+    for key in kind.required_args.iter() {
+        if !args.contains_key(*key) {
+            return Err(format!("Expected waveform kind {} to contain key {}", name, key));
+        }
+    }
+    Ok((kind.factory)(args))
+}
+
+/// Waveform computed by a python callable `f(t: np.ndarray) -> np.ndarray`.
+///
+/// The callable receives the absolute sample times of the instruction segment and has to return
+/// a one-dimensional `float64` array of the same length. It is called with the GIL held, from whichever thread
+/// is evaluating the instruction (the streamer calls it from its worker threads, so the GIL must not be
+/// held by the thread waiting on them).
+///
+/// Evaluation panics if the callable raises or returns a wrongly typed or shaped array. Before panicking,
+/// the python exception is stashed on the waveform, see [`take_pyfunc_error`].
+///
+/// Equality is python object identity: two instructions wrapping the same function object are merged
+/// by `BaseChannel::compile` when adjacent, while distinct (even identical-looking) functions are not.
+pub struct PyFuncWaveform {
+    func: PyObject,
+    repr: String,
+    error: Mutex<Option<PyErr>>,
+}
+
+impl PyFuncWaveform {
+    /// Wraps `func`, returning an error if it is not callable
+    pub fn new(py: Python, func: PyObject) -> Result<Self, String> {
+        let func_ref = func.as_ref(py);
+        let repr = func_ref
+            .repr()
+            .map(|repr| repr.to_string())
+            .unwrap_or_else(|_| "<python callable>".to_string());
+        if !func_ref.is_callable() {
+            return Err(format!("Expected a python callable, got {}", repr));
+        }
+        Ok(Self { func, repr, error: Mutex::new(None) })
+    }
+
+    /// Takes the exception of the first failed evaluation since the last call, if any
+    pub fn take_error(&self) -> Option<PyErr> {
+        self.error.lock().unwrap().take()
+    }
+
+    /// Calls the python function on `t_arr` and writes the returned values back into it.
+    ///
+    /// Returns the exception raised by the function as is, a `TypeError` if the returned value is not
+    /// a 1D `float64` array and a `ValueError` if its length doesn't match `t_arr`.
+    pub fn call(&self, py: Python, t_arr: &mut ndarray::ArrayViewMut1<f64>) -> PyResult<()> {
+        let t_py = numpy::PyArray::from_array(py, &t_arr.view());
+        let ret = self.func.call1(py, (t_py,))?;
+        let ret_ref = ret.as_ref(py);
+        let vals: PyReadonlyArray1<f64> = ret_ref.extract().map_err(|_| {
+            let describe = |attr: &str| {
+                ret_ref
+                    .getattr(attr)
+                    .map(|val| val.to_string())
+                    .unwrap_or_else(|_| "?".to_string())
+            };
+            PyTypeError::new_err(format!(
+                "Python function {} must return a 1D numpy array of float64, got {} with dtype {} and shape {}",
+                self.repr,
+                ret_ref.get_type().name().unwrap_or("?"),
+                describe("dtype"),
+                describe("shape"),
+            ))
+        })?;
+        let vals = vals.as_array();
+        if vals.len() != t_arr.len() {
+            return Err(PyValueError::new_err(format!(
+                "Python function {} returned {} values for {} time points",
+                self.repr,
+                vals.len(),
+                t_arr.len()
+            )));
+        }
+        t_arr.assign(&vals);
+        Ok(())
+    }
+}
+
+impl fmt::Display for PyFuncWaveform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "pyfunc({})", self.repr)
+    }
+}
+
+impl Waveform for PyFuncWaveform {
+    fn kind(&self) -> &str {
+        "pyfunc"
+    }
+
+    fn required_args() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Panics with the python error message if the call fails or returns a wrongly shaped or typed array.
+    /// The exception itself is kept for [`take_pyfunc_error`] (only the first one until it is taken).
+    fn eval_inplace(&self, t_arr: &mut ndarray::ArrayViewMut1<f64>) {
+        if let Err(err) = Python::with_gil(|py| self.call(py, t_arr)) {
+            let msg = format!("Python function {} failed: {}", self.repr, err);
+            self.error.lock().unwrap().get_or_insert(err);
+            panic!("{}", msg);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn Waveform) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<PyFuncWaveform>()
+            .is_some_and(|other| self.func.is(&other.func))
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        state.write_usize(self.func.as_ptr() as usize);
+    }
+}
+
+/// Takes the exceptions stashed by failed [`PyFuncWaveform`]s anywhere in the instruction tree of `instr`,
+/// returning the first one
+pub fn take_pyfunc_error(instr: &Instruction) -> Option<PyErr> {
+    match instr.data.as_deref() {
+        Some(InstrData::Custom(waveform)) => waveform
+            .as_any()
+            .downcast_ref::<PyFuncWaveform>()
+            .and_then(|waveform| waveform.take_error()),
+        Some(InstrData::Children(children)) => children
+            .iter()
+            .fold(None, |first, child| first.or(take_pyfunc_error(child))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::instruction::*;
+    use crate::waveform::*;
+
+    #[derive(PartialEq)]
+    struct Triangle {
+        period: f64,
+    }
+    impl fmt::Display for Triangle {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "triangle(period={})", self.period)
+        }
+    }
+    impl Waveform for Triangle {
+        fn kind(&self) -> &str {
+            "test_triangle"
+        }
+        fn required_args() -> &'static [&'static str] {
+            &["period"]
+        }
+        fn eval_inplace(&self, t_arr: &mut ndarray::ArrayViewMut1<f64>) {
+            t_arr.map_inplace(|t| *t = 1.0 - 2.0 * ((*t / self.period).fract() - 0.5).abs() * 2.0);
+        }
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        fn dyn_eq(&self, other: &dyn Waveform) -> bool {
+            waveform_eq(self, other)
+        }
+        fn dyn_hash(&self, state: &mut dyn Hasher) {
+            state.write_u64(self.period.to_bits());
+        }
+    }
+
+    fn register() {
+        register_waveform("test_triangle", |args| Triangle { period: args["period"] });
+    }
+
+    fn triangle(period: f64) -> Instruction {
+        let mut args = InstrArgs::new();
+        args.insert("period".to_string(), period);
+        Instruction::new_custom("test_triangle", args)
+    }
+
+    #[test]
+    fn registry() {
+        register();
+        assert!(registered_waveforms().contains(&"test_triangle".to_string()));
+        assert_eq!(waveform_required_args("test_triangle"), Some(vec!["period".to_string()]));
+
+        let instr = triangle(2.0);
+        assert_eq!(instr.eval_point(1.0), 1.0);
+        assert_eq!(instr.eval_point(0.5), 0.0);
+        assert_eq!(
+            instr.to_string(),
+            "[CUSTOM, {period: 2}, triangle(period=2)]"
+        );
+        assert!(build_waveform("test_unknown", &InstrArgs::new())
+            .err()
+            .unwrap()
+            .contains("not registered"));
+        assert!(build_waveform("test_triangle", &InstrArgs::new())
+            .err()
+            .unwrap()
+            .contains("contain key period"));
+    }
+
+    #[test]
+    fn equality() {
+        register();
+        assert!(triangle(1.0) == triangle(1.0));
+        assert!(triangle(1.0) != triangle(2.0));
+        assert!(triangle(1.0) != Instruction::new_const(1.0));
+
+        let hash = |instr: &Instruction| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            match instr.data.as_deref() {
+                Some(InstrData::Custom(waveform)) => waveform.hash(&mut hasher),
+                _ => unreachable!(),
+            };
+            hasher.finish()
+        };
+        assert_eq!(hash(&triangle(1.0)), hash(&triangle(1.0)));
+    }
+
+    #[test]
+    fn pyfunc_errors() {
+        use crate::experiment::*;
+        use pyo3::exceptions::PyZeroDivisionError;
+        use pyo3::types::PyDict;
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let funcs = PyDict::new(py);
+            py.run(
+                "import numpy as np\n\
+                 def good(t): return 2 * t\n\
+                 def raises(t): raise ZeroDivisionError('test error')\n\
+                 def wrong_dtype(t): return t.astype(np.int64)\n\
+                 def wrong_len(t): return t[:-1]\n",
+                None,
+                Some(funcs),
+            )
+            .unwrap();
+
+            let exp_with = |name: &str| {
+                let mut exp = Experiment::new();
+                exp.add_ao_device("Dev1", 1e3);
+                exp.add_ao_channel("Dev1", 0, 0.0);
+                let func = funcs.get_item(name).unwrap().to_object(py);
+                exp.pyfunc(py, "Dev1", "ao0", 0.0, 10e-3, func, false).unwrap();
+                exp
+            };
+
+            // The function is only called when samples are computed
+            let calc = |exp: &mut Experiment| {
+                exp.compile(None).unwrap();
+                exp.channel_calc_signal_nsamps("Dev1", "ao0", 0.0, 5e-3, 5)
+            };
+
+            let signal = calc(&mut exp_with("good")).unwrap();
+            for (i, val) in signal.iter().enumerate() {
+                assert!((val - 2e-3 * i as f64).abs() < 1e-12);
+            }
+
+            // The original python exception is raised, not a panic
+            let mut exp = exp_with("raises");
+            let err = calc(&mut exp).err().unwrap();
+            assert!(err.is_instance_of::<PyZeroDivisionError>(py));
+            assert!(err.to_string().contains("test error"));
+            // and is only reported once
+            assert!(exp.take_pyfunc_error().is_none());
+
+            let err = calc(&mut exp_with("wrong_dtype")).err().unwrap();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+            assert!(err.to_string().contains("float64"));
+
+            let err = calc(&mut exp_with("wrong_len")).err().unwrap();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            assert!(err.to_string().contains("values for"));
+        });
+    }
+}
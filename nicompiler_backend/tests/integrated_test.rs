@@ -26,7 +26,7 @@ use nicompiler_backend::BaseExperiment;
 
 //     // PXI1Slot3/ao0 starts with a 1s-long 7Hz sine wave with offset 1
 //     // and unit amplitude, zero phase. Does not keep its value.
-//     exp.sine("PXI1Slot3", "ao0", 0., 1., false, 7., None, None, Some(1.));
+//     exp.sine("PXI1Slot3", "ao0", 0., 1., false, 7., None, None, Some(1.), None);
 //     // Ends with a half-second long 1V constant signal which returns to zero
 //     exp.constant("PXI1Slot3", "ao0", 9., 0.5, 1., false);
 
@@ -55,7 +55,7 @@ fn empty_compile_do() {
     let mut exp = Experiment::new();
     // Define devices and associated channels
     exp.add_do_device("PXI1Slot6", 1e7);
-    exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
+    exp.add_do_channel("PXI1Slot6", 0, 0, false);
 
     exp.compile(false);
     println!("Compiled!");
@@ -66,25 +66,25 @@ fn empty_compile_ao_do_mix() {
     let mut exp = Experiment::new();
     // Define devices and associated channels
     exp.add_do_device("PXI1Slot6", 1e7);
-    exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
+    exp.add_do_channel("PXI1Slot6", 0, 0, false);
     exp.add_ao_device("PXI1Slot3", 1e6);
     exp.add_ao_channel("PXI1Slot3", 0, 0.);
 
-    exp.sine("PXI1Slot3", "ao0", 0., 1., false, 10., None, None, None);
+    exp.sine("PXI1Slot3", "ao0", 0., 1., false, 10., None, None, None, None);
     exp.compile(false);
     println!("Compiled!");
 }
 
 #[test]
-#[should_panic(expected="There is no channel with streamable=false, editable=false")]
+#[should_panic(expected="Device PXI1Slot6 has no compiled channels")]
 fn empty_calc_signal() {
     let mut exp = Experiment::new();
     // Define devices and associated channels
     exp.add_do_device("PXI1Slot6", 1e7);
-    exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
+    exp.add_do_channel("PXI1Slot6", 0, 0, false);
 
     exp.compile(false);
-    exp.device_calc_signal_nsamps("PXI1Slot6", 0, 10, 100, false, false);
+    exp.device_calc_signal_nsamps("PXI1Slot6", 0, 10, 100, false);
 }
 
 #[test]
@@ -92,15 +92,15 @@ fn test_reset_tick() {
     let mut exp = Experiment::new();
     // Define devices and associated channels
     exp.add_do_device("PXI1Slot6", 10.);
-    exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
-    exp.add_do_channel("PXI1Slot6", 0, 1, 0.);
+    exp.add_do_channel("PXI1Slot6", 0, 0, false);
+    exp.add_do_channel("PXI1Slot6", 0, 1, false);
 
     exp.high("PXI1Slot6", "port0/line0", 0., 1.);
     exp.go_high("PXI1Slot6", "port0/line1", 0.);
     exp.compile_with_stoptime(5.);
 
     // Calculate from t=0 ~ 5
-    let sig = exp.device_calc_signal_nsamps("PXI1Slot6", 0, 50, 50, false, true);
+    let sig = exp.device_calc_signal_nsamps("PXI1Slot6", 0, 50, 50, false);
     assert!(sig[[0, 9]] == 1. && sig[[0, 10]] == 0.); // go_high takes effect on the tick corresponding to specified time. 
     assert!(sig[[1, 9]] == 1. && sig[[1, 10]] == 1.); 
     
@@ -108,7 +108,7 @@ fn test_reset_tick() {
     // Reset tick happens at the earliest unspecified interval across all channels
     assert!(reset_tick_time == 1.0); 
     exp.compile_with_stoptime(5.);
-    let sig = exp.device_calc_signal_nsamps("PXI1Slot6", 0, 50, 50, false, true);
+    let sig = exp.device_calc_signal_nsamps("PXI1Slot6", 0, 50, 50, false);
     assert!(sig[[0, 9]] == 1. && sig[[0, 10]] == 0.); 
     assert!(sig[[1, 9]] == 1. && sig[[1, 10]] == 0.); // Also zeros channel 1 at t=1
     // println!("{:?}, reset_tick_time={}", sig, reset_tick_time);
@@ -189,7 +189,7 @@ pub trait StreamableDevice: BaseDevice + Sync + Send {
         ));
 
         // Obtain the first signal (optional: from parallel thread), and do first bufwrite
-        let signal = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true, false);
+        let signal = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true);
         timer1.tick_print(&format!("{} calc initial sample chunk", self.name()));
         bufwrite(signal);
         timer1.tick_print(&format!("{} initial bufwrite", self.name()));
@@ -216,7 +216,7 @@ pub trait StreamableDevice: BaseDevice + Sync + Send {
             // Main streaming loop
             while end_pos != seq_len {
                 (start_pos, end_pos) = counter.tick_next();
-                let signal_stream = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true, false);
+                let signal_stream = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true);
                 bufwrite(signal_stream);
                 // FixMe: add timeout = max(1 second, 2*buf_dur) to avoid deadlocks (hardware bug - trigger not connected -> deadlock).
                 //  Also add option to specify WaitInfinitely for advanced cases (external sample clock freezing and external trigger)
@@ -226,7 +226,7 @@ pub trait StreamableDevice: BaseDevice + Sync + Send {
             if nreps > 1 {
                 // If we're on repeat: don't wait for the task to finish, calculate and write the next chunk
                 (start_pos, end_pos) = counter.tick_next();
-                let signal_next_start = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true, false);
+                let signal_next_start = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true);
                 task.wait_until_done(buf_dur * 10.0);
                 timer2.tick_print(&format!("{} end", self.name()));
                 task.stop();
@@ -294,7 +294,7 @@ pub trait StreamableDevice: BaseDevice + Sync + Send {
 
         // Calc and write the initial sample chunk into the buffer
         let (start_pos, end_pos) = stream_bundle.counter.tick_next().unwrap();
-        let samp_arr = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true, false);
+        let samp_arr = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true);
         stream_bundle.write_buf(samp_arr)?;
 
         // FixMe [after Device move to streamer crate]:
@@ -319,7 +319,7 @@ pub trait StreamableDevice: BaseDevice + Sync + Send {
 
         // Main streaming loop
         while let Some((start_pos, end_pos)) = stream_bundle.counter.tick_next() {
-            let samp_arr = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true, false);
+            let samp_arr = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true);
             stream_bundle.write_buf(samp_arr)?;
         }
 
@@ -331,7 +331,7 @@ pub trait StreamableDevice: BaseDevice + Sync + Send {
         } else {
             stream_bundle.counter.reset();
             let (start_pos, end_pos) = stream_bundle.counter.tick_next().unwrap();
-            let samp_arr = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true, false);
+            let samp_arr = self.calc_signal_nsamps(start_pos, end_pos, end_pos - start_pos, true);
 
             stream_bundle.ni_task.wait_until_done(stream_bundle.buf_write_timeout.clone())?;
             stream_bundle.ni_task.stop()?;
@@ -354,23 +354,23 @@ pub trait StreamableDevice: BaseDevice + Sync + Send {
     /// # Behavior
     ///
     /// Depending on the device's `task_type`, the method will:
-    /// * For `TaskType::AO`: Iterate through the compiled, streamable channels and invoke the
-    /// `create_ao_chan` method for each channel.
-    /// * For `TaskType::DO`: Iterate through the compiled, streamable channels and invoke the
-    /// `create_do_chan` method for each channel.
+    /// * For `TaskType::AO`: Iterate through the compiled channels and invoke the
+    /// `create_ao_chan` method for each channel, passing on its output limits.
+    /// * For `TaskType::DO`: Iterate through the ports merged during compilation and invoke the
+    /// `create_do_chan` method for each port.
     ///
     /// The channel names are constructed using the format `/{device_name}/{channel_name}`.
     fn create_task_channels(&self, task: &NiTask) -> Result<(), DAQmxError> {
         match self.task_type() {
             TaskType::AO => {
-                // Require compiled, streamable channels
-                for chan in self.compiled_channels(true, false).iter() {
-                    task.create_ao_chan(&format!("/{}/{}", self.name(), chan.name()))?;
+                for chan in self.ao_channels().values().filter(|chan| chan.is_compiled()) {
+                    let (min_val, max_val) = chan.output_limits().unwrap();
+                    task.create_ao_chan(&format!("/{}/{}", self.name(), chan.name()), min_val, max_val)?;
                 };
             }
             TaskType::DO => {
-                for chan in self.compiled_channels(true, false).iter() {
-                    task.create_do_chan(&format!("/{}/{}", self.name(), chan.name()))?;
+                for port in self.do_ports().values() {
+                    task.create_do_chan(&format!("/{}/{}", self.name(), port.name()))?;
                 };
             }
         };
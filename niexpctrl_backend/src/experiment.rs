@@ -198,9 +198,9 @@ impl Experiment {
                         Ok(()) => err_msg_map.insert(dev_name.to_string(), format!("Unexpected scenario - worker has dropped its report_sender yet returned Ok")),
                     }
                 },
-                Err(_panic_info) => {
+                Err(panic_info) => {
                     // The worker has panicked. Panic info should be contained in the returned object
-                    err_msg_map.insert(dev_name.to_string(), format!("Worker has panicked"))
+                    err_msg_map.insert(dev_name.to_string(), format!("Worker has panicked: {}", panic_msg(&panic_info)))
                 },
             };
 
@@ -364,9 +364,9 @@ impl Experiment {
                         },
                     }
                 },
-                Err(_panic_info) => {
+                Err(panic_info) => {
                     // The worker has panicked
-                    worker_join_err_msgs.insert(dev_name, format!("The worker appears to have panicked"));
+                    worker_join_err_msgs.insert(dev_name, format!("The worker appears to have panicked: {}", panic_msg(&panic_info)));
                 },
             };
         }
@@ -447,9 +447,27 @@ impl Experiment {
 
 impl Drop for Experiment {
     fn drop(&mut self) {
-        let res = self.close_run_();
+        // Same as `close_run()`: the workers may need the GIL to evaluate python function instructions,
+        // so it is released while joining them. Without an interpreter (pure Rust use) there is no GIL to release.
+        let res = if unsafe { pyo3::ffi::Py_IsInitialized() } != 0 {
+            Python::with_gil(|py| py.allow_threads(|| self.close_run_()))
+        } else {
+            self.close_run_()
+        };
         if let Err(msg) = res {
             println!("Error when dropping NIStreamer: {msg}")
         };
     }
 }
+
+/// Extracts the message from the payload of a panicked worker thread
+/// (e.g. a python exception raised by a function instruction evaluated in the worker)
+fn panic_msg(panic_info: &Box<dyn std::any::Any + Send>) -> String {
+    if let Some(msg) = panic_info.downcast_ref::<String>() {
+        msg.clone()
+    } else if let Some(msg) = panic_info.downcast_ref::<&str>() {
+        msg.to_string()
+    } else {
+        "<no panic message>".to_string()
+    }
+}
@@ -69,7 +69,7 @@
 //!
 //! // PXI1Slot3/ao0 starts with a 1s-long 7Hz sine wave with offset 1
 //! // and unit amplitude, zero phase. Does not keep its value.
-//! exp.sine("PXI1Slot3", "ao0", 0., 1., false, 7., None, None, Some(1.));
+//! exp.sine("PXI1Slot3", "ao0", 0., 1., false, 7., None, None, Some(1.), None);
 //! // Ends with a half-second long 1V constant signal which returns to zero
 //! exp.constant("PXI1Slot3", "ao0", 9., 0.5, 1., false);
 //!
@@ -193,22 +193,22 @@ impl Experiment {
 
     fn assert_contains_chan(&self, dev_name: &str, chan_name: &str) -> PyResult<()> {
         let dev = self.get_dev(dev_name)?;
-        if dev.channels().contains_key(chan_name) {
+        if dev.has_chan(chan_name) {
             Ok(())
         } else {
             Err(PyKeyError::new_err(format!(
                 "Device {dev_name} does not contain channel {chan_name}. Registered channels are: {:?}",
-                dev.channels().keys().collect::<Vec<_>>()
+                dev.chan_names()
             )))
         }
     }
-    fn get_chan(&self, dev_name: &str, chan_name: &str) -> PyResult<&Channel> {
+    fn get_chan(&self, dev_name: &str, chan_name: &str) -> PyResult<&dyn BaseChannel> {
         self.assert_contains_chan(dev_name, chan_name)?;
-        Ok(self.devices().get(dev_name).unwrap().channels().get(chan_name).unwrap())
+        Ok(self.devices().get(dev_name).unwrap().chan(chan_name))
     }
-    fn get_chan_mut(&mut self, dev_name: &str, chan_name: &str) -> PyResult<&mut Channel> {
+    fn get_chan_mut(&mut self, dev_name: &str, chan_name: &str) -> PyResult<&mut dyn BaseChannel> {
         self.assert_contains_chan(dev_name, chan_name)?;
-        Ok(self.devices_().get_mut(dev_name).unwrap().channels_().get_mut(chan_name).unwrap())
+        Ok(self.devices_().get_mut(dev_name).unwrap().chan_(chan_name))
     }
 }
 /* impl Device {
@@ -217,10 +217,10 @@ impl Experiment {
     fn assert_contains_chan(&self, name: &str) -> PyResult<()> {
         todo!()
     }
-    fn get_chan(&self, name: &str) -> PyResult<&Channel> {
+    fn get_chan(&self, name: &str) -> PyResult<&dyn BaseChannel> {
         todo!()
     }
-    fn get_chan_mut(&mut self, name: &str) -> PyResult<&mut Channel> {
+    fn get_chan_mut(&mut self, name: &str) -> PyResult<&mut dyn BaseChannel> {
         todo!()
     }
 } */
@@ -302,22 +302,25 @@ impl Experiment {
     // endregion
 
     // region Run control
-    pub fn cfg_run(&mut self, bufsize_ms: f64) -> PyResult<()> {
-        match self.cfg_run_(bufsize_ms) {
+    //  The GIL is released while waiting on the workers: instructions wrapping python functions
+    //  acquire it from the worker threads when evaluated. If one of them failed, its original exception
+    //  is raised instead of the worker error message (see `BaseExperiment::take_pyfunc_error`).
+    pub fn cfg_run(&mut self, py: Python, bufsize_ms: f64) -> PyResult<()> {
+        match py.allow_threads(|| self.cfg_run_(bufsize_ms)) {
             Ok(()) => Ok(()),
-            Err(msg) => Err(PyValueError::new_err(msg)),
+            Err(msg) => Err(self.take_pyfunc_error().unwrap_or_else(|| PyValueError::new_err(msg))),
         }
     }
-    pub fn stream_run(&mut self, calc_next: bool) -> PyResult<()> {
-        match self.stream_run_(calc_next) {
+    pub fn stream_run(&mut self, py: Python, calc_next: bool) -> PyResult<()> {
+        match py.allow_threads(|| self.stream_run_(calc_next)) {
             Ok(()) => Ok(()),
-            Err(msg) => Err(PyRuntimeError::new_err(msg)),
+            Err(msg) => Err(self.take_pyfunc_error().unwrap_or_else(|| PyRuntimeError::new_err(msg))),
         }
     }
-    pub fn close_run(&mut self) -> PyResult<()> {
-        match self.close_run_() {
+    pub fn close_run(&mut self, py: Python) -> PyResult<()> {
+        match py.allow_threads(|| self.close_run_()) {
             Ok(()) => Ok(()),
-            Err(msg) => Err(PyRuntimeError::new_err(msg)),
+            Err(msg) => Err(self.take_pyfunc_error().unwrap_or_else(|| PyRuntimeError::new_err(msg))),
         }
     }
     // endregion
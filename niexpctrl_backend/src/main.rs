@@ -16,8 +16,8 @@ use niexpctrl_backend::*;
     exp.add_ao_channel("PXI1Slot4", 0, 0.);
 
     exp.add_do_device("PXI1Slot6", 1e7);
-    exp.add_do_channel("PXI1Slot6", 0, 0, 0.);
-    exp.add_do_channel("PXI1Slot6", 0, 4, 0.);
+    exp.add_do_channel("PXI1Slot6", 0, 0, false);
+    exp.add_do_channel("PXI1Slot6", 0, 4, false);
 
     // Define synchronization behavior:
     exp.device_cfg_trig("PXI1Slot3", "PXI_Trig0", true);
@@ -31,7 +31,7 @@ use niexpctrl_backend::*;
 
     // PXI1Slot3/ao0 starts with a 1s-long 7Hz sine wave with offset 1
     // and unit amplitude, zero phase. Does not keep its value.
-    exp.sine("PXI1Slot3", "ao0", 0., 1., false, 7., None, None, Some(1.));
+    exp.sine("PXI1Slot3", "ao0", 0., 1., false, 7., None, None, Some(1.), None);
     // Ends with a half-second long 1V constant signal which returns to zero
     exp.constant("PXI1Slot3", "ao0", 9., 0.5, 1., false);
 
@@ -92,7 +92,7 @@ fn main() {
     // Add instructions
     // - Dev2
     streamer.go_constant("Dev2", "ao0", 0.0, 1.0);
-    streamer.sine("Dev2", "ao0", 1.0, 1.0, false, 10.0, Some(1.5), None, None);
+    streamer.sine("Dev2", "ao0", 1.0, 1.0, false, 10.0, Some(1.5), None, None, None);
     streamer.constant("Dev2", "ao0", 3.0, 1.0, -1.0);
     // - Dev3
     streamer.constant("Dev3", "ao0", 0.5, 2.0, 1.0);
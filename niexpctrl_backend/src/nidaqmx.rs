@@ -52,7 +52,7 @@
 //! ```ignore
 //! # use niexpctrl_backend::*;
 //! let task = NiTask::new();
-//! task.create_ao_chan("Dev1/ao0");
+//! task.create_ao_chan("Dev1/ao0", -10.0, 10.0);
 //! task.cfg_sample_clk("", 1000.0, 1000);
 //! // ... other configurations and operations ...
 //! task.start();
@@ -379,7 +379,7 @@ impl NiTask {
         daqmx_call(|| unsafe { DAQmxCfgOutputBuffer(self.handle, buf_size as CUint32) })
     }
 
-    pub fn create_ao_chan(&self, name: &str) -> Result<(), DAQmxError> {
+    pub fn create_ao_chan(&self, name: &str, min_val: f64, max_val: f64) -> Result<(), DAQmxError> {
         let name_cstr = std::ffi::CString::new(name)?;
         let assigned_name_cstr = std::ffi::CString::new("")?;
         daqmx_call(|| unsafe {
@@ -387,8 +387,8 @@ impl NiTask {
                 self.handle,
                 name_cstr.as_ptr(),
                 assigned_name_cstr.as_ptr(),
-                -10.,
-                10.,
+                min_val,
+                max_val,
                 DAQMX_VAL_VOLTS,
                 std::ptr::null(),
             )
@@ -1 +1,2 @@
 from .streamer import NIStreamer
+from . import instr
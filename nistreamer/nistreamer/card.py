@@ -128,13 +128,27 @@ class AOCardProxy(BaseCardProxy):
     def __repr__(self):
         return 'AO card ' + super().__repr__()
 
-    def add_chan(self, chan_idx: int, default_value: float = 0., nickname: str = None, proxy_class=AOChanProxy):
+    def add_chan(
+            self,
+            chan_idx: int,
+            default_value: float = 0.,
+            nickname: str = None,
+            proxy_class=AOChanProxy,
+            min_val: float = -10.,
+            max_val: float = 10.,
+    ):
         # Raw Rust NIStreamer call
         self._streamer.add_ao_channel(
             self.max_name, 
             channel_id=chan_idx,  # FixMe[Rust]: maybe change `channel_id` to `chan_idx`,
             default_value=default_value
         )
+        self._streamer.ao_channel_set_output_limits(
+            dev_name=self.max_name,
+            chan_name=f'ao{chan_idx}',
+            min_val=min_val,
+            max_val=max_val
+        )
         # Instantiate proxy object
         chan_proxy = proxy_class(
             _streamer=self._streamer,
@@ -159,7 +173,7 @@ class DOCardProxy(BaseCardProxy):
             # FixMe[Rust]: maybe change `port_id` to `port_idx`
             #  - idx is associated with "int" - values from 0 to N-1, while "id" is more general
             line_id=line_idx,  # FixMe[Rust]: maybe change `channel_id` to `chan_idx`,
-            default_value=1. if default_value else 0.  # ToDo: remove this hack when AO/DO types are split in Rust backend
+            default_value=default_value
         )
         # Instantiate proxy object
         chan_proxy = proxy_class(
@@ -1,3 +1,4 @@
+import numpy as np
 from niexpctrl_backend import Experiment as RawStreamer  # FixMe[Rust]: rename Experiment to NIStreamer
 
 
@@ -29,6 +30,13 @@ class BaseChanProxy:
             chan_name=self.chan_name
         )
 
+    @property
+    def reset_val(self):
+        return self._streamer.channel_reset_value(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name
+        )
+
     @property
     def nickname(self):
         if self._nickname is not None:
@@ -82,6 +90,87 @@ class BaseChanProxy:
             chan_name=self.chan_name
         )
 
+    def remove_instr(self, t):
+        """Remove the instruction starting at or covering time `t`"""
+        self._streamer.channel_remove_instr(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t
+        )
+
+    def replace_instr(self, t, instr_type: str, **args):
+        """Replace the instruction starting at or covering time `t`, keeping its start and end.
+
+        The new instruction is given by its type name and arguments, e.g.
+        `replace_instr(t, 'LINRAMP', start_val=0, end_val=1, start_time=t0, end_time=t1)`.
+        Time arguments are absolute, same as for the instruction being replaced.
+        """
+        self._streamer.channel_replace_instr(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            instr_type=instr_type.upper(),
+            args={name: float(val) for name, val in args.items()}
+        )
+
+    def move_instr(self, t, new_t):
+        """Move the instruction starting at or covering time `t` such that it starts at `new_t`"""
+        self._streamer.channel_move_instr(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            new_t=new_t
+        )
+
+    def instr_list(self, t_start=None, t_end=None):
+        """Instructions programmed on the channel which overlap with [t_start, t_end].
+
+        Returns a list of dicts with keys `start_time`, `end_time`, `keep_val`, `type` and `args`,
+        plus `children` for composite instructions (nested dicts with `type`, `args` and `children`).
+        `end_time` and `keep_val` are `None` for "go-something" instructions without a specified duration.
+        """
+        return self._streamer.channel_instr_list(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            start_time=t_start if t_start is not None else 0.0,
+            end_time=t_end if t_end is not None else np.inf,
+        )
+
+    def compiled_segments(self, t_start=None, t_end=None):
+        """Compiled segment table of the channel, restricted to segments overlapping with [t_start, t_end].
+
+        Returns a list of dicts with keys `start_time`, `end_time`, `type` and `args`, plus `children` for composite instructions as in `instr_list()`.
+        Unlike `instr_list()`, segments cover the whole run including padding,
+        and sines with local/continuous phase or instructions starting from the current value are shown resolved.
+        """
+        return self._streamer.channel_compiled_segments(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            start_time=t_start if t_start is not None else 0.0,
+            end_time=t_end if t_end is not None else np.inf,
+        )
+
+    def value_bounds(self, t_start=None, t_end=None):
+        t_start = t_start if t_start is not None else 0.0
+        t_end = t_end if t_end is not None else self.last_instr_end_time()
+        return self._streamer.channel_value_bounds(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            start_time=t_start,
+            end_time=t_end,
+        )
+
+    def output_bounds(self, t_start=None, t_end=None):
+        # Same as `value_bounds()`, but in volts as returned by `calc_signal()` for calibrated channels
+        t_start = t_start if t_start is not None else 0.0
+        t_end = t_end if t_end is not None else self.last_instr_end_time()
+        return self._streamer.channel_output_bounds(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            start_time=t_start,
+            end_time=t_end,
+        )
+
 
 class AOChanProxy(BaseChanProxy):
     def __init__(
@@ -106,6 +195,104 @@ class AOChanProxy(BaseChanProxy):
     def chan_name(self):
         return f'ao{self.chan_idx}'
 
+    @property
+    def max_slew_rate(self):
+        return self._streamer.channel_max_slew_rate(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name
+        )
+
+    @max_slew_rate.setter
+    def max_slew_rate(self, rate):
+        self._streamer.channel_set_max_slew_rate(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            max_slew_rate=rate
+        )
+
+    @property
+    def unit(self):
+        return self._streamer.channel_unit(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name
+        )
+
+    def set_linear_calibration(self, unit: str, gain: float, offset: float = 0.):
+        """volts = gain * value + offset"""
+        self._streamer.ao_channel_set_linear_calibration(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            unit=unit,
+            gain=gain,
+            offset=offset
+        )
+
+    def set_poly_calibration(self, unit: str, coeffs):
+        """volts = coeffs[0] + coeffs[1] * value + coeffs[2] * value**2 + ...
+
+        Note the ascending order of powers - reverse the output of `numpy.polyfit()`.
+        """
+        self._streamer.ao_channel_set_poly_calibration(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            unit=unit,
+            coeffs=list(coeffs)
+        )
+
+    def set_table_calibration(self, unit: str, values, volts):
+        """Linear interpolation between measured points, values outside of the measured range are rejected"""
+        self._streamer.ao_channel_set_table_calibration(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            unit=unit,
+            input=list(values),
+            output=list(volts)
+        )
+
+    def load_calibration_csv(self, unit: str, path: str):
+        """Same as `set_table_calibration()` with points from a two-column CSV file (value, volts)"""
+        self._streamer.ao_channel_load_calibration_csv(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            unit=unit,
+            path=str(path)
+        )
+
+    def clear_calibration(self):
+        self._streamer.ao_channel_clear_calibration(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name
+        )
+
+    @property
+    def output_limits(self):
+        return self._streamer.channel_output_limits(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name
+        )
+
+    @output_limits.setter
+    def output_limits(self, limits):
+        min_val, max_val = limits
+        self._streamer.ao_channel_set_output_limits(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            min_val=min_val,
+            max_val=max_val
+        )
+
+    @property
+    def reset_val(self):
+        return BaseChanProxy.reset_val.fget(self)
+
+    @reset_val.setter
+    def reset_val(self, val):
+        self._streamer.ao_channel_set_reset_value(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            reset_value=val
+        )
+
     def const(self, t, dur, val):
         self._streamer.constant(
             dev_name=self._card_max_name,
@@ -124,7 +311,7 @@ class AOChanProxy(BaseChanProxy):
             value=val,
         )
 
-    def sine(self, t, dur, amp, freq, phase=0, dc_offs=0, keep_val=False):
+    def sine(self, t, dur, amp, freq, phase=0, dc_offs=0, keep_val=False, time_ref=None):
         self._streamer.sine(
             dev_name=self._card_max_name,
             chan_name=self.chan_name,
@@ -136,10 +323,25 @@ class AOChanProxy(BaseChanProxy):
             # FixMe[Rust]: better to use 0.0 instead of None for default. Is it conveninient in Rust?
             dc_offset=dc_offs if dc_offs != 0 else None,  # FixMe[Rust]: better to use 0.0 instead of None for default
             keep_val=keep_val,
+            time_ref=time_ref,
+        )
+        return dur
+
+    def sine_from_current(self, t, dur, amp, freq, phase=0, keep_val=False, time_ref=None):
+        self._streamer.sine_from_current(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            amplitude=amp,
+            freq=freq,
+            phase=phase if phase != 0 else None,
+            keep_val=keep_val,
+            time_ref=time_ref,
         )
         return dur
     
-    def go_sine(self, t, amp, freq, phase=0, dc_offs=0):
+    def go_sine(self, t, amp, freq, phase=0, dc_offs=0, time_ref=None):
         self._streamer.go_sine(
             dev_name=self._card_max_name,
             chan_name=self.chan_name,
@@ -149,8 +351,45 @@ class AOChanProxy(BaseChanProxy):
             phase=phase if phase != 0 else None,
             # FixMe[Rust]: better to use 0.0 instead of None for default. Is it conveninient in Rust?
             dc_offset=dc_offs if dc_offs != 0 else None,  # FixMe[Rust]: better to use 0.0 instead of None for default
+            time_ref=time_ref,
         )
 
+    def chirp(self, t, dur, amp, f_start, f_end, exponential=False, phase=0, dc_offs=0, keep_val=False):
+        self._streamer.chirp(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            keep_val=keep_val,
+            f_start=f_start,
+            f_end=f_end,
+            exponential=exponential,
+            amplitude=amp,
+            phase=phase if phase != 0 else None,
+            dc_offset=dc_offs if dc_offs != 0 else None,
+        )
+        return dur
+
+    def pulse(self, t, dur, amp, window='gauss', freq=None, phase=0, dc_offs=0, sigma=None,
+              rise_time=None, fall_time=None, keep_val=False, time_ref=None):
+        self._streamer.pulse(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            keep_val=keep_val,
+            window=window,
+            amplitude=amp,
+            freq=freq,
+            phase=phase if phase != 0 else None,
+            dc_offset=dc_offs if dc_offs != 0 else None,
+            sigma=sigma,
+            rise_time=rise_time,
+            fall_time=fall_time,
+            time_ref=time_ref,
+        )
+        return dur
+
     def linramp(self, t, dur, start_val, end_val, keep_val=True):
         self._streamer.linramp(
             dev_name=self._card_max_name,
@@ -163,6 +402,211 @@ class AOChanProxy(BaseChanProxy):
         )
         return dur
 
+    def linramp_from_current(self, t, dur, end_val, keep_val=True):
+        self._streamer.linramp_from_current(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            end_val=end_val,
+            keep_val=keep_val,
+        )
+        return dur
+
+    def smooth_ramp(self, t, dur, start_val, end_val, shape='minjerk', steepness=None, keep_val=True):
+        self._streamer.smooth_ramp(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            start_val=start_val,
+            end_val=end_val,
+            shape=shape,
+            steepness=steepness,
+            keep_val=keep_val,
+        )
+        return dur
+
+    def expramp(self, t, dur, start_val, end_val, keep_val=True):
+        self._streamer.expramp(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            start_val=start_val,
+            end_val=end_val,
+            keep_val=keep_val,
+        )
+        return dur
+
+    def expramp_tau(self, t, dur, start_val, target_val, tau, keep_val=True):
+        self._streamer.expramp_tau(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            start_val=start_val,
+            target_val=target_val,
+            tau=tau,
+            keep_val=keep_val,
+        )
+        return dur
+
+    def logramp(self, t, dur, start_val, end_val, tau, keep_val=True):
+        self._streamer.logramp(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            start_val=start_val,
+            end_val=end_val,
+            tau=tau,
+            keep_val=keep_val,
+        )
+        return dur
+
+    def powramp(self, t, dur, start_val, end_val, exponent, keep_val=True):
+        self._streamer.powramp(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            start_val=start_val,
+            end_val=end_val,
+            exponent=exponent,
+            keep_val=keep_val,
+        )
+        return dur
+
+    def expr(self, t, dur, expr, keep_val=False, **params):
+        self._streamer.expr(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            expr=expr,
+            params={name: float(val) for name, val in params.items()},
+            keep_val=keep_val,
+        )
+        return dur
+
+    def custom(self, t, dur, kind, keep_val=False, **args):
+        self._streamer.custom(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            kind=kind,
+            args={name: float(val) for name, val in args.items()},
+            keep_val=keep_val,
+        )
+        return dur
+
+    def add_instr(self, t, dur, spec, keep_val=False):
+        """Plays an instruction tree built with the `nistreamer.instr` helpers,
+        e.g. `instr.sum_(instr.linramp(...), instr.sine(...))` for a sine riding on a ramp.
+
+        Time arguments inside `spec` are absolute.
+        """
+        self._streamer.add_instr_spec(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            spec=spec,
+            keep_val=keep_val,
+        )
+        return dur
+
+    def pyfunc(self, t, dur, func, keep_val=False):
+        """Plays `func(t)` - a function mapping a numpy array of absolute times [s]
+        to a float64 array of output values of the same length.
+
+        `func` is called lazily, during compilation and streaming.
+        """
+        self._streamer.pyfunc(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            func=func,
+            keep_val=keep_val,
+        )
+        return dur
+
+    def arb(self, t, samples, samp_rate, interp='linear', keep_val=True):
+        samples = np.ascontiguousarray(samples, dtype=np.float64)
+        self._streamer.arb(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            samples=samples,
+            samp_rate=samp_rate,
+            interp=interp,
+            keep_val=keep_val,
+        )
+        return len(samples) / samp_rate
+
+    def pwl(self, t, breakpoints, keep_val=True):
+        self._streamer.pwl(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            breakpoints=[(float(bp_t), float(bp_val)) for bp_t, bp_val in breakpoints],
+            keep_val=keep_val,
+        )
+        return breakpoints[-1][0]
+
+    def spline(self, t, breakpoints, keep_val=True):
+        self._streamer.spline(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            breakpoints=[(float(bp_t), float(bp_val)) for bp_t, bp_val in breakpoints],
+            keep_val=keep_val,
+        )
+        return breakpoints[-1][0]
+
+    def poly(self, t, dur, coefs, keep_val=False):
+        self._streamer.poly(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            coefs=[float(coef) for coef in coefs],
+            keep_val=keep_val,
+        )
+        return dur
+
+    def fourier(self, t, dur, freq, amps, phases=None, dc_offs=0, keep_val=False):
+        self._streamer.fourier(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            keep_val=keep_val,
+            freq=freq,
+            amplitudes=[float(amp) for amp in amps],
+            phases=[float(phase) for phase in phases] if phases is not None else None,
+            dc_offset=dc_offs if dc_offs != 0 else None,
+        )
+        return dur
+
+    def noise(self, t, dur, amp, seed, gaussian=False, bandwidth=None, dc_offs=0, keep_val=False):
+        self._streamer.noise(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            duration=dur,
+            keep_val=keep_val,
+            amplitude=amp,
+            seed=seed,
+            gaussian=gaussian,
+            bandwidth=bandwidth,
+            dc_offset=dc_offs if dc_offs != 0 else None,
+        )
+        return dur
+
 
 class DOChanProxy(BaseChanProxy):
     def __init__(
@@ -188,12 +632,19 @@ class DOChanProxy(BaseChanProxy):
 
     @property
     def default_val(self):
-        float_val = self._streamer.chan_get_default_val(
+        return bool(BaseChanProxy.default_val.fget(self))
+
+    @property
+    def reset_val(self):
+        return bool(BaseChanProxy.reset_val.fget(self))
+
+    @reset_val.setter
+    def reset_val(self, val: bool):
+        self._streamer.do_channel_set_reset_value(
             dev_name=self._card_max_name,
-            chan_name=self.chan_name
+            chan_name=self.chan_name,
+            reset_value=val
         )
-        # ToDo: remove this hack when AO/DO types are split in Rust backend
-        return True if float_val > 0.5 else False
 
     def go_high(self, t):
         self._streamer.go_high(
@@ -226,3 +677,18 @@ class DOChanProxy(BaseChanProxy):
             duration=dur
         )
         return dur
+
+    def pulse_train(self, t, period, duty=0.5, dur=None, phase=0, count=None):
+        if dur is None and count is None:
+            raise ValueError('Pulse train needs either `dur` or `count`')
+        self._streamer.pulse_train(
+            dev_name=self._card_max_name,
+            chan_name=self.chan_name,
+            t=t,
+            period=period,
+            duty=duty,
+            duration=dur,
+            phase=phase if phase != 0 else None,
+            count=count,
+        )
+        return dur if dur is not None else (count - phase) * period
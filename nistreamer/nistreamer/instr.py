@@ -0,0 +1,64 @@
+"""Builders for instruction trees passed to `AOChanProxy.add_instr()`.
+
+Each function returns a plain dict `{'type': ..., 'args': {...}, 'children': [...]}`,
+the same layout as the `type`, `args` and `children` keys of the `BaseChanProxy.instr_list()` entries,
+so a reported instruction with scalar arguments can be passed back to `add_instr()`.
+Time arguments are absolute, for example a sine riding on a ramp between `t0` and `t1`:
+
+    ao.add_instr(t0, t1 - t0, instr.sum_(
+        instr.linramp(0., 1., t0, t1),
+        instr.sine(freq=1e3, amp=0.1),
+    ))
+"""
+
+
+def instr(instr_type: str, **args):
+    """Any instruction type with scalar arguments, e.g. `instr('expramp', start_val=1, end_val=2, ...)`"""
+    return {'type': instr_type.upper(), 'args': {name: float(val) for name, val in args.items()}}
+
+
+def const(val):
+    return instr('CONST', value=val)
+
+
+def sine(freq, amp=1., phase=0., dc_offs=0.):
+    return instr('SINE', freq=freq, amplitude=amp, phase=phase, offset=dc_offs)
+
+
+def linramp(start_val, end_val, start_time, end_time):
+    return instr('LINRAMP', start_val=start_val, end_val=end_val, start_time=start_time, end_time=end_time)
+
+
+def _composite(instr_type, children, **args):
+    spec = instr(instr_type, **args)
+    spec['children'] = list(children)
+    return spec
+
+
+def sum_(*children):
+    return _composite('SUM', children)
+
+
+def product(*children):
+    return _composite('PRODUCT', children)
+
+
+def scale(child, factor):
+    return _composite('SCALE', [child], factor=factor)
+
+
+def offset(child, offs):
+    return _composite('OFFSET', [child], offset=offs)
+
+
+def clip(child, min_val=None, max_val=None):
+    bounds = {}
+    if min_val is not None:
+        bounds['min'] = min_val
+    if max_val is not None:
+        bounds['max'] = max_val
+    return _composite('CLIP', [child], **bounds)
+
+
+def time_shift(child, dt):
+    return _composite('TIMESHIFT', [child], dt=dt)
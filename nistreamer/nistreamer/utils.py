@@ -116,5 +116,11 @@ def iplot(chan_list, t_start=None, t_end=None, nsamps=1000, renderer='browser',
             row=idx + 1, col=1
         )
 
+        # Fix the Y-range from the analytic bounds, so that it doesn't depend on which samples were drawn
+        y_min, y_max = chan.output_bounds(t_start=t_start, t_end=t_end)
+        if np.isfinite(y_min) and np.isfinite(y_max):
+            margin = 0.05 * (y_max - y_min) if y_max > y_min else 0.5
+            fig.update_yaxes(range=[y_min - margin, y_max + margin], row=idx + 1, col=1)
+
     fig.show(renderer=renderer)
 # endregion